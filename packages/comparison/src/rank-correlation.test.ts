@@ -183,6 +183,24 @@ describe('hoeffdingD', () => {
     expect(Number.isFinite(d)).toBe(true);
   });
 
+  test('matches reference values', () => {
+    // Same reference values as the wasm-compute hoeffding_tests (defining
+    // U-statistic evaluated by brute force).
+    const x = [1, 2, 3, 4, 5, 6, 7, 8];
+    expect(hoeffdingD(x, x)).toBeCloseTo(1, 12);
+    expect(hoeffdingD(x, [...x].reverse())).toBeCloseTo(1, 12);
+    expect(hoeffdingD(x, [4, 7, 2, 8, 1, 6, 3, 5])).toBeCloseTo(1 / 56, 12);
+    expect(
+      hoeffdingD([0.5, 1.7, 2.2, 3.1, 4.9, 5.3, 6.6, 7.4], [3.3, 1.2, 0.8, 4.4, 2.9, 5.1, 0.1, 6.0])
+    ).toBeCloseTo(0, 12);
+    expect(hoeffdingD([-3, -2, -1, 0, 1, 2, 3], [9, 4, 1, 0, 1.5, 4.5, 9.5])).toBeCloseTo(1 / 21, 12);
+    // Ties use fractional Q counts, pinned to the wasm-compute value.
+    expect(hoeffdingD([1, 2, 2, 3, 4, 5, 5, 6], [2, 1, 3, 3, 5, 4, 6, 6])).toBeCloseTo(
+      0.37332589285714285,
+      12
+    );
+  });
+
   test('returns 0 for small samples', () => {
     // Need at least 5 observations
     const x = [1, 2, 3, 4];
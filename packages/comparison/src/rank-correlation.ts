@@ -194,39 +194,38 @@ export function hoeffdingD(x: number[], y: number[]): number {
   const ranksX = computeRanks(x);
   const ranksY = computeRanks(y);
 
-  // Compute Q, R (bivariate rank)
+  // Q[i] = 1 + points below and to the left of i, with ties counted as
+  // 1/4 (both ranks equal) or 1/2 (one rank equal); same as wasm-compute.
   let D1 = 0;
   let D2 = 0;
   let D3 = 0;
 
   for (let i = 0; i < n; i++) {
-    // Count pairs with both ranks <= current observation's ranks
-    let qi = 0;
+    const ri = ranksX[i];
+    const si = ranksY[i];
+
+    let qi = 1;
     for (let j = 0; j < n; j++) {
-      if (ranksX[j] < ranksX[i] && ranksY[j] < ranksY[i]) {
-        qi++;
+      if (j === i) continue;
+      const rj = ranksX[j];
+      const sj = ranksY[j];
+      if (rj < ri && sj < si) {
+        qi += 1;
+      } else if (rj === ri && sj === si) {
+        qi += 0.25;
+      } else if ((rj === ri && sj < si) || (rj < ri && sj === si)) {
+        qi += 0.5;
       }
     }
 
-    // Adjusted ranks
-    const ri = ranksX[i];
-    const si = ranksY[i];
-
-    D1 += qi * (qi - 1);
+    D1 += (qi - 1) * (qi - 2);
     D2 += (ri - 1) * (ri - 2) * (si - 1) * (si - 2);
-    D3 += (ri - 2) * (si - 2) * qi;
+    D3 += (ri - 2) * (si - 2) * (qi - 1);
   }
 
-  // Hoeffding's D formula (simplified)
-  const n1 = n - 1;
-  const n2 = n - 2;
-  const n3 = n - 3;
-  const n4 = n - 4;
-
-  const A = D1 - (2 * (n - 2) * D3 + D2) / n1;
-  const B = n * n1 * n2 * n3 * n4 / 120;
-
-  return B > 0 ? (A + n * n2 * n3) / B : 0;
+  // D = 30 * ((n-2)(n-3) D1 + D2 - 2(n-2) D3) / (n(n-1)(n-2)(n-3)(n-4))
+  const denom = n * (n - 1) * (n - 2) * (n - 3) * (n - 4);
+  return (30 * ((n - 2) * (n - 3) * D1 + D2 - 2 * (n - 2) * D3)) / denom;
 }
 
 /**
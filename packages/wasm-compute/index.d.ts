@@ -130,6 +130,11 @@ declare module '@phage/wasm-compute' {
     readonly d: number;
     /** Number of observations used. */
     readonly n: number;
+    /** Asymptotic (Blum-Kiefer-Rosenblatt) p-value; NaN for n < 5. */
+    readonly p_value: number;
+    /** Permutation p-value; NaN unless permutations were run. */
+    readonly permutation_p_value: number;
+    readonly permutations: number;
   }
 
   /**
@@ -142,7 +147,7 @@ declare module '@phage/wasm-compute' {
    *
    * @param x - First vector of observations (Float64Array)
    * @param y - Second vector of observations (must have same length as x)
-   * @returns HoeffdingResult containing the D statistic and sample size.
+   * @returns HoeffdingResult containing the D statistic, sample size and asymptotic p-value.
    *
    * D ranges approximately from -0.5 to 1:
    * - D ≈ 0: variables are independent
@@ -153,6 +158,17 @@ declare module '@phage/wasm-compute' {
    */
   export function hoeffdings_d(x: Float64Array, y: Float64Array): HoeffdingResult;
 
+  /**
+   * Hoeffding's D with asymptotic and seeded permutation p-values.
+   * @param permutations - Shuffles of y (0 = asymptotic only; 999 typical), O(n²) each
+   */
+  export function hoeffdings_d_test(
+    x: Float64Array,
+    y: Float64Array,
+    permutations: number,
+    seed: bigint
+  ): HoeffdingResult;
+
   // ============================================================================
   // PCA (Principal Component Analysis) via Power Iteration
   // ============================================================================
@@ -197,6 +213,326 @@ declare module '@phage/wasm-compute' {
     tolerance: number
   ): PCAResult;
 
+  /** Preprocessing applied before PCA centering. */
+  export enum PcaTransform {
+    None = 0,
+    /** Scale each feature to unit sample variance */
+    Standardize = 1,
+    /** Centered log-ratio per sample (compositional data) */
+    Clr = 2,
+  }
+
+  /**
+   * Fitted PCA model (randomized SVD or incremental).
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class PcaModel {
+    free(): void;
+    /** Principal axes, row-major (n_components × n_features) */
+    readonly components: Float32Array;
+    /** Explained variance per component (sample-covariance eigenvalues) */
+    readonly eigenvalues: Float64Array;
+    readonly explained_variance_ratio: Float64Array;
+    readonly singular_values: Float64Array;
+    /** Components scaled by √eigenvalue */
+    readonly loadings: Float32Array;
+    /** Scores of the fitted samples (n_samples × n_components); empty for incremental models */
+    readonly scores: Float32Array;
+    readonly mean: Float32Array;
+    readonly scale: Float32Array;
+    readonly total_variance: number;
+    readonly transform: PcaTransform;
+    readonly n_samples: number;
+    readonly n_features: number;
+    readonly n_components: number;
+    readonly error: string | undefined;
+    /** Project new samples (row-major) to scores (n_samples × n_components). */
+    project(data: Float32Array, n_samples: number): Float32Array;
+  }
+
+  /**
+   * PCA by randomized SVD (Halko et al.).
+   *
+   * @param oversamples - Extra sketch vectors (10 is typical)
+   * @param power_iterations - Subspace iterations (2-4)
+   * @param seed - Seed for the Gaussian test matrix
+   */
+  export function pca_randomized(
+    data: Float32Array,
+    n_samples: number,
+    n_features: number,
+    n_components: number,
+    transform: PcaTransform,
+    oversamples: number,
+    power_iterations: number,
+    seed: bigint
+  ): PcaModel;
+
+  /**
+   * PCA fitted from chunks of samples (Standardize is not supported).
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class IncrementalPca {
+    constructor(n_features: number, n_components: number, transform: PcaTransform);
+    free(): void;
+    /** Fold a chunk (row-major) into the model; false on size mismatch. */
+    partial_fit(chunk: Float32Array, n_samples: number): boolean;
+    readonly n_samples_seen: number;
+    /** Current model (no scores; use `project`). */
+    model(): PcaModel;
+    readonly error: string | undefined;
+  }
+
+  // ============================================================================
+  // Nonlinear Embeddings (t-SNE, UMAP)
+  // ============================================================================
+
+  /**
+   * Barnes–Hut t-SNE layout. Advance with `step` and redraw `coords` between
+   * calls for animated layouts, or let `step_with_callback` deliver frames.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class TsneEmbedding {
+    free(): void;
+    /** Run up to `iterations` more steps; returns progress in [0, 1]. */
+    step(iterations: number): number;
+    /**
+     * Run up to `iterations` more steps, calling `callback(coords, iteration)`
+     * every `every` iterations and after the last one (a throw stops stepping
+     * and is rethrown); returns progress in [0, 1].
+     */
+    step_with_callback(
+      iterations: number,
+      every: number,
+      callback: (coords: Float32Array, iteration: number) => void
+    ): number;
+    /** Coordinates (n × 2, row-major) */
+    readonly coords: Float32Array;
+    readonly progress: number;
+    readonly is_complete: boolean;
+    readonly iteration: number;
+    readonly n: number;
+    readonly error: string | undefined;
+  }
+
+  /**
+   * Start t-SNE on a feature matrix (Euclidean distances).
+   * @param perplexity - Effective neighbor count (5-50)
+   * @param theta - Barnes–Hut accuracy (0.5 typical, 0 = exact)
+   * @param n_iterations - Total iterations (1000 typical)
+   */
+  export function tsne_from_features(
+    data: Float32Array,
+    n_samples: number,
+    n_features: number,
+    perplexity: number,
+    theta: number,
+    n_iterations: number,
+    seed: bigint
+  ): TsneEmbedding;
+
+  /** Start t-SNE on a condensed distance matrix. */
+  export function tsne_from_distances(
+    condensed: Float32Array,
+    perplexity: number,
+    theta: number,
+    n_iterations: number,
+    seed: bigint
+  ): TsneEmbedding;
+
+  /**
+   * UMAP layout. Advance with `step` and redraw `coords` between calls, or
+   * let `step_with_callback` deliver frames.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class UmapEmbedding {
+    free(): void;
+    /** Run up to `epochs` more epochs; returns progress in [0, 1]. */
+    step(epochs: number): number;
+    /**
+     * Run up to `epochs` more epochs, calling `callback(coords, epoch)` every
+     * `every` epochs and after the last one (a throw stops stepping and is
+     * rethrown); returns progress in [0, 1].
+     */
+    step_with_callback(
+      epochs: number,
+      every: number,
+      callback: (coords: Float32Array, epoch: number) => void
+    ): number;
+    /** Coordinates (n × 2, row-major) */
+    readonly coords: Float32Array;
+    readonly progress: number;
+    readonly is_complete: boolean;
+    readonly epoch: number;
+    readonly n_epochs: number;
+    readonly n: number;
+    readonly edge_count: number;
+    readonly error: string | undefined;
+  }
+
+  /**
+   * Start UMAP on a feature matrix (Euclidean distances).
+   * @param n_neighbors - Neighborhood size (15 typical)
+   * @param min_dist - Minimum embedded spacing (0.1 typical)
+   * @param n_epochs - Total epochs (0 = automatic)
+   */
+  export function umap_from_features(
+    data: Float32Array,
+    n_samples: number,
+    n_features: number,
+    n_neighbors: number,
+    min_dist: number,
+    n_epochs: number,
+    seed: bigint
+  ): UmapEmbedding;
+
+  /** Start UMAP on a condensed distance matrix. */
+  export function umap_from_distances(
+    condensed: Float32Array,
+    n_neighbors: number,
+    min_dist: number,
+    n_epochs: number,
+    seed: bigint
+  ): UmapEmbedding;
+
+  // ============================================================================
+  // Flat Clustering (k-means, k-medoids, DBSCAN, HDBSCAN)
+  // ============================================================================
+
+  /**
+   * Labels and summaries of a flat clustering.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class ClusterAssignment {
+    free(): void;
+    /** Cluster label per point (-1 = noise) */
+    readonly labels: Int32Array;
+    /** 1 for noise points, 0 otherwise */
+    readonly outliers: Uint8Array;
+    /** Membership strength in [0, 1] (1 for k-means and DBSCAN members) */
+    readonly probabilities: Float32Array;
+    /** Cluster means (cluster_count × n_features); empty for distance input */
+    readonly centroids: Float32Array;
+    /** Medoid point index per cluster; empty for feature input */
+    readonly medoids: Uint32Array;
+    readonly cluster_sizes: Uint32Array;
+    readonly cluster_count: number;
+    readonly noise_count: number;
+    /** Within-cluster squared distances (k-means), distances to medoids (k-medoids), NaN otherwise */
+    readonly inertia: number;
+    readonly iterations: number;
+    readonly error: string | undefined;
+  }
+
+  /**
+   * k-means with k-means++ seeding on a feature matrix.
+   * @param max_iterations - Lloyd iterations per run (300 typical)
+   * @param n_init - Independent seedings; the lowest-inertia run is kept
+   */
+  export function kmeans(
+    data: Float32Array,
+    n_samples: number,
+    n_features: number,
+    k: number,
+    max_iterations: number,
+    n_init: number,
+    seed: bigint
+  ): ClusterAssignment;
+
+  /** k-medoids on a condensed distance matrix (k-means++ seeding). */
+  export function kmedoids_from_distances(
+    condensed: Float32Array,
+    k: number,
+    max_iterations: number,
+    n_init: number,
+    seed: bigint
+  ): ClusterAssignment;
+
+  /**
+   * Silhouette per sample (NaN for negative/noise labels); empty on size
+   * mismatch. Labels need not be contiguous.
+   */
+  export function silhouette_scores(
+    data: Float32Array,
+    n_samples: number,
+    n_features: number,
+    labels: Int32Array
+  ): Float64Array;
+
+  /** Silhouette per sample from a condensed distance matrix. */
+  export function silhouette_scores_from_distances(
+    condensed: Float32Array,
+    labels: Int32Array
+  ): Float64Array;
+
+  /**
+   * Mean silhouette of the best k-means run for each k in k_min..=k_max
+   * (k >= 2); entry i is for k = max(k_min, 2) + i.
+   */
+  export function kmeans_silhouette_scan(
+    data: Float32Array,
+    n_samples: number,
+    n_features: number,
+    k_min: number,
+    k_max: number,
+    n_init: number,
+    seed: bigint
+  ): Float64Array;
+
+  /** `kmeans_silhouette_scan` with k-medoids on a condensed distance matrix. */
+  export function kmedoids_silhouette_scan(
+    condensed: Float32Array,
+    k_min: number,
+    k_max: number,
+    n_init: number,
+    seed: bigint
+  ): Float64Array;
+
+  /**
+   * DBSCAN on a feature matrix (Euclidean).
+   * @param eps - Neighborhood radius (inclusive)
+   * @param min_samples - Neighbors within eps, the point itself included, for a core point
+   */
+  export function dbscan(
+    data: Float32Array,
+    n_samples: number,
+    n_features: number,
+    eps: number,
+    min_samples: number
+  ): ClusterAssignment;
+
+  /** DBSCAN on a condensed distance matrix. */
+  export function dbscan_from_distances(
+    condensed: Float32Array,
+    eps: number,
+    min_samples: number
+  ): ClusterAssignment;
+
+  /**
+   * HDBSCAN on a feature matrix (Euclidean), excess-of-mass selection.
+   * @param min_cluster_size - Smallest group reported as a cluster (>= 2)
+   * @param min_samples - Core distance neighborhood, self included (0 = min_cluster_size)
+   */
+  export function hdbscan(
+    data: Float32Array,
+    n_samples: number,
+    n_features: number,
+    min_cluster_size: number,
+    min_samples: number
+  ): ClusterAssignment;
+
+  /** HDBSCAN on a condensed distance matrix. */
+  export function hdbscan_from_distances(
+    condensed: Float32Array,
+    min_cluster_size: number,
+    min_samples: number
+  ): ClusterAssignment;
+
   /**
    * Compute Hoeffding's D between two k-mer frequency vectors derived from sequences.
    *
@@ -216,6 +552,98 @@ declare module '@phage/wasm-compute' {
     k: number
   ): HoeffdingResult;
 
+  /** `kmer_hoeffdings_d` with a seeded permutation test. */
+  export function kmer_hoeffdings_d_test(
+    sequence_a: string,
+    sequence_b: string,
+    k: number,
+    permutations: number,
+    seed: bigint
+  ): HoeffdingResult;
+
+  // ============================================================================
+  // Rank Correlation - Spearman, Kendall tau-b, distance correlation, MIC
+  // ============================================================================
+
+  /** A correlation coefficient with its significance. */
+  export class CorrelationResult {
+    free(): void;
+    /** The coefficient (0 when undefined, e.g. constant input) */
+    readonly coefficient: number;
+    /** Two-sided p-value; NaN for distance correlation, MIC, or too few points */
+    readonly p_value: number;
+    readonly n: number;
+  }
+
+  /** Spearman's rho (average ranks for ties), Student's t p-value. */
+  export function spearman_rho(x: Float64Array, y: Float64Array): CorrelationResult;
+
+  /** Kendall's tau-b in O(n log n), tie-corrected normal p-value. */
+  export function kendall_tau_b(x: Float64Array, y: Float64Array): CorrelationResult;
+
+  /** Székely's distance correlation in [0, 1]; 0 only under independence. O(n²). */
+  export function distance_correlation(x: Float64Array, y: Float64Array): CorrelationResult;
+
+  /** Maximal information coefficient (ApproxMaxMI, B = n^0.6, c = 15). */
+  export function maximal_information_coefficient(
+    x: Float64Array,
+    y: Float64Array
+  ): CorrelationResult;
+
+  /** Spearman's rho between the k-mer count vectors of two sequences. */
+  export function kmer_spearman_rho(
+    sequence_a: string,
+    sequence_b: string,
+    k: number
+  ): CorrelationResult;
+
+  /** Kendall's tau-b between the k-mer count vectors of two sequences. */
+  export function kmer_kendall_tau_b(
+    sequence_a: string,
+    sequence_b: string,
+    k: number
+  ): CorrelationResult;
+
+  /** Distance correlation between the k-mer count vectors of two sequences. */
+  export function kmer_distance_correlation(
+    sequence_a: string,
+    sequence_b: string,
+    k: number
+  ): CorrelationResult;
+
+  /** MIC between the k-mer count vectors of two sequences. */
+  export function kmer_maximal_information_coefficient(
+    sequence_a: string,
+    sequence_b: string,
+    k: number
+  ): CorrelationResult;
+
+  // ============================================================================
+  // Multiple Testing - Bonferroni, Holm, BH/BY FDR, Storey q-values
+  // ============================================================================
+
+  export enum PValueAdjustment {
+    Bonferroni = 0,
+    Holm = 1,
+    BenjaminiHochberg = 2,
+    BenjaminiYekutieli = 3,
+  }
+
+  /**
+   * Adjust p-values for multiple testing (matches R's p.adjust). NaN or
+   * out-of-range entries stay NaN and do not count towards m.
+   */
+  export function adjust_p_values(
+    p_values: Float64Array,
+    method: PValueAdjustment
+  ): Float64Array;
+
+  /** Storey's null proportion #{p > lambda} / (m (1 - lambda)), clamped to [1/m, 1]; NaN lambda = 0.5. */
+  export function storey_pi0(p_values: Float64Array, lambda: number): number;
+
+  /** Storey q-values: BH-adjusted p-values scaled by the estimated pi0. */
+  export function storey_q_values(p_values: Float64Array, lambda: number): Float64Array;
+
   // ============================================================================
   // Entropy Functions - Information-theoretic sequence analysis
   // ============================================================================
@@ -259,6 +687,66 @@ declare module '@phage/wasm-compute' {
     counts_b: Float64Array
   ): number;
 
+  // ============================================================================
+  // Positional Information - Column MI, I(d), block entropies
+  // ============================================================================
+
+  /** Mutual information between all pairs of alignment columns (bits). */
+  export class ColumnCoupling {
+    free(): void;
+    readonly columns: number;
+    readonly num_sequences: number;
+    /** Raw MI, condensed (SciPy order); NaN for pairs with an excluded column */
+    readonly mi: Float32Array;
+    /** APC-corrected MI: MI(i,j) - MI(i,·)·MI(j,·)/MI(·,·), condensed */
+    readonly apc: Float32Array;
+    /** Best `count` pairs by APC as [i0, j0, i1, j1, ...] (i < j) */
+    top_pairs(count: number): Uint32Array;
+    readonly error: string | undefined;
+  }
+
+  /**
+   * MI between every pair of alignment columns with the average product
+   * correction for coevolution analysis. Gaps and N/X are skipped per pair.
+   *
+   * @param aligned_fasta - Aligned FASTA (`-` or `.` gaps), equal row lengths, at most 3000 columns
+   * @param protein - true for amino acids, false for nucleotides
+   * @param max_gap_fraction - Columns with more gaps/unknowns are excluded
+   * @returns ColumnCoupling (caller must call `.free()`)
+   */
+  export function alignment_mutual_information(
+    aligned_fasta: string,
+    protein: boolean,
+    max_gap_fraction: number
+  ): ColumnCoupling;
+
+  /**
+   * Mutual information function I(d) between bases d apart (bits).
+   *
+   * @returns Float64Array of length max_distance; entry d - 1 holds I(d)
+   */
+  export function mutual_information_function(
+    seq: Uint8Array,
+    max_distance: number
+  ): Float64Array;
+
+  /** Block entropies and conditional entropies for block lengths 1..k. */
+  export class BlockEntropies {
+    free(): void;
+    /** H_k of overlapping k-mers for k = 1..max_k */
+    readonly block_entropies: Float64Array;
+    /** h_k = H_k - H_{k-1} (h_1 = H_1) */
+    readonly conditional_entropies: Float64Array;
+    /** Largest k with at least 10 windows per possible k-mer */
+    readonly reliable_k: number;
+    /** h_k at reliable_k, bits per base */
+    readonly entropy_rate: number;
+    readonly error: string | undefined;
+  }
+
+  /** Block and conditional entropies of a nucleotide sequence, max_k in 1..16. */
+  export function block_entropies(seq: Uint8Array, max_k: number): BlockEntropies;
+
   // ============================================================================
   // Repeat Detection - Palindromes and Tandem Repeats
   // ============================================================================
@@ -362,6 +850,72 @@ declare module '@phage/wasm-compute' {
     k: number
   ): Float64Array;
 
+  // ============================================================================
+  // Changepoint Segmentation - PELT, binary segmentation, composition HMM
+  // ============================================================================
+
+  /**
+   * Segments with per-segment statistics. Coordinates are track indices for
+   * segment_track_* (window i starts at base i * step_size) and bases for
+   * segment_composition_hmm; ends are exclusive.
+   */
+  export class Segmentation {
+    free(): void;
+    readonly starts: Uint32Array;
+    readonly ends: Uint32Array;
+    /** Starts of all segments but the first */
+    readonly changepoints: Uint32Array;
+    /** Segment mean (GC fraction for HMM segments) */
+    readonly means: Float64Array;
+    readonly std_devs: Float64Array;
+    /** HMM state per segment, 0 = most AT-rich; empty for tracks */
+    readonly states: Uint32Array;
+    /** A/C/G/T fractions per HMM segment (segments x 4); empty for tracks */
+    readonly composition: Float64Array;
+    readonly segment_count: number;
+    /** Log likelihood of the trained HMM (NaN for tracks) */
+    readonly log_likelihood: number;
+    readonly error: string | undefined;
+  }
+
+  /**
+   * Optimal change-in-mean segmentation of a track (GC skew, entropy,
+   * complexity, KL values) by PELT. Use non-overlapping windows
+   * (step_size >= window_size) or a larger penalty for overlapping ones.
+   *
+   * @param values - Track values (no NaN)
+   * @param penalty - Cost per changepoint; 0 for the default 2 ln(n)
+   * @param min_size - Minimum segment length in track values
+   */
+  export function segment_track_pelt(
+    values: Float64Array,
+    penalty: number,
+    min_size: number
+  ): Segmentation;
+
+  /** Greedy binary segmentation; max_changepoints = 0 means no limit. */
+  export function segment_track_binary(
+    values: Float64Array,
+    penalty: number,
+    min_size: number,
+    max_changepoints: number
+  ): Segmentation;
+
+  /**
+   * Segment raw bases into composition classes with a Baum-Welch trained
+   * HMM and Viterbi decoding (genomic islands without choosing a window).
+   *
+   * @param num_states - Composition classes (2..8)
+   * @param switch_probability - Per-base state change probability, e.g. 1e-5
+   * @param max_iterations - Baum-Welch iteration cap
+   */
+  export function segment_composition_hmm(
+    seq: Uint8Array,
+    num_states: number,
+    switch_probability: number,
+    max_iterations: number
+  ): Segmentation;
+
   // ============================================================================
   // Grid Building - HOT PATH for viewport rendering
   // ============================================================================
@@ -442,118 +996,726 @@ declare module '@phage/wasm-compute' {
     readonly k: number;
     /** Number of unique k-mers (non-zero counts) */
     readonly unique_count: number;
+    /** Abundance spectrum (max_count bins, 0 = 1000) */
+    spectrum(max_count: number): KmerSpectrum;
+  }
+
+  /**
+   * Dense k-mer counting with typed array output.
+   *
+   * Uses a rolling 2-bit index algorithm with no per-position heap allocations.
+   * Ambiguous bases (N and non-ACGT) reset the rolling state.
+   *
+   * @param seq - Sequence as bytes (ASCII). Accepts both upper and lower case.
+   * @param k - K-mer size. Must be 1 <= k <= 10 (4^10 = ~4MB max array).
+   * @returns DenseKmerResult (caller must call `.free()`)
+   *
+   * Returns empty result with all-zero counts if k is invalid.
+   *
+   * @see phage_explorer-vk7b.1.1
+   * @see docs/wasm-abi.md
+   */
+  export function count_kmers_dense(seq: Uint8Array, k: number): DenseKmerResult;
+
+  /**
+   * Dense k-mer counting with reverse complement combined.
+   *
+   * Counts both forward and reverse complement k-mers into the same array.
+   * Uses canonical k-mers (min of forward and RC) for strand-independent analysis.
+   *
+   * @param seq - Sequence as bytes (ASCII)
+   * @param k - K-mer size (1 <= k <= 10)
+   * @returns DenseKmerResult with combined forward + RC counts
+   */
+  export function count_kmers_dense_canonical(seq: Uint8Array, k: number): DenseKmerResult;
+
+  /**
+   * Check if a k value is valid for dense k-mer counting.
+   * Returns true if 1 <= k <= 10.
+   */
+  export function is_valid_dense_kmer_k(k: number): boolean;
+
+  /**
+   * Get the maximum allowed k for dense k-mer counting (currently 10).
+   */
+  export function get_dense_kmer_max_k(): number;
+
+  // ============================================================================
+  // Sparse K-mer Counter (k up to 31, open-addressing table)
+  // ============================================================================
+
+  /**
+   * Sparse k-mer counts; memory scales with distinct k-mers, not 4^k.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class SparseKmerCounts {
+    free(): void;
+    /** Count more sequence (e.g. one read at a time) */
+    add_sequence(seq: Uint8Array): void;
+    readonly k: number;
+    readonly canonical: boolean;
+    /** Total valid k-mer windows counted */
+    readonly total_valid: bigint;
+    readonly distinct_count: number;
+    /** Count of a k-mer (reverse complement folded in canonical mode) */
+    get(kmer: string): number;
+    /** Number of k-mers with count >= min_count */
+    solid_count(min_count: number): number;
+    /**
+     * Abundance histogram of length max_count + 1: entry c = distinct k-mers
+     * seen exactly c times; the last entry aggregates counts >= max_count.
+     */
+    histogram_counts(max_count: number): Uint32Array;
+    top_kmers(n: number): TopKmers;
+    /** Abundance spectrum (max_count bins, 0 = 1000) */
+    spectrum(max_count: number): KmerSpectrum;
+    readonly error: string | undefined;
+  }
+
+  /**
+   * K-mer abundance spectrum with genome-size and repeat estimates.
+   *
+   * For raw reads (counted with add_sequence) the coverage peak gives a
+   * GenomeScope-like haploid genome size; for an assembled sequence the
+   * peak is 1 and genome_size is the number of k-mers.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class KmerSpectrum {
+    free(): void;
+    readonly k: number;
+    /** Entry c = distinct k-mers seen c times; last entry aggregates the tail */
+    readonly histogram: Uint32Array;
+    readonly total_kmers: bigint;
+    readonly distinct_kmers: bigint;
+    /** Distinct k-mers / total occurrences */
+    readonly distinct_fraction: number;
+    /** Singleton k-mers / distinct k-mers */
+    readonly unique_fraction: number;
+    /** Count at the end of the error peak (0 if none) */
+    readonly valley: number;
+    /** K-mer coverage peak */
+    readonly peak_coverage: number;
+    /** Estimated genome size in bases */
+    readonly genome_size: number;
+    /** Fraction of occurrences at or below the valley */
+    readonly error_fraction: number;
+    /** Estimated fraction of the genome in multi-copy k-mers */
+    readonly repeat_fraction: number;
+  }
+
+  /**
+   * Most abundant k-mers, count descending.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class TopKmers {
+    free(): void;
+    readonly kmers_json: string;
+    /** 2-bit packed codes (A=0, C=1, G=2, T=3) */
+    readonly codes: BigUint64Array;
+    readonly counts: Uint32Array;
+    readonly count: number;
+  }
+
+  /**
+   * Sparse k-mer counting.
+   * @param seq - Sequence as bytes (ASCII)
+   * @param k - K-mer size (1 <= k <= 31)
+   * @param canonical - Merge each k-mer with its reverse complement
+   */
+  export function count_kmers_sparse(
+    seq: Uint8Array,
+    k: number,
+    canonical: boolean
+  ): SparseKmerCounts;
+
+  // ============================================================================
+  // MinHash Signature (rolling index, typed array output)
+  // @see phage_explorer-vk7b.2.1
+  // ============================================================================
+
+  /**
+   * Result of MinHash signature computation.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   *
+   * @example
+   * ```ts
+   * const sig = wasm.minhash_signature(seqBytes, 16, 128);
+   * try {
+   *   const values = sig.signature; // Uint32Array[128]
+   *   const totalKmers = Number(sig.total_kmers);
+   * } finally {
+   *   sig.free(); // Required!
+   * }
+   * ```
+   */
+  export class MinHashSignature {
+    free(): void;
+    /** Signature values as Uint32Array (minimum hash for each seed) */
+    readonly signature: Uint32Array;
+    /** Total valid k-mers hashed (bigint from Rust u64) */
+    readonly total_kmers: bigint;
+    /** K value used */
+    readonly k: number;
+    /** Number of hash functions (signature length) */
+    readonly num_hashes: number;
+    /** Whether k-mers were hashed strand-independently */
+    readonly canonical: boolean;
+    /** Hash family seed (0 for the public signature functions; identifies the patterns for spaced seeds) */
+    readonly hash_seed: number;
+    /** Estimated Jaccard (0 if k, hash count, seed or strand mode differ) */
+    jaccard(other: MinHashSignature): number;
+  }
+
+  /**
+   * Compute MinHash signature using rolling k-mer index.
+   *
+   * Uses a rolling 2-bit index algorithm with no per-k-mer string allocations.
+   * Much faster than string-based approach for long sequences.
+   *
+   * @param seq - Sequence as bytes (ASCII). Case-insensitive, U treated as T.
+   * @param k - K-mer size (capped at 32 for u64 index)
+   * @param num_hashes - Number of hash functions (signature length, e.g., 128)
+   * @returns MinHashSignature (caller must call `.free()`)
+   *
+   * @see phage_explorer-vk7b.2.1
+   */
+  export function minhash_signature(seq: Uint8Array, k: number, num_hashes: number): MinHashSignature;
+
+  /**
+   * Compute MinHash signature using canonical k-mers (strand-independent).
+   *
+   * For each k-mer position, uses the minimum of forward and reverse complement
+   * indices before hashing. This makes the signature identical regardless of
+   * which strand the sequence represents.
+   *
+   * @param seq - Sequence as bytes (ASCII)
+   * @param k - K-mer size (capped at 32)
+   * @param num_hashes - Number of hash functions
+   * @returns MinHashSignature with strand-independent hashes
+   */
+  export function minhash_signature_canonical(seq: Uint8Array, k: number, num_hashes: number): MinHashSignature;
+
+  /**
+   * Estimate Jaccard similarity between two MinHash signatures.
+   *
+   * @param sig_a - First signature (Uint32Array)
+   * @param sig_b - Second signature (must have same length)
+   * @returns Estimated Jaccard similarity (0.0 to 1.0)
+   */
+  export function minhash_jaccard_from_signatures(sig_a: Uint32Array, sig_b: Uint32Array): number;
+
+  // ============================================================================
+  // Spaced-Seed K-mers
+  // ============================================================================
+
+  /**
+   * Sparse counts of spaced-seed words (bases at the `1` positions of a
+   * pattern such as "1101101"). Several equal-weight patterns may be given,
+   * separated by commas or spaces; their words are kept apart.
+   *
+   * @param seq - Sequence as bytes (ASCII)
+   * @param patterns - Seed pattern(s), each starting and ending with 1 (weight <= 28)
+   * @param canonical - Strand-independent words
+   * @returns SparseKmerCounts with `k` = seed weight (`error` set for invalid patterns)
+   */
+  export function count_spaced_kmers(
+    seq: Uint8Array,
+    patterns: string,
+    canonical: boolean
+  ): SparseKmerCounts;
+
+  /**
+   * `analyze_kmers` on spaced-seed words; `k` is the seed weight.
+   * @returns undefined if the patterns are invalid
+   */
+  export function analyze_spaced_kmers(
+    sequence_a: string,
+    sequence_b: string,
+    patterns: string
+  ): KmerAnalysisResult | undefined;
+
+  /**
+   * MinHash signature over spaced-seed words; compare with
+   * `MinHashSignature.jaccard` (same patterns on both sides). `hash_seed`
+   * identifies the patterns (0 only for a single contiguous seed), so
+   * signatures of other patterns or plain k-mers are incompatible.
+   * @returns undefined if the patterns are invalid
+   */
+  export function minhash_signature_spaced(
+    seq: Uint8Array,
+    patterns: string,
+    num_hashes: number,
+    canonical: boolean
+  ): MinHashSignature | undefined;
+
+  // ============================================================================
+  // Serializable MinHash Sketches and Sketch Database
+  // ============================================================================
+
+  /**
+   * Named MinHash sketch with its hashing parameters.
+   * Binary format: "PXMH" v1 (see src/sketch.rs).
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class MinHashSketch {
+    free(): void;
+    /** Wrap an existing signature (hash seed 0) */
+    static from_signature(
+      signature: MinHashSignature,
+      name: string,
+      genome_length: bigint
+    ): MinHashSketch;
+    /** Decode bytes from `to_bytes`; undefined if malformed */
+    static from_bytes(bytes: Uint8Array): MinHashSketch | undefined;
+    /** Encode in the versioned binary format */
+    to_bytes(): Uint8Array;
+    /** Same k, hash count, seed and strand mode */
+    is_compatible(other: MinHashSketch): boolean;
+    /** Estimated Jaccard (0 if incompatible) */
+    jaccard(other: MinHashSketch): number;
+    readonly name: string;
+    readonly k: number;
+    readonly num_hashes: number;
+    readonly canonical: boolean;
+    readonly hash_seed: number;
+    /** Sequence length in bases */
+    readonly genome_length: bigint;
+    /** Valid k-mers hashed */
+    readonly total_kmers: bigint;
+    readonly signature: Uint32Array;
+  }
+
+  /**
+   * Compute a named MinHash sketch.
+   *
+   * @param seq - Sequence bytes (ASCII)
+   * @param k - K-mer size (capped at 32)
+   * @param num_hashes - Signature length
+   * @param canonical - Hash canonical (strand-independent) k-mers
+   * @param hash_seed - Hash family seed (0 matches minhash_signature)
+   * @param name - Genome name stored with the sketch
+   */
+  export function minhash_sketch(
+    seq: Uint8Array,
+    k: number,
+    num_hashes: number,
+    canonical: boolean,
+    hash_seed: number,
+    name: string
+  ): MinHashSketch;
+
+  /**
+   * Ranked hits from a sketch database query.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class SketchQueryResult {
+    free(): void;
+    /** Database indices, best first */
+    readonly indices: Uint32Array;
+    /** Estimated Jaccard per hit */
+    readonly jaccard: Float64Array;
+    /** Mash distance per hit */
+    readonly mash_distances: Float64Array;
+    /** Hit names as a JSON array */
+    readonly names_json: string;
+    readonly count: number;
+  }
+
+  /**
+   * Container of compatible sketches. Binary format: "PXSD" v1.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class SketchDatabase {
+    free(): void;
+    constructor();
+    /** Add a copy; false if incompatible with stored sketches */
+    add(sketch: MinHashSketch): boolean;
+    readonly len: number;
+    is_empty(): boolean;
+    /** Copy of the sketch at index, undefined if out of range */
+    get(index: number): MinHashSketch | undefined;
+    to_bytes(): Uint8Array;
+    /** Decode bytes from `to_bytes`; undefined if malformed */
+    static from_bytes(bytes: Uint8Array): SketchDatabase | undefined;
+    /** Top-N most similar sketches by Jaccard */
+    query(query: MinHashSketch, top_n: number): SketchQueryResult;
+  }
+
+  // ============================================================================
+  // HyperLogLog - distinct k-mer cardinality sketches
+  // ============================================================================
+
+  /**
+   * HyperLogLog sketch of k-mer hashes; mergeable across genomes.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class HyperLogLog {
+    free(): void;
+    /**
+     * @param precision - log2 of the register count (4..18)
+     * @param k - K-mer size (1..32)
+     * @param canonical - Hash min(forward, reverse complement)
+     * @param seed - Hash seed (only equal seeds merge)
+     */
+    constructor(precision: number, k: number, canonical: boolean, seed: bigint);
+    add_sequence(seq: Uint8Array): void;
+    /** Estimated number of distinct k-mers */
+    estimate(): number;
+    /** Union in place; false if parameters differ */
+    merge(other: HyperLogLog): boolean;
+    /** NaN if parameters differ */
+    union_estimate(other: HyperLogLog): number;
+    /** |A| + |B| - |A ∪ B|, clamped at 0 */
+    intersection_estimate(other: HyperLogLog): number;
+    jaccard_estimate(other: HyperLogLog): number;
+    /** 1.04 / sqrt(2^precision) */
+    readonly relative_error: number;
+    readonly precision: number;
+    readonly k: number;
+    readonly canonical: boolean;
+    readonly seed: bigint;
+    readonly registers: Uint8Array;
+    /** Serialize to the PXHL v1 format */
+    to_bytes(): Uint8Array;
+    static from_bytes(bytes: Uint8Array): HyperLogLog | undefined;
+  }
+
+  // ============================================================================
+  // HNSW - approximate nearest-neighbor search over feature vectors
+  // ============================================================================
+
+  /** Distance for HnswIndex. */
+  export enum VectorMetric {
+    /** 1 - cosine similarity (vectors are normalized on insert) */
+    Cosine = 0,
+    /** Euclidean (L2) distance */
+    Euclidean = 1,
+  }
+
+  /**
+   * HNSW graph index over fixed-dimension float vectors (k-mer profiles,
+   * PCA scores, protein embeddings). Ids are insertion order.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class HnswIndex {
+    free(): void;
+    /**
+     * @param dim - Vector dimension
+     * @param m - Links per node and layer (16 typical; layer 0 keeps 2m)
+     * @param ef_construction - Beam width while inserting (200 typical)
+     * @param seed - Seed for node levels
+     */
+    constructor(dim: number, metric: VectorMetric, m: number, ef_construction: number, seed: bigint);
+    /** Returns the new id, or undefined if the length does not match dim */
+    insert(vector: Float32Array): number | undefined;
+    /** Row-major batch; returns the number inserted (0 on length mismatch) */
+    insert_batch(data: Float32Array): number;
+    /** Top-k approximate neighbors, nearest first; ef = beam width (>= k) */
+    search(query: Float32Array, k: number, ef: number): HnswSearchResult;
+    readonly len: number;
+    is_empty(): boolean;
+    readonly dim: number;
+    readonly metric: VectorMetric;
+    readonly max_level: number;
+    readonly error: string | undefined;
+    /** Serialize to the PXHN v1 format */
+    to_bytes(): Uint8Array;
+    static from_bytes(bytes: Uint8Array): HnswIndex | undefined;
+  }
+
+  /**
+   * Hits of an HNSW query, nearest first.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class HnswSearchResult {
+    free(): void;
+    readonly ids: Uint32Array;
+    /** 1 - cosine similarity, or Euclidean distance */
+    readonly distances: Float32Array;
+    readonly count: number;
+  }
+
+  // ============================================================================
+  // Genome Collections - all-vs-all condensed distance matrices
+  // ============================================================================
+
+  /** Distance measure for GenomeCollection.distance_matrix. */
+  export enum DistanceMetric {
+    /** Mash distance from MinHash signatures */
+    Mash = 0,
+    /** 1 - Jaccard from MinHash signatures */
+    Jaccard = 1,
+    /** Jensen-Shannon divergence between k-mer profiles */
+    Jsd = 2,
+    /** 1 - cosine similarity between k-mer profiles */
+    Cosine = 3,
+  }
+
+  /**
+   * Condensed distance matrix (SciPy pdist layout: upper triangle, row-major,
+   * n*(n-1)/2 values). May be computed incrementally with `step()`.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class CondensedDistanceMatrix {
+    free(): void;
+    /** Compute up to max_pairs more pairs; returns fraction completed */
+    step(collection: GenomeCollection, max_pairs: number): number;
+    readonly progress: number;
+    readonly is_complete: boolean;
+    readonly n: number;
+    readonly metric: DistanceMetric;
+    /** Condensed distances */
+    readonly values: Float32Array;
+    /** Distance between i and j (0 on diagonal, NaN if out of range) */
+    get(i: number, j: number): number;
+    readonly error: string | undefined;
+  }
+
+  /**
+   * Genomes with MinHash signatures and/or dense k-mer profiles.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class GenomeCollection {
+    free(): void;
+    /** @param k - K-mer size of the signatures (for Mash distance) */
+    constructor(k: number);
+    /**
+     * Add a genome with a MinHash signature; pass an empty profile if none.
+     * @returns Index, or -1 if rejected (signature k, hash count, strand mode
+     *   or seed differs from the collection, profile length mismatch, or limits)
+     */
+    add(name: string, signature: MinHashSignature, profile: Float64Array): number;
+    /**
+     * Add a genome that only has a k-mer profile.
+     * @returns Index, or -1 if rejected (empty or length mismatch, or limits)
+     */
+    add_profile(name: string, profile: Float64Array): number;
+    readonly len: number;
+    is_empty(): boolean;
+    name(index: number): string;
+    readonly k: number;
+    /** Start an incremental computation (drive with `step()`) */
+    start_distance_matrix(metric: DistanceMetric): CondensedDistanceMatrix;
+    /** Compute the full matrix in one call */
+    distance_matrix(metric: DistanceMetric): CondensedDistanceMatrix;
+  }
+
+  // ============================================================================
+  // Phylogenetic Trees - UPGMA/WPGMA and neighbor joining
+  // ============================================================================
+
+  /** Tree construction method for build_tree. */
+  export enum TreeMethod {
+    Upgma = 0,
+    Wpgma = 1,
+    NeighborJoining = 2,
+    /** Neighbor joining with RapidNJ-style bounded search */
+    RapidNj = 3,
+  }
+
+  /**
+   * Phylogram coordinates, indexed by node id.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class TreeLayout {
+    free(): void;
+    /** Distance from the root */
+    readonly x: Float32Array;
+    /** Leaves 0..n-1 in order; internal nodes midway between first and last child */
+    readonly y: Float32Array;
+    /** Parent node id (-1 for the root) */
+    readonly parents: Int32Array;
+    readonly branch_lengths: Float32Array;
+    /** 1 for leaves, 0 for internal nodes */
+    readonly is_leaf: Uint8Array;
   }
 
   /**
-   * Dense k-mer counting with typed array output.
-   *
-   * Uses a rolling 2-bit index algorithm with no per-position heap allocations.
-   * Ambiguous bases (N and non-ACGT) reset the rolling state.
-   *
-   * @param seq - Sequence as bytes (ASCII). Accepts both upper and lower case.
-   * @param k - K-mer size. Must be 1 <= k <= 10 (4^10 = ~4MB max array).
-   * @returns DenseKmerResult (caller must call `.free()`)
+   * Rooted phylogenetic tree.
    *
-   * Returns empty result with all-zero counts if k is invalid.
-   *
-   * @see phage_explorer-vk7b.1.1
-   * @see docs/wasm-abi.md
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
    */
-  export function count_kmers_dense(seq: Uint8Array, k: number): DenseKmerResult;
+  export class PhyloTree {
+    free(): void;
+    /** Newick with branch lengths */
+    readonly newick: string;
+    readonly node_count: number;
+    readonly leaf_count: number;
+    node_name(index: number): string;
+    /** Reroot at the midpoint of the longest leaf-to-leaf path */
+    midpoint_root(): void;
+    layout(): TreeLayout;
+    readonly error: string | undefined;
+  }
 
   /**
-   * Dense k-mer counting with reverse complement combined.
-   *
-   * Counts both forward and reverse complement k-mers into the same array.
-   * Uses canonical k-mers (min of forward and RC) for strand-independent analysis.
+   * Build a tree from a condensed distance matrix.
+   * @param condensed - Upper-triangle distances (e.g. CondensedDistanceMatrix.values)
+   * @param names - Taxon names (missing names become t0, t1, ...)
+   * @param method - Construction method
+   */
+  export function build_tree(
+    condensed: Float32Array,
+    names: string[],
+    method: TreeMethod
+  ): PhyloTree;
+
+  // ============================================================================
+  // Hierarchical Clustering and Intergenomic Similarity (VIRIDIC-style)
+  // ============================================================================
+
+  /** Linkage criterion for hierarchical_cluster. */
+  export enum Linkage {
+    Single = 0,
+    Complete = 1,
+    /** UPGMA */
+    Average = 2,
+  }
+
+  /**
+   * Hierarchical clustering result (SciPy linkage conventions).
    *
-   * @param seq - Sequence as bytes (ASCII)
-   * @param k - K-mer size (1 <= k <= 10)
-   * @returns DenseKmerResult with combined forward + RC counts
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
    */
-  export function count_kmers_dense_canonical(seq: Uint8Array, k: number): DenseKmerResult;
+  export class Dendrogram {
+    free(): void;
+    readonly n: number;
+    /** (n-1) x 4 row-major: [cluster_a, cluster_b, height, size] */
+    readonly linkage_matrix: Float64Array;
+    readonly heights: Float64Array;
+    /** Flat labels: items joined by merges at height <= threshold */
+    cut(threshold: number): Int32Array;
+    cluster_count(threshold: number): number;
+    readonly error: string | undefined;
+  }
 
   /**
-   * Check if a k value is valid for dense k-mer counting.
-   * Returns true if 1 <= k <= 10.
+   * Cluster a condensed distance matrix.
+   * @param condensed - Upper-triangle distances (n*(n-1)/2 values)
    */
-  export function is_valid_dense_kmer_k(k: number): boolean;
+  export function hierarchical_cluster(condensed: Float32Array, linkage: Linkage): Dendrogram;
 
   /**
-   * Get the maximum allowed k for dense k-mer counting (currently 10).
+   * All-vs-all intergenomic similarity with ICTV clusters
+   * (species >= 95%, genus >= 70%).
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
    */
-  export function get_dense_kmer_max_k(): number;
+  export class IntergenomicResult {
+    free(): void;
+    readonly n: number;
+    readonly names_json: string;
+    readonly lengths: Uint32Array;
+    /** Condensed similarity matrix (percent) */
+    readonly similarity: Float32Array;
+    /** Condensed distance matrix (100 - similarity) */
+    readonly distance: Float32Array;
+    /** Full n x n: fraction of genome i covered by hits against genome j */
+    readonly aligned_fraction: Float32Array;
+    readonly species: Int32Array;
+    readonly genus: Int32Array;
+    readonly species_count: number;
+    readonly genus_count: number;
+    /** Clusters at a custom similarity threshold (percent) */
+    clusters_at(similarity_threshold: number): Int32Array;
+    readonly error: string | undefined;
+  }
+
+  /**
+   * VIRIDIC-style similarity: (idAB + idBA) * 100 / (lenA + lenB), with
+   * identities from chained exact-match anchors on both strands.
+   * @param fasta - Multi-FASTA of complete genomes
+   * @param linkage - Linkage used to cluster on 100 - similarity
+   */
+  export function intergenomic_similarity_matrix(
+    fasta: string,
+    linkage: Linkage
+  ): IntergenomicResult;
 
   // ============================================================================
-  // MinHash Signature (rolling index, typed array output)
-  // @see phage_explorer-vk7b.2.1
+  // FracMinHash (scaled) Sketches - containment and ANI
   // ============================================================================
 
   /**
-   * Result of MinHash signature computation.
+   * FracMinHash sketch: all canonical k-mer hashes below 2^64 / scaled.
    *
    * IMPORTANT: Must call `.free()` when done to release WASM memory.
-   *
-   * @example
-   * ```ts
-   * const sig = wasm.minhash_signature(seqBytes, 16, 128);
-   * try {
-   *   const values = sig.signature; // Uint32Array[128]
-   *   const totalKmers = Number(sig.total_kmers);
-   * } finally {
-   *   sig.free(); // Required!
-   * }
-   * ```
    */
-  export class MinHashSignature {
+  export class FracMinHashSketch {
     free(): void;
-    /** Signature values as Uint32Array (minimum hash for each seed) */
-    readonly signature: Uint32Array;
-    /** Total valid k-mers hashed (bigint from Rust u64) */
-    readonly total_kmers: bigint;
-    /** K value used */
+    /** Retained hashes, sorted ascending */
+    readonly hashes: BigUint64Array;
+    readonly size: number;
     readonly k: number;
-    /** Number of hash functions (signature length) */
-    readonly num_hashes: number;
+    readonly scaled: bigint;
+    readonly seed: bigint;
+    readonly total_kmers: bigint;
+    /** size × scaled */
+    readonly estimated_distinct_kmers: number;
   }
 
   /**
-   * Compute MinHash signature using rolling k-mer index.
-   *
-   * Uses a rolling 2-bit index algorithm with no per-k-mer string allocations.
-   * Much faster than string-based approach for long sequences.
-   *
-   * @param seq - Sequence as bytes (ASCII). Case-insensitive, U treated as T.
-   * @param k - K-mer size (capped at 32 for u64 index)
-   * @param num_hashes - Number of hash functions (signature length, e.g., 128)
-   * @returns MinHashSignature (caller must call `.free()`)
+   * Compute a FracMinHash sketch from sequence bytes.
    *
-   * @see phage_explorer-vk7b.2.1
+   * @param seq - Sequence bytes (ASCII)
+   * @param k - K-mer size (capped at 32)
+   * @param scaled - Keep hashes below 2^64 / scaled (e.g. 1000n)
+   * @param seed - Hash seed
    */
-  export function minhash_signature(seq: Uint8Array, k: number, num_hashes: number): MinHashSignature;
+  export function frac_minhash_sketch(seq: Uint8Array, k: number, scaled: bigint, seed: bigint): FracMinHashSketch;
 
   /**
-   * Compute MinHash signature using canonical k-mers (strand-independent).
-   *
-   * For each k-mer position, uses the minimum of forward and reverse complement
-   * indices before hashing. This makes the signature identical regardless of
-   * which strand the sequence represents.
+   * Comparison of two FracMinHash sketches. ANI bounds come from a Wilson
+   * interval on the sampled containment / Jaccard.
    *
-   * @param seq - Sequence as bytes (ASCII)
-   * @param k - K-mer size (capped at 32)
-   * @param num_hashes - Number of hash functions
-   * @returns MinHashSignature with strand-independent hashes
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
    */
-  export function minhash_signature_canonical(seq: Uint8Array, k: number, num_hashes: number): MinHashSignature;
+  export class FracMinHashComparison {
+    free(): void;
+    readonly shared: number;
+    readonly jaccard: number;
+    /** |A ∩ B| / |A| */
+    readonly containment_a: number;
+    /** |A ∩ B| / |B| */
+    readonly containment_b: number;
+    /** shared / min(|A|, |B|) */
+    readonly max_containment: number;
+    /** ANI from max containment (C^(1/k)) */
+    readonly ani: number;
+    readonly ani_low: number;
+    readonly ani_high: number;
+    /** Mash-style ANI from Jaccard */
+    readonly ani_jaccard: number;
+    readonly ani_jaccard_low: number;
+    readonly ani_jaccard_high: number;
+    readonly confidence: number;
+    /** Set when k or seed differ */
+    readonly error: string | undefined;
+  }
 
   /**
-   * Estimate Jaccard similarity between two MinHash signatures.
+   * Compare two FracMinHash sketches (at the coarser scaled if they differ).
    *
-   * @param sig_a - First signature (Uint32Array)
-   * @param sig_b - Second signature (must have same length)
-   * @returns Estimated Jaccard similarity (0.0 to 1.0)
+   * @param confidence - Confidence level for ANI bounds (default 0.95 if out of range)
    */
-  export function minhash_jaccard_from_signatures(sig_a: Uint32Array, sig_b: Uint32Array): number;
+  export function frac_minhash_compare(
+    a: FracMinHashSketch,
+    b: FracMinHashSketch,
+    confidence: number
+  ): FracMinHashComparison;
 
   // ============================================================================
   // Spatial-Hash Bond Detection
@@ -632,7 +1794,7 @@ declare module '@phage/wasm-compute' {
   ): FunctionalGroupResult;
 
   // ============================================================================
-  // Dot Plot (Self-Similarity Matrix)
+  // Dot Plot (Self-Similarity and Pairwise Matrices)
   // ============================================================================
 
   /**
@@ -662,6 +1824,47 @@ declare module '@phage/wasm-compute' {
    */
   export function dotplot_self_buffers(seq: Uint8Array, bins: number, window: number): DotPlotBuffers;
 
+  /**
+   * Result of an A-vs-B dot plot (independent bins per axis).
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class DotPlotPairBuffers {
+    free(): void;
+    /** Forward-strand values (row-major, bins_a × bins_b, index i*bins_b + j) */
+    readonly direct: Float32Array;
+    /** Reverse-complement values (row-major, bins_a × bins_b) */
+    readonly inverted: Float32Array;
+    /** Bins along sequence A (rows) */
+    readonly bins_a: number;
+    /** Bins along sequence B (columns) */
+    readonly bins_b: number;
+    /** Identity window size (0 in word-match mode) */
+    readonly window: number;
+    /** Seed k-mer size (0 in identity mode) */
+    readonly word_size: number;
+  }
+
+  /**
+   * Compute a dot plot of sequence A (rows) against sequence B (columns).
+   *
+   * @param seq_a - Sequence A bytes (ASCII)
+   * @param seq_b - Sequence B bytes (ASCII)
+   * @param bins_a - Bins along A
+   * @param bins_b - Bins along B
+   * @param window - Identity window in bases. If 0, derives a default.
+   * @param word_size - Seed k-mer size (1-32) for word-match scoring, or 0 for windowed identity
+   * @returns DotPlotPairBuffers with forward and reverse-complement layers
+   */
+  export function dotplot_pair_buffers(
+    seq_a: Uint8Array,
+    seq_b: Uint8Array,
+    bins_a: number,
+    bins_b: number,
+    window: number,
+    word_size: number
+  ): DotPlotPairBuffers;
+
   // ============================================================================
   // Sequence Rendering Helpers (Optional, not currently wired in)
   // ============================================================================
@@ -730,6 +1933,10 @@ declare module '@phage/wasm-compute' {
     readonly window_count: number;
     /** K-mer size used */
     readonly k: number;
+    /** Window size in bases */
+    readonly window_size: number;
+    /** Step between window starts */
+    readonly step_size: number;
   }
 
   /**
@@ -751,6 +1958,60 @@ declare module '@phage/wasm-compute' {
    */
   export function scan_kl_windows(seq: Uint8Array, k: number, window_size: number, step_size: number): KLScanResult;
 
+  /** Significant regions of a KL scan (BH-adjusted, adjacent windows merged). */
+  export class KlAnomalyCalls {
+    free(): void;
+    /** One-sided p-value per scanned window */
+    readonly window_p_values: Float64Array;
+    /** Benjamini-Hochberg q-value per scanned window */
+    readonly window_q_values: Float64Array;
+    /** Region starts (bases) */
+    readonly starts: Uint32Array;
+    /** Region ends (bases, exclusive) */
+    readonly ends: Uint32Array;
+    /** Highest window KL per region */
+    readonly peak_kl: Float64Array;
+    /** Start of the peak window per region */
+    readonly peak_positions: Uint32Array;
+    /** Smallest window p-value per region */
+    readonly p_values: Float64Array;
+    /** Smallest window q-value per region */
+    readonly q_values: Float64Array;
+    /** Significant windows merged into each region */
+    readonly window_counts: Uint32Array;
+    readonly region_count: number;
+    readonly error: string | undefined;
+  }
+
+  /**
+   * Call anomalous KL windows with a robust z-score null
+   * ((KL - median) / MAD, upper normal tail).
+   *
+   * @param scan - Result of scan_kl_windows
+   * @param alpha - FDR level for calling windows
+   * @returns KlAnomalyCalls (caller must call `.free()`)
+   */
+  export function call_kl_anomalies_robust(scan: KLScanResult, alpha: number): KlAnomalyCalls;
+
+  /**
+   * Call anomalous KL windows against dinucleotide-preserving shuffles of
+   * the sequence, rescanned with the same k, window and step.
+   *
+   * @param seq - The sequence that was scanned
+   * @param scan - Result of scan_kl_windows(seq, ...)
+   * @param shuffles - Shuffled genomes in the empirical null
+   * @param seed - RNG seed
+   * @param alpha - FDR level for calling windows
+   * @returns KlAnomalyCalls (caller must call `.free()`)
+   */
+  export function call_kl_anomalies_shuffled(
+    seq: Uint8Array,
+    scan: KLScanResult,
+    shuffles: number,
+    seed: bigint,
+    alpha: number
+  ): KlAnomalyCalls;
+
   // ============================================================================
   // Myers Diff/Alignment
   // @see phage_explorer-kyo0.1
@@ -849,6 +2110,250 @@ declare module '@phage/wasm-compute' {
    */
   export function equal_len_diff(seq_a: Uint8Array, seq_b: Uint8Array): MyersDiffResult;
 
+  // ============================================================================
+  // Variant Calling (alignment -> normalized variants -> VCF 4.3)
+  // ============================================================================
+
+  /** Variant classes. */
+  export enum VariantKind {
+    Snv = 0,
+    Mnv = 1,
+    Insertion = 2,
+    Deletion = 3,
+    Complex = 4,
+  }
+
+  /** Predicted coding consequence (most severe over overlapping CDS). */
+  export enum VariantEffect {
+    Intergenic = 0,
+    Synonymous = 1,
+    Missense = 2,
+    Nonsense = 3,
+    StopLost = 4,
+    Frameshift = 5,
+    InframeIndel = 6,
+  }
+
+  /**
+   * Normalized variant records between a reference and a query.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class VariantCallResult {
+    free(): void;
+    /** Number of records */
+    readonly count: number;
+    /** 1-based VCF POS per record */
+    readonly positions: Uint32Array;
+    /** VariantKind code per record */
+    readonly kinds: Uint8Array;
+    /** VariantEffect code per record */
+    readonly effects: Uint8Array;
+    /** CDS index used for the effect (-1 if intergenic) */
+    readonly cds_indices: Int32Array;
+    /** JSON array of {pos, kind, ref, alt, effect, cds, aa} */
+    readonly json: string;
+    /** Error message if the alignment failed (e.g. guardrails exceeded) or exactly one sequence is empty */
+    readonly error: string | undefined;
+    /**
+     * Serialize as VCF 4.3 text.
+     * @param chrom - CHROM column value
+     * @param sample - Sample column name ('' for a sites-only VCF)
+     */
+    to_vcf(chrom: string, sample: string): string;
+  }
+
+  /**
+   * Align a query against a reference (Myers diff) and call normalized variants:
+   * SNV/MNV, left-aligned indels with VCF padding, and complex replacements.
+   *
+   * CDS features are parallel arrays with 0-based half-open coordinates on the
+   * reference; strands are 1 (forward) or -1 (reverse).
+   *
+   * @returns VariantCallResult (caller must call `.free()`)
+   */
+  export function call_variants(
+    reference: Uint8Array,
+    query: Uint8Array,
+    cds_starts: Uint32Array,
+    cds_ends: Uint32Array,
+    cds_strands: Int8Array
+  ): VariantCallResult;
+
+  /**
+   * Call variants from an existing diff (myers_diff, myers_diff_with_limit or equal_len_diff).
+   *
+   * @returns VariantCallResult (caller must call `.free()`)
+   */
+  export function call_variants_from_diff(
+    reference: Uint8Array,
+    query: Uint8Array,
+    diff: MyersDiffResult,
+    cds_starts: Uint32Array,
+    cds_ends: Uint32Array,
+    cds_strands: Int8Array
+  ): VariantCallResult;
+
+  // ============================================================================
+  // Edit Distance (bit-parallel, banded, approximate search)
+  // ============================================================================
+
+  /**
+   * Edit (Levenshtein) distance using Myers/Hyyro bit-parallel vectors.
+   * O(n * ceil(m/64)); accepts ASCII or ACGT05-encoded bytes. N never matches.
+   */
+  export function edit_distance_bitparallel(seq_a: Uint8Array, seq_b: Uint8Array): number;
+
+  /**
+   * Banded edit distance with early exit.
+   *
+   * @returns The distance, or -1 if it exceeds max_k
+   */
+  export function edit_distance_banded(seq_a: Uint8Array, seq_b: Uint8Array, max_k: number): number;
+
+  /**
+   * Occurrences of a pattern found by approximate search.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class ApproxMatchResult {
+    free(): void;
+    /** Start positions in the text (inclusive) */
+    readonly starts: Uint32Array;
+    /** End positions in the text (exclusive) */
+    readonly ends: Uint32Array;
+    /** Edit distance per occurrence */
+    readonly distances: Uint32Array;
+    /** Number of occurrences */
+    readonly count: number;
+  }
+
+  /**
+   * k-differences search: all occurrences of `pattern` in `text` with at most
+   * max_k edits. Runs of adjacent end positions are reported once, at their
+   * lowest-distance end.
+   *
+   * @returns ApproxMatchResult (caller must call `.free()`)
+   */
+  export function approximate_pattern_search(
+    pattern: Uint8Array,
+    text: Uint8Array,
+    max_k: number
+  ): ApproxMatchResult;
+
+  // ============================================================================
+  // Multiple Sequence Alignment (progressive, affine gaps)
+  // ============================================================================
+
+  /**
+   * Progressive multiple sequence alignment. Rows are in input order.
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class MsaResult {
+    free(): void;
+    /** Number of aligned sequences */
+    readonly num_sequences: number;
+    /** Number of alignment columns */
+    readonly alignment_length: number;
+    /** Per-column conservation in [0, 1] */
+    readonly conservation: Float32Array;
+    /** Sequence names as a JSON array */
+    readonly names_json: string;
+    /** Aligned FASTA text (60 columns per line) */
+    readonly aligned_fasta: string;
+    /** Clustal-format text with conservation lines */
+    readonly clustal: string;
+    /** UPGMA guide tree (Newick) */
+    readonly guide_tree: string;
+    /** Error message if alignment failed */
+    readonly error: string | undefined;
+    /** Aligned row (with '-' gaps), '' if out of range */
+    row(index: number): string;
+  }
+
+  /**
+   * Align a gene family: k-mer/MinHash distances -> UPGMA guide tree ->
+   * profile-profile alignment with affine gaps (DNA +5/-4 or BLOSUM62).
+   *
+   * @param fasta - Unaligned sequences in FASTA format
+   * @param protein - true for amino acids, false for nucleotides
+   * @param gap_open - Penalty for the first gap position (e.g. 10)
+   * @param gap_extend - Penalty per extra gap position (e.g. 0.5 DNA, 1 protein)
+   * @returns MsaResult (caller must call `.free()`)
+   */
+  export function progressive_msa(
+    fasta: string,
+    protein: boolean,
+    gap_open: number,
+    gap_extend: number
+  ): MsaResult;
+
+  // ============================================================================
+  // Alignment Tracks (consensus, entropy, conservation, logos)
+  // ============================================================================
+
+  /**
+   * Per-column tracks for an alignment; every array has one entry per column
+   * (logo_heights has columns x logo_alphabet.length entries).
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class AlignmentTracks {
+    free(): void;
+    /** Consensus (IUPAC for DNA, '-' where gaps are the majority) */
+    readonly consensus: string;
+    /** Shannon entropy (bits) of non-gap residues */
+    readonly entropy: Float32Array;
+    /** Jensen-Shannon conservation vs. alignment background, in [0, 1] */
+    readonly js_conservation: Float32Array;
+    /** Fraction of sequences with a gap */
+    readonly gap_fraction: Float32Array;
+    /** Logo letter heights (bits), row-major columns x logo_alphabet.length */
+    readonly logo_heights: Float32Array;
+    /** Symbol order for logo_heights ("ACGT" or 20 amino acids) */
+    readonly logo_alphabet: string;
+    /** Number of columns */
+    readonly columns: number;
+    /** Number of sequences */
+    readonly num_sequences: number;
+    /** Error message for empty or ragged input */
+    readonly error: string | undefined;
+  }
+
+  /**
+   * Compute consensus and conservation tracks from aligned FASTA.
+   *
+   * @param aligned_fasta - Aligned sequences ('-' or '.' gaps), equal lengths
+   * @param protein - true for amino acids, false for nucleotides
+   * @returns AlignmentTracks (caller must call `.free()`)
+   */
+  export function alignment_tracks(aligned_fasta: string, protein: boolean): AlignmentTracks;
+
+  // ============================================================================
+  // Minimizers and Syncmers - sampled k-mers for seeding and indexing
+  // ============================================================================
+
+  /**
+   * Sampled k-mers as parallel arrays (see SequenceHandle.minimizers and
+   * the syncmer methods).
+   *
+   * IMPORTANT: Must call `.free()` when done to release WASM memory.
+   */
+  export class KmerSample {
+    free(): void;
+    readonly hashes: BigUint64Array;
+    /** K-mer start positions (ascending) */
+    readonly positions: Uint32Array;
+    /** 0 = forward was canonical, 1 = reverse complement */
+    readonly strands: Uint8Array;
+    readonly count: number;
+    readonly k: number;
+    /** Sampled k-mers / valid k-mer positions */
+    readonly density: number;
+    readonly error: string | undefined;
+  }
+
   // ============================================================================
   // SequenceHandle - Zero-copy sequence storage in WASM memory
   // @see phage_explorer-8qk2.5
@@ -964,6 +2469,89 @@ declare module '@phage/wasm-compute' {
      * @see phage_explorer-8qk2.6
      */
     dotplot_self(bins: number, window: number): DotPlotBuffers;
+
+    /**
+     * Compute an A-vs-B dot plot against another handle (this sequence is A).
+     *
+     * @param other - Sequence B
+     * @param bins_a - Bins along this sequence (rows)
+     * @param bins_b - Bins along `other` (columns)
+     * @param window - Identity window in bases. If 0, derives a default.
+     * @param word_size - Seed k-mer size (1-32), or 0 for windowed identity
+     * @returns DotPlotPairBuffers with forward and reverse-complement layers
+     */
+    dotplot_pair(
+      other: SequenceHandle,
+      bins_a: number,
+      bins_b: number,
+      window: number,
+      word_size: number
+    ): DotPlotPairBuffers;
+
+    /**
+     * Compute a FracMinHash sketch from the pre-encoded sequence.
+     *
+     * @param k - K-mer size (capped at 32)
+     * @param scaled - Keep hashes below 2^64 / scaled
+     * @param seed - Hash seed
+     */
+    frac_minhash(k: number, scaled: bigint, seed: bigint): FracMinHashSketch;
+
+    /**
+     * VIRIDIC-style intergenomic similarity (percent) with another sequence.
+     */
+    intergenomic_similarity(other: SequenceHandle): number;
+
+    /**
+     * (w,k)-minimizers: smallest k-mer hash in every window of w k-mers.
+     *
+     * @param w - Window size in k-mers
+     * @param k - K-mer size (1..32)
+     * @param canonical - Hash min(forward, reverse complement)
+     * @param seed - Hash seed
+     */
+    minimizers(w: number, k: number, canonical: boolean, seed: bigint): KmerSample;
+
+    /**
+     * Sparse k-mer counts of the pre-encoded sequence (1 <= k <= 31).
+     */
+    count_kmers_sparse(k: number, canonical: boolean): SparseKmerCounts;
+
+    /**
+     * Sparse counts of spaced-seed words (see `count_spaced_kmers`).
+     */
+    count_spaced_kmers(patterns: string, canonical: boolean): SparseKmerCounts;
+
+    /**
+     * Canonical MinHash signature over spaced-seed words (undefined if the
+     * patterns are invalid).
+     */
+    minhash_spaced(patterns: string, num_hashes: number): MinHashSignature | undefined;
+
+    /**
+     * HyperLogLog sketch of the canonical k-mers.
+     *
+     * @param precision - log2 of the register count (4..18)
+     * @param k - K-mer size (1..32)
+     * @param seed - Hash seed
+     */
+    hyperloglog(precision: number, k: number, seed: bigint): HyperLogLog;
+
+    /**
+     * Closed syncmers: k-mers whose smallest s-mer is first or last.
+     */
+    closed_syncmers(k: number, s: number, canonical: boolean, seed: bigint): KmerSample;
+
+    /**
+     * Open syncmers: k-mers whose smallest s-mer is at offset t.
+     */
+    open_syncmers(
+      k: number,
+      s: number,
+      t: number,
+      canonical: boolean,
+      seed: bigint
+    ): KmerSample;
   }
 
   // ============================================================================
@@ -0,0 +1,325 @@
+//! Per-column consensus and conservation tracks for aligned sequences.
+//!
+//! Works on any MSA given as aligned FASTA (e.g. `MsaResult::aligned_fasta`
+//! or an external aligner). Every track has one value per alignment column
+//! so the sequence viewport can index it directly.
+
+use wasm_bindgen::prelude::*;
+
+use crate::msa::{parse_fasta, MsaAlphabet, PROTEIN_ALPHABET};
+use crate::{jensen_shannon_divergence_from_counts, shannon_entropy_from_counts};
+
+/// A base contributes to the IUPAC consensus when at least this fraction of
+/// the column's residues carry it.
+const CONSENSUS_BASE_MIN_FRACTION: f64 = 0.25;
+
+/// IUPAC codes indexed by base bitmask (A=1, C=2, G=4, T=8).
+const IUPAC_BY_MASK: &[u8; 16] = b"-ACMGRSVTWYHKDBN";
+
+/// Per-column tracks for an alignment.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct AlignmentTracks {
+    consensus: String,
+    entropy: Vec<f32>,
+    js_conservation: Vec<f32>,
+    gap_fraction: Vec<f32>,
+    logo_heights: Vec<f32>,
+    logo_alphabet: String,
+    num_sequences: usize,
+    error: Option<String>,
+}
+
+#[wasm_bindgen]
+impl AlignmentTracks {
+    /// Consensus string, one character per column (IUPAC for DNA; `-` where
+    /// most sequences have a gap).
+    #[wasm_bindgen(getter)]
+    pub fn consensus(&self) -> String {
+        self.consensus.clone()
+    }
+
+    /// Shannon entropy (bits) of non-gap residues per column as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn entropy(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.entropy.len() as u32);
+        arr.copy_from(&self.entropy);
+        arr
+    }
+
+    /// Jensen–Shannon conservation in [0, 1] per column as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn js_conservation(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.js_conservation.len() as u32);
+        arr.copy_from(&self.js_conservation);
+        arr
+    }
+
+    /// Fraction of sequences with a gap per column as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn gap_fraction(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.gap_fraction.len() as u32);
+        arr.copy_from(&self.gap_fraction);
+        arr
+    }
+
+    /// Sequence logo letter heights (bits) as Float32Array, row-major
+    /// `columns × logo_alphabet.length`.
+    #[wasm_bindgen(getter)]
+    pub fn logo_heights(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.logo_heights.len() as u32);
+        arr.copy_from(&self.logo_heights);
+        arr
+    }
+
+    /// Symbol order of `logo_heights` ("ACGT" or the 20 amino acids).
+    #[wasm_bindgen(getter)]
+    pub fn logo_alphabet(&self) -> String {
+        self.logo_alphabet.clone()
+    }
+
+    /// Number of alignment columns.
+    #[wasm_bindgen(getter)]
+    pub fn columns(&self) -> usize {
+        self.gap_fraction.len()
+    }
+
+    /// Number of aligned sequences.
+    #[wasm_bindgen(getter)]
+    pub fn num_sequences(&self) -> usize {
+        self.num_sequences
+    }
+
+    /// Error message if the input was not a valid alignment.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Compute consensus and conservation tracks from aligned FASTA.
+///
+/// Per column, over non-gap residues (unknown N/X residues are ignored):
+/// - `consensus`: DNA uses the IUPAC code of all bases with frequency ≥ 25%;
+///   protein uses the most frequent residue. `-` if gaps are the majority.
+/// - `entropy`: Shannon entropy in bits.
+/// - `js_conservation`: Jensen–Shannon divergence between the column and
+///   the alignment-wide background composition, scaled by (1 - gap fraction)
+///   (Capra & Singh 2007).
+/// - `gap_fraction`: share of sequences with a gap.
+/// - `logo_heights`: frequency × information content, with the small-sample
+///   correction (s - 1) / (2 ln 2 · n), scaled by (1 - gap fraction).
+///
+/// # Arguments
+/// * `aligned_fasta` - Aligned sequences (`-` or `.` as gaps), equal lengths
+/// * `protein` - true for amino acids, false for nucleotides
+///
+/// # Returns
+/// `AlignmentTracks`; `error` is set and tracks are empty when there are no
+/// sequences or row lengths differ.
+#[wasm_bindgen]
+pub fn alignment_tracks(aligned_fasta: &str, protein: bool) -> AlignmentTracks {
+    let alphabet = if protein {
+        MsaAlphabet::Protein
+    } else {
+        MsaAlphabet::Dna
+    };
+    let symbols = alphabet.unknown();
+    let logo_alphabet: &[u8] = if protein { PROTEIN_ALPHABET } else { b"ACGT" };
+    let mut result = AlignmentTracks {
+        consensus: String::new(),
+        entropy: Vec::new(),
+        js_conservation: Vec::new(),
+        gap_fraction: Vec::new(),
+        logo_heights: Vec::new(),
+        logo_alphabet: String::from_utf8_lossy(logo_alphabet).into_owned(),
+        num_sequences: 0,
+        error: None,
+    };
+
+    let records = parse_fasta(aligned_fasta, true);
+    if records.is_empty() {
+        result.error = Some("No sequences found in FASTA input".to_string());
+        return result;
+    }
+    let cols = records[0].residues.len();
+    if let Some(bad) = records.iter().find(|r| r.residues.len() != cols) {
+        result.error = Some(format!(
+            "Row '{}' has {} columns, expected {}",
+            bad.name,
+            bad.residues.len(),
+            cols
+        ));
+        return result;
+    }
+    let rows = records.len();
+    result.num_sequences = rows;
+
+    // Symbol index per cell (usize::MAX for gaps and unknown residues).
+    let encoded: Vec<Vec<usize>> = records
+        .iter()
+        .map(|r| {
+            r.residues
+                .iter()
+                .map(|&b| {
+                    if b == b'-' {
+                        usize::MAX
+                    } else {
+                        let sym = alphabet.symbol(b);
+                        if sym < symbols {
+                            sym
+                        } else {
+                            usize::MAX
+                        }
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut background = vec![0.0f64; symbols];
+    for row in &encoded {
+        for &sym in row.iter().filter(|&&s| s != usize::MAX) {
+            background[sym] += 1.0;
+        }
+    }
+
+    let max_bits = (symbols as f64).log2();
+    let mut counts = vec![0.0f64; symbols];
+    let mut consensus = Vec::with_capacity(cols);
+    result.entropy.reserve(cols);
+    result.js_conservation.reserve(cols);
+    result.gap_fraction.reserve(cols);
+    result.logo_heights.reserve(cols * symbols);
+
+    for c in 0..cols {
+        counts.iter_mut().for_each(|x| *x = 0.0);
+        let mut gaps = 0usize;
+        for (row, record) in encoded.iter().zip(&records) {
+            match row[c] {
+                usize::MAX if record.residues[c] == b'-' => gaps += 1,
+                usize::MAX => {}
+                sym => counts[sym] += 1.0,
+            }
+        }
+        let observed: f64 = counts.iter().sum();
+        let gap_fraction = gaps as f64 / rows as f64;
+        result.gap_fraction.push(gap_fraction as f32);
+
+        if observed == 0.0 {
+            consensus.push(if gaps * 2 > rows { b'-' } else { b'N' });
+            result.entropy.push(0.0);
+            result.js_conservation.push(0.0);
+            result
+                .logo_heights
+                .extend(std::iter::repeat_n(0.0f32, symbols));
+            continue;
+        }
+
+        consensus.push(if gaps * 2 > rows {
+            b'-'
+        } else if protein {
+            consensus_residue(&counts)
+        } else {
+            consensus_iupac(&counts, observed)
+        });
+
+        let entropy = shannon_entropy_from_counts(&counts);
+        result.entropy.push(entropy as f32);
+
+        let jsd = jensen_shannon_divergence_from_counts(&counts, &background);
+        result
+            .js_conservation
+            .push((jsd * (1.0 - gap_fraction)) as f32);
+
+        let correction = (symbols as f64 - 1.0) / (2.0 * std::f64::consts::LN_2 * observed);
+        let information = (max_bits - (entropy + correction)).max(0.0) * (1.0 - gap_fraction);
+        result.logo_heights.extend(
+            counts
+                .iter()
+                .map(|&count| (count / observed * information) as f32),
+        );
+    }
+
+    if protein {
+        consensus = consensus
+            .into_iter()
+            .map(|b| if b == b'N' { b'X' } else { b })
+            .collect();
+    }
+    result.consensus = String::from_utf8(consensus).unwrap_or_default();
+    result
+}
+
+/// Most frequent amino acid; ties resolve to alphabet order.
+fn consensus_residue(counts: &[f64]) -> u8 {
+    let mut best = 0;
+    for (i, &count) in counts.iter().enumerate() {
+        if count > counts[best] {
+            best = i;
+        }
+    }
+    PROTEIN_ALPHABET[best]
+}
+
+/// IUPAC code for all bases at or above `CONSENSUS_BASE_MIN_FRACTION`.
+fn consensus_iupac(counts: &[f64], observed: f64) -> u8 {
+    let mask = counts
+        .iter()
+        .take(4)
+        .enumerate()
+        .filter(|(_, &count)| count / observed >= CONSENSUS_BASE_MIN_FRACTION)
+        .fold(0usize, |mask, (i, _)| mask | (1 << i));
+    IUPAC_BY_MASK[mask]
+}
+
+#[cfg(test)]
+mod alignment_tracks_tests {
+    use super::*;
+
+    #[test]
+    fn test_iupac_consensus_and_gaps() {
+        let fasta = ">a\nACGT-A\n>b\nACGT-G\n>c\nATGA-A\n>d\nATGC-G\n";
+        let tracks = alignment_tracks(fasta, false);
+        assert!(tracks.error.is_none());
+        assert_eq!(tracks.columns(), 6);
+        // C/T 50:50 -> Y; A/T/C mix: T 50%, A 25%, C 25% -> H; all gaps -> '-'.
+        assert_eq!(tracks.consensus, "AYGH-R");
+        assert_eq!(tracks.gap_fraction, vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_entropy_and_conservation() {
+        let fasta = ">a\nAA\n>b\nAC\n>c\nAG\n>d\nAT\n";
+        let tracks = alignment_tracks(fasta, false);
+        assert_eq!(tracks.entropy[0], 0.0);
+        assert!((tracks.entropy[1] - 2.0).abs() < 1e-6);
+        // Invariant column diverges from the background; uniform column matches less.
+        assert!(tracks.js_conservation[0] > tracks.js_conservation[1]);
+        // Logo: column 1 carries no information.
+        let col1 = &tracks.logo_heights[4..8];
+        assert!(col1.iter().all(|&h| h == 0.0));
+        // Column 0: only 'A' has height, below the 2-bit maximum.
+        let col0 = &tracks.logo_heights[0..4];
+        assert!(col0[0] > 1.0 && col0[0] < 2.0);
+        assert!(col0[1..].iter().all(|&h| h == 0.0));
+    }
+
+    #[test]
+    fn test_protein_consensus() {
+        let fasta = ">a\nMKV-\n>b\nMRV-\n>c\nMKIW\n";
+        let tracks = alignment_tracks(fasta, true);
+        assert_eq!(tracks.consensus, "MKV-");
+        assert_eq!(tracks.logo_alphabet, "ARNDCQEGHILKMFPSTWYV");
+        assert_eq!(tracks.logo_heights.len(), 4 * 20);
+    }
+
+    #[test]
+    fn test_ragged_alignment_reports_error() {
+        let tracks = alignment_tracks(">a\nACGT\n>b\nACG\n", false);
+        assert!(tracks.error.is_some());
+        assert_eq!(tracks.columns(), 0);
+    }
+}
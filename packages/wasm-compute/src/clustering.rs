@@ -0,0 +1,349 @@
+//! Agglomerative hierarchical clustering over condensed distance matrices.
+//!
+//! Single, complete and average (UPGMA) linkage are computed with the
+//! nearest-neighbor chain algorithm in O(n²) time on a condensed matrix.
+//! The merge list uses the SciPy linkage-matrix convention (leaves `0..n`,
+//! merged clusters `n + merge index`, merges sorted by height) so results
+//! can be compared directly with `scipy.cluster.hierarchy.linkage`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::distance_matrix::condensed_index;
+use crate::phylo::taxa_from_condensed;
+
+/// Maximum number of items (condensed f64 working copy is ~100 MB).
+pub(crate) const CLUSTER_MAX_ITEMS: usize = 5_000;
+
+/// Linkage criterion for `hierarchical_cluster`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Linkage {
+    /// Minimum distance between members
+    Single = 0,
+    /// Maximum distance between members
+    Complete = 1,
+    /// Size-weighted mean distance between members (UPGMA)
+    Average = 2,
+}
+
+/// One merge in SciPy linkage-matrix form.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Merge {
+    pub(crate) a: usize,
+    pub(crate) b: usize,
+    pub(crate) height: f64,
+    pub(crate) size: usize,
+}
+
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+/// Cluster `n` items given a condensed distance matrix.
+pub(crate) fn linkage_merges(condensed: &[f64], n: usize, linkage: Linkage) -> Vec<Merge> {
+    if n < 2 {
+        return Vec::new();
+    }
+    let mut d = condensed.to_vec();
+    let dist = |d: &[f64], i: usize, j: usize| -> f64 {
+        if i < j {
+            d[condensed_index(n, i, j)]
+        } else {
+            d[condensed_index(n, j, i)]
+        }
+    };
+
+    let mut active = vec![true; n];
+    let mut sizes = vec![1usize; n];
+    let mut chain: Vec<usize> = Vec::with_capacity(n);
+    // (slot merged away, surviving slot, height)
+    let mut raw: Vec<(usize, usize, f64)> = Vec::with_capacity(n - 1);
+
+    while raw.len() < n - 1 {
+        if chain.is_empty() {
+            chain.push((0..n).find(|&i| active[i]).unwrap_or(0));
+        }
+        let (x, y, dxy) = loop {
+            let x = chain[chain.len() - 1];
+            let prev = (chain.len() >= 2).then(|| chain[chain.len() - 2]);
+            // Prefer the previous chain element on ties so the chain terminates.
+            let (mut y, mut best) = match prev {
+                Some(p) => (p, dist(&d, x, p)),
+                None => (usize::MAX, f64::INFINITY),
+            };
+            for (i, &alive) in active.iter().enumerate() {
+                if alive && i != x {
+                    let v = dist(&d, x, i);
+                    if v < best {
+                        best = v;
+                        y = i;
+                    }
+                }
+            }
+            if Some(y) == prev {
+                chain.truncate(chain.len() - 2);
+                break (x, y, best);
+            }
+            chain.push(y);
+        };
+
+        let (sx, sy) = (sizes[x] as f64, sizes[y] as f64);
+        for (k, &alive) in active.iter().enumerate() {
+            if !alive || k == x || k == y {
+                continue;
+            }
+            let (dxk, dyk) = (dist(&d, x, k), dist(&d, y, k));
+            let merged = match linkage {
+                Linkage::Single => dxk.min(dyk),
+                Linkage::Complete => dxk.max(dyk),
+                Linkage::Average => (dxk * sx + dyk * sy) / (sx + sy),
+            };
+            let idx = if y < k {
+                condensed_index(n, y, k)
+            } else {
+                condensed_index(n, k, y)
+            };
+            d[idx] = merged;
+        }
+        active[x] = false;
+        sizes[y] += sizes[x];
+        raw.push((x, y, dxy));
+    }
+
+    merges_from_edges(raw, n)
+}
+
+/// SciPy-ordered merges from (item, item, height) joins, e.g. NN-chain
+/// merges or minimum spanning tree edges: sorts by height and relabels
+/// through union-find.
+pub(crate) fn merges_from_edges(mut raw: Vec<(usize, usize, f64)>, n: usize) -> Vec<Merge> {
+    raw.sort_by(|a, b| a.2.total_cmp(&b.2));
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut label: Vec<usize> = (0..n).collect();
+    let mut size = vec![1usize; n];
+    raw.iter()
+        .enumerate()
+        .map(|(idx, &(x, y, height))| {
+            let (rx, ry) = (find(&mut parent, x), find(&mut parent, y));
+            let (la, lb) = (label[rx], label[ry]);
+            parent[rx] = ry;
+            label[ry] = n + idx;
+            size[ry] += size[rx];
+            Merge {
+                a: la.min(lb),
+                b: la.max(lb),
+                height,
+                size: size[ry],
+            }
+        })
+        .collect()
+}
+
+/// Flat cluster labels: merges at height <= `threshold` are joined.
+///
+/// Labels are numbered from 0 in order of first appearance among the items.
+pub(crate) fn cut_merges(merges: &[Merge], n: usize, threshold: f64) -> Vec<i32> {
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut representative: Vec<usize> = (0..n).collect();
+    for m in merges {
+        let (ra, rb) = (representative[m.a], representative[m.b]);
+        representative.push(ra);
+        if m.height <= threshold {
+            let (fa, fb) = (find(&mut parent, ra), find(&mut parent, rb));
+            parent[fa] = fb;
+        }
+    }
+
+    let mut root_label: Vec<i32> = vec![-1; n];
+    let mut next = 0;
+    (0..n)
+        .map(|i| {
+            let root = find(&mut parent, i);
+            if root_label[root] < 0 {
+                root_label[root] = next;
+                next += 1;
+            }
+            root_label[root]
+        })
+        .collect()
+}
+
+/// Result of hierarchical clustering.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct Dendrogram {
+    n: usize,
+    merges: Vec<Merge>,
+    error: Option<String>,
+}
+
+impl Dendrogram {
+    pub(crate) fn from_condensed(condensed: &[f64], n: usize, linkage: Linkage) -> Dendrogram {
+        Dendrogram {
+            n,
+            merges: linkage_merges(condensed, n, linkage),
+            error: None,
+        }
+    }
+
+    pub(crate) fn labels(&self, threshold: f64) -> Vec<i32> {
+        cut_merges(&self.merges, self.n, threshold)
+    }
+}
+
+#[wasm_bindgen]
+impl Dendrogram {
+    /// Number of clustered items.
+    #[wasm_bindgen(getter)]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// SciPy linkage matrix as Float64Array ((n-1) × 4, row-major):
+    /// `[cluster_a, cluster_b, height, size]` per merge.
+    #[wasm_bindgen(getter)]
+    pub fn linkage_matrix(&self) -> js_sys::Float64Array {
+        let flat: Vec<f64> = self
+            .merges
+            .iter()
+            .flat_map(|m| [m.a as f64, m.b as f64, m.height, m.size as f64])
+            .collect();
+        let arr = js_sys::Float64Array::new_with_length(flat.len() as u32);
+        arr.copy_from(&flat);
+        arr
+    }
+
+    /// Merge heights in ascending order as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn heights(&self) -> js_sys::Float64Array {
+        let heights: Vec<f64> = self.merges.iter().map(|m| m.height).collect();
+        let arr = js_sys::Float64Array::new_with_length(heights.len() as u32);
+        arr.copy_from(&heights);
+        arr
+    }
+
+    /// Flat cluster label per item as Int32Array.
+    ///
+    /// Items end up in the same cluster when they are connected by merges at
+    /// height <= `threshold` (SciPy `fcluster(..., criterion="distance")`).
+    pub fn cut(&self, threshold: f64) -> js_sys::Int32Array {
+        let labels = self.labels(threshold);
+        let arr = js_sys::Int32Array::new_with_length(labels.len() as u32);
+        arr.copy_from(&labels);
+        arr
+    }
+
+    /// Number of flat clusters at `threshold`.
+    pub fn cluster_count(&self, threshold: f64) -> usize {
+        self.n - self.merges.iter().filter(|m| m.height <= threshold).count()
+    }
+
+    /// Error message if clustering failed.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Hierarchical clustering of a condensed distance matrix.
+///
+/// # Arguments
+/// * `condensed` - Upper-triangle distances, row-major (n*(n-1)/2 values)
+/// * `linkage` - Linkage criterion
+///
+/// # Returns
+/// `Dendrogram`; use `cut(threshold)` for flat clusters.
+#[wasm_bindgen]
+pub fn hierarchical_cluster(condensed: &[f32], linkage: Linkage) -> Dendrogram {
+    let failed = |message: String| Dendrogram {
+        n: 0,
+        merges: Vec::new(),
+        error: Some(message),
+    };
+    let Some(n) = taxa_from_condensed(condensed.len()) else {
+        return failed(format!(
+            "Condensed matrix length {} is not n*(n-1)/2",
+            condensed.len()
+        ));
+    };
+    if n > CLUSTER_MAX_ITEMS {
+        return failed(format!("Too many items: {} (max {})", n, CLUSTER_MAX_ITEMS));
+    }
+    if condensed.iter().any(|v| !v.is_finite()) {
+        return failed("Distance matrix contains non-finite values".to_string());
+    }
+    let values: Vec<f64> = condensed.iter().map(|&v| v as f64).collect();
+    Dendrogram::from_condensed(&values, n, linkage)
+}
+
+#[cfg(test)]
+mod clustering_tests {
+    use super::*;
+
+    /// Two tight pairs {0,1} and {2,3} plus an outlier 4 on a line.
+    fn line_points() -> (Vec<f64>, usize) {
+        let x: [f64; 5] = [0.0, 1.0, 5.0, 6.5, 20.0];
+        let n = x.len();
+        let mut d = Vec::new();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                d.push((x[i] - x[j]).abs());
+            }
+        }
+        (d, n)
+    }
+
+    #[test]
+    fn test_single_linkage_merge_order() {
+        let (d, n) = line_points();
+        let merges = linkage_merges(&d, n, Linkage::Single);
+        let rows: Vec<(usize, usize, f64, usize)> = merges
+            .iter()
+            .map(|m| (m.a, m.b, m.height, m.size))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (0, 1, 1.0, 2),
+                (2, 3, 1.5, 2),
+                (5, 6, 4.0, 4),
+                (4, 7, 13.5, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complete_and_average_heights() {
+        let (d, n) = line_points();
+        let complete = linkage_merges(&d, n, Linkage::Complete);
+        assert_eq!(complete[2].height, 6.5);
+        assert_eq!(complete[3].height, 20.0);
+        let average = linkage_merges(&d, n, Linkage::Average);
+        // Mean of |{0,1} x {5,6.5}| = (5 + 6.5 + 4 + 5.5) / 4
+        assert!((average[2].height - 5.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cut_by_threshold() {
+        let (d, n) = line_points();
+        let merges = linkage_merges(&d, n, Linkage::Single);
+        assert_eq!(cut_merges(&merges, n, 0.5), vec![0, 1, 2, 3, 4]);
+        assert_eq!(cut_merges(&merges, n, 2.0), vec![0, 0, 1, 1, 2]);
+        assert_eq!(cut_merges(&merges, n, 5.0), vec![0, 0, 0, 0, 1]);
+        assert_eq!(cut_merges(&merges, n, 100.0), vec![0; 5]);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let result = hierarchical_cluster(&[1.0, 2.0], Linkage::Average);
+        assert!(result.error.is_some());
+        let result = hierarchical_cluster(&[f32::NAN], Linkage::Average);
+        assert!(result.error.is_some());
+    }
+}
@@ -0,0 +1,405 @@
+//! Density-based clustering with explicit noise.
+//!
+//! - DBSCAN: clusters are connected regions of core points (at least
+//!   `min_samples` points, itself included, within `eps`); border points
+//!   join the first cluster that reaches them, the rest is noise
+//! - HDBSCAN: single linkage over mutual reachability distances, condensed
+//!   with `min_cluster_size` and cut by excess of mass, so clusters of
+//!   different densities come out without choosing `eps`
+//!
+//! Both use exact O(n²) neighbor queries and O(n) extra memory.
+
+use wasm_bindgen::prelude::*;
+
+use crate::clustering::merges_from_edges;
+use crate::embedding::PointSet;
+use crate::partitioning::ClusterAssignment;
+
+fn run_dbscan(points: Result<PointSet, String>, eps: f64, min_samples: usize) -> ClusterAssignment {
+    let points = match points {
+        Ok(points) => points,
+        Err(error) => return ClusterAssignment::failed(error),
+    };
+    if eps.is_nan() || eps < 0.0 {
+        return ClusterAssignment::failed(format!("eps must be non-negative (got {})", eps));
+    }
+    let n = points.len();
+    let neighbors =
+        |i: usize| -> Vec<usize> { (0..n).filter(|&j| points.distance(i, j) <= eps).collect() };
+
+    let mut labels = vec![-1i32; n];
+    let mut visited = vec![false; n];
+    // Each point is queued at most once, so expansion stays O(n) in memory.
+    let mut enqueued = vec![false; n];
+    let mut queue = Vec::new();
+    let mut next = 0;
+    for i in 0..n {
+        if visited[i] {
+            continue;
+        }
+        visited[i] = true;
+        let seeds = neighbors(i);
+        if seeds.len() < min_samples {
+            continue;
+        }
+        labels[i] = next;
+        enqueued[i] = true;
+        for m in seeds {
+            if !enqueued[m] {
+                enqueued[m] = true;
+                queue.push(m);
+            }
+        }
+        while let Some(j) = queue.pop() {
+            if labels[j] < 0 {
+                labels[j] = next;
+            }
+            if visited[j] {
+                continue;
+            }
+            visited[j] = true;
+            let reach = neighbors(j);
+            if reach.len() >= min_samples {
+                for m in reach {
+                    if !enqueued[m] {
+                        enqueued[m] = true;
+                        queue.push(m);
+                    }
+                }
+            }
+        }
+        next += 1;
+    }
+    let probabilities = labels
+        .iter()
+        .map(|&l| f32::from(u8::from(l >= 0)))
+        .collect();
+    ClusterAssignment::from_labels(&points, labels, probabilities, f64::NAN, 0)
+}
+
+/// Cluster of the condensed tree.
+struct Condensed {
+    parent: Option<usize>,
+    birth: f64,
+    stability: f64,
+    children: Vec<usize>,
+}
+
+/// Points under a SciPy merge node.
+fn leaves(node: usize, n: usize, merges: &[crate::clustering::Merge], out: &mut Vec<usize>) {
+    let mut stack = vec![node];
+    while let Some(x) = stack.pop() {
+        if x < n {
+            out.push(x);
+        } else {
+            stack.push(merges[x - n].a);
+            stack.push(merges[x - n].b);
+        }
+    }
+}
+
+/// Minimum spanning tree of the mutual reachability graph (dense Prim).
+fn reachability_mst(points: &PointSet, core: &[f64]) -> Vec<(usize, usize, f64)> {
+    let n = points.len();
+    let mut in_tree = vec![false; n];
+    let mut best = vec![(f64::INFINITY, 0usize); n];
+    let mut edges = Vec::with_capacity(n - 1);
+    let mut current = 0;
+    in_tree[0] = true;
+    for _ in 1..n {
+        for j in 0..n {
+            if !in_tree[j] {
+                let d = points.distance(current, j).max(core[current]).max(core[j]);
+                if d < best[j].0 {
+                    best[j] = (d, current);
+                }
+            }
+        }
+        let next = (0..n)
+            .filter(|&j| !in_tree[j])
+            .min_by(|&a, &b| best[a].0.total_cmp(&best[b].0))
+            .unwrap();
+        in_tree[next] = true;
+        edges.push((best[next].1, next, best[next].0));
+        current = next;
+    }
+    edges
+}
+
+fn run_hdbscan(
+    points: Result<PointSet, String>,
+    min_cluster_size: usize,
+    min_samples: usize,
+) -> ClusterAssignment {
+    let points = match points {
+        Ok(points) => points,
+        Err(error) => return ClusterAssignment::failed(error),
+    };
+    let n = points.len();
+    if min_cluster_size < 2 || min_cluster_size > n {
+        return ClusterAssignment::failed(format!(
+            "min_cluster_size must be 2..={} (got {})",
+            n, min_cluster_size
+        ));
+    }
+    let min_samples = if min_samples == 0 {
+        min_cluster_size
+    } else {
+        min_samples.min(n)
+    };
+
+    // Core distance: distance to the min_samples-th point, itself included.
+    let core: Vec<f64> = if min_samples <= 1 {
+        vec![0.0; n]
+    } else {
+        points
+            .knn(min_samples - 1)
+            .iter()
+            .map(|row| row.last().map_or(0.0, |&(_, d)| d))
+            .collect()
+    };
+    let merges = merges_from_edges(reachability_mst(&points, &core), n);
+
+    // λ = 1 / height; zero heights (duplicates) get a large finite λ so
+    // stabilities stay comparable.
+    let floor = merges
+        .last()
+        .map_or(1.0, |m| m.height)
+        .max(f64::MIN_POSITIVE)
+        * 1e-10;
+    let lambda = |height: f64| 1.0 / height.max(floor);
+    let size = |node: usize| if node < n { 1 } else { merges[node - n].size };
+
+    // Condense: walk down from the root, following splits where both sides
+    // have at least min_cluster_size points; smaller sides fall out.
+    let mut clusters = vec![Condensed {
+        parent: None,
+        birth: 0.0,
+        stability: 0.0,
+        children: Vec::new(),
+    }];
+    let mut point_lambda = vec![0.0f64; n];
+    let mut point_cluster = vec![0usize; n];
+    let mut stack = vec![(2 * n - 2, 0usize)];
+    let mut fallen = Vec::new();
+    while let Some((node, c)) = stack.pop() {
+        if node < n {
+            continue;
+        }
+        let m = merges[node - n];
+        let l = lambda(m.height);
+        let big = [m.a, m.b].map(|x| size(x) >= min_cluster_size);
+        if big[0] && big[1] {
+            clusters[c].stability += (l - clusters[c].birth) * m.size as f64;
+            for child in [m.a, m.b] {
+                let id = clusters.len();
+                clusters.push(Condensed {
+                    parent: Some(c),
+                    birth: l,
+                    stability: 0.0,
+                    children: Vec::new(),
+                });
+                clusters[c].children.push(id);
+                stack.push((child, id));
+            }
+            continue;
+        }
+        for (child, keep) in [m.a, m.b].into_iter().zip(big) {
+            if keep {
+                stack.push((child, c));
+            } else {
+                clusters[c].stability += (l - clusters[c].birth) * size(child) as f64;
+                fallen.clear();
+                leaves(child, n, &merges, &mut fallen);
+                for &p in &fallen {
+                    point_lambda[p] = l;
+                    point_cluster[p] = c;
+                }
+            }
+        }
+    }
+
+    // Excess of mass: keep a cluster unless its descendants are jointly
+    // more stable. Children always have larger ids than their parent.
+    let count = clusters.len();
+    let mut selected = vec![false; count];
+    let mut subtree = vec![0.0f64; count];
+    for c in (1..count).rev() {
+        let below: f64 = clusters[c].children.iter().map(|&ch| subtree[ch]).sum();
+        if clusters[c].children.is_empty() || clusters[c].stability >= below {
+            selected[c] = true;
+            subtree[c] = clusters[c].stability;
+        } else {
+            subtree[c] = below;
+        }
+    }
+    let mut label_of = vec![-1i32; count];
+    let mut covered = vec![false; count];
+    let mut next = 0;
+    for c in 1..count {
+        let parent = clusters[c].parent.unwrap();
+        covered[c] = covered[parent] || label_of[parent] >= 0;
+        if selected[c] && !covered[c] {
+            label_of[c] = next;
+            next += 1;
+        }
+    }
+
+    let labels: Vec<i32> = (0..n)
+        .map(|p| {
+            let mut c = Some(point_cluster[p]);
+            while let Some(id) = c {
+                if label_of[id] >= 0 {
+                    return label_of[id];
+                }
+                c = clusters[id].parent;
+            }
+            -1
+        })
+        .collect();
+    let mut max_lambda = vec![0.0f64; next as usize];
+    for (p, &l) in labels.iter().enumerate() {
+        if l >= 0 {
+            max_lambda[l as usize] = max_lambda[l as usize].max(point_lambda[p]);
+        }
+    }
+    let probabilities = labels
+        .iter()
+        .enumerate()
+        .map(|(p, &l)| {
+            if l < 0 {
+                0.0
+            } else {
+                let top = max_lambda[l as usize];
+                if top > 0.0 {
+                    (point_lambda[p].min(top) / top) as f32
+                } else {
+                    1.0
+                }
+            }
+        })
+        .collect();
+    ClusterAssignment::from_labels(&points, labels, probabilities, f64::NAN, 0)
+}
+
+/// DBSCAN on a feature matrix (Euclidean).
+///
+/// # Arguments
+/// * `data` - Row-major matrix (n_samples × n_features)
+/// * `eps` - Neighborhood radius (inclusive)
+/// * `min_samples` - Neighbors within `eps`, the point itself included,
+///   for a core point
+#[wasm_bindgen]
+pub fn dbscan(
+    data: &[f32],
+    n_samples: usize,
+    n_features: usize,
+    eps: f64,
+    min_samples: usize,
+) -> ClusterAssignment {
+    run_dbscan(
+        PointSet::from_features(data, n_samples, n_features),
+        eps,
+        min_samples,
+    )
+}
+
+/// DBSCAN on a condensed distance matrix.
+#[wasm_bindgen]
+pub fn dbscan_from_distances(condensed: &[f32], eps: f64, min_samples: usize) -> ClusterAssignment {
+    run_dbscan(PointSet::from_condensed(condensed), eps, min_samples)
+}
+
+/// HDBSCAN on a feature matrix (Euclidean).
+///
+/// # Arguments
+/// * `data` - Row-major matrix (n_samples × n_features)
+/// * `min_cluster_size` - Smallest group reported as a cluster (>= 2)
+/// * `min_samples` - Neighborhood size for core distances, the point itself
+///   included (0 = `min_cluster_size`); larger values call more noise
+///
+/// `probabilities` are membership strengths: 1 at the cluster's densest
+/// point, falling towards 0 at its fringe.
+#[wasm_bindgen]
+pub fn hdbscan(
+    data: &[f32],
+    n_samples: usize,
+    n_features: usize,
+    min_cluster_size: usize,
+    min_samples: usize,
+) -> ClusterAssignment {
+    run_hdbscan(
+        PointSet::from_features(data, n_samples, n_features),
+        min_cluster_size,
+        min_samples,
+    )
+}
+
+/// HDBSCAN on a condensed distance matrix.
+#[wasm_bindgen]
+pub fn hdbscan_from_distances(
+    condensed: &[f32],
+    min_cluster_size: usize,
+    min_samples: usize,
+) -> ClusterAssignment {
+    run_hdbscan(
+        PointSet::from_condensed(condensed),
+        min_cluster_size,
+        min_samples,
+    )
+}
+
+#[cfg(test)]
+mod density_tests {
+    use super::*;
+    use crate::partitioning::partitioning_tests::{blobs, same_partition};
+
+    /// Blobs plus far-away isolated points appended at the end.
+    fn with_outliers(clusters: usize, per: usize) -> (Vec<f32>, usize) {
+        let mut data = blobs(clusters, per, 3);
+        for i in 0..3 {
+            data.extend_from_slice(&[-40.0 - 25.0 * i as f32, 60.0, -30.0, 45.0]);
+        }
+        (data, clusters * per + 3)
+    }
+
+    #[test]
+    fn test_dbscan_flags_outliers() {
+        let (data, n) = with_outliers(3, 25);
+        let result = dbscan(&data, n, 4, 3.0, 4);
+        assert!(result.error.is_none());
+        assert_eq!(result.cluster_count, 3);
+        assert!(same_partition(&result.labels[..75], 25));
+        assert_eq!(&result.labels[75..], &[-1, -1, -1]);
+        assert_eq!(result.noise_count(), 3);
+        assert!(dbscan(&data, n, 4, -1.0, 4).error.is_some());
+    }
+
+    #[test]
+    fn test_hdbscan_features_and_distances() {
+        let (data, n) = with_outliers(4, 30);
+        let result = hdbscan(&data, n, 4, 10, 5);
+        assert!(result.error.is_none());
+        assert_eq!(result.cluster_count, 4);
+        assert!(same_partition(&result.labels[..120], 30));
+        assert_eq!(&result.labels[120..], &[-1, -1, -1]);
+        assert!(result
+            .probabilities
+            .iter()
+            .all(|&p| (0.0..=1.0).contains(&p)));
+        assert!(result.probabilities.contains(&1.0));
+        assert_eq!(result.centroids.len(), 16);
+
+        let points = PointSet::from_features(&data, n, 4).unwrap();
+        let mut condensed = Vec::new();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                condensed.push(points.distance(i, j) as f32);
+            }
+        }
+        let from_distances = hdbscan_from_distances(&condensed, 10, 5);
+        assert_eq!(from_distances.labels, result.labels);
+        assert_eq!(from_distances.medoids.len(), 4);
+        assert!(hdbscan(&data, n, 4, 1, 5).error.is_some());
+    }
+}
@@ -0,0 +1,438 @@
+//! All-vs-all distance matrices for genome collections.
+//!
+//! `GenomeCollection` holds per-genome MinHash signatures and/or dense k-mer
+//! profiles in WASM memory so the full pairwise matrix is computed in one
+//! place instead of looping over the boundary from JS.
+//!
+//! Output uses the condensed (SciPy `pdist`) layout: the upper triangle
+//! without the diagonal, row by row, `n * (n - 1) / 2` values. Long runs can
+//! be driven incrementally via `CondensedDistanceMatrix::step`, which returns
+//! the completed fraction so a worker can post progress between calls.
+
+use wasm_bindgen::prelude::*;
+
+use crate::sketch::mash_distance;
+use crate::{
+    jensen_shannon_divergence_from_counts, minhash_jaccard_from_signatures, MinHashSignature,
+};
+
+/// Maximum number of genomes in a collection (condensed f32 matrix ~50 MB).
+pub(crate) const COLLECTION_MAX_GENOMES: usize = 5_000;
+
+/// Maximum total number of stored profile values (~128 MB of f64).
+pub(crate) const COLLECTION_MAX_PROFILE_VALUES: usize = 16_000_000;
+
+/// Distance measure for `GenomeCollection::distance_matrix`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// Mash distance from MinHash signatures: -1/k · ln(2J / (1 + J))
+    Mash = 0,
+    /// 1 - Jaccard estimated from MinHash signatures
+    Jaccard = 1,
+    /// Jensen–Shannon divergence (log2, in [0, 1]) between k-mer profiles
+    Jsd = 2,
+    /// 1 - cosine similarity between k-mer profiles
+    Cosine = 3,
+}
+
+impl DistanceMetric {
+    fn uses_signatures(self) -> bool {
+        matches!(self, DistanceMetric::Mash | DistanceMetric::Jaccard)
+    }
+}
+
+/// Index of pair (i, j), i < j, in a condensed matrix of `n` items.
+#[inline(always)]
+pub(crate) fn condensed_index(n: usize, i: usize, j: usize) -> usize {
+    debug_assert!(i < j && j < n);
+    n * i - i * (i + 1) / 2 + (j - i - 1)
+}
+
+/// Number of entries in a condensed matrix of `n` items.
+#[inline(always)]
+pub(crate) fn condensed_len(n: usize) -> usize {
+    n * n.saturating_sub(1) / 2
+}
+
+/// A collection of genomes with MinHash signatures and/or k-mer profiles.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct GenomeCollection {
+    names: Vec<String>,
+    signatures: Vec<Option<MinHashSignature>>,
+    profiles: Vec<Option<Vec<f64>>>,
+    /// Precomputed L2 norms of profiles (for cosine).
+    norms: Vec<f64>,
+    k: usize,
+    profile_values: usize,
+}
+
+#[wasm_bindgen]
+impl GenomeCollection {
+    /// Create an empty collection.
+    ///
+    /// # Arguments
+    /// * `k` - K-mer size of the signatures (used by the Mash distance)
+    #[wasm_bindgen(constructor)]
+    pub fn new(k: usize) -> GenomeCollection {
+        GenomeCollection {
+            names: Vec::new(),
+            signatures: Vec::new(),
+            profiles: Vec::new(),
+            norms: Vec::new(),
+            k,
+            profile_values: 0,
+        }
+    }
+
+    /// Add a genome with a MinHash signature.
+    ///
+    /// The signature must use the collection's k and share the hash count,
+    /// strand mode and seed of the signatures already added. Pass an empty
+    /// profile if none is available; profiles must all have the same length.
+    ///
+    /// # Arguments
+    /// * `name` - Genome label
+    /// * `signature` - MinHash signature (e.g. from `minhash_signature_canonical`)
+    /// * `profile` - Dense k-mer counts (e.g. `DenseKmerResult.counts`)
+    ///
+    /// # Returns
+    /// Index of the new genome, or -1 if the input was rejected (incompatible
+    /// signature, profile length mismatch, or collection limits exceeded).
+    pub fn add(&mut self, name: &str, signature: &MinHashSignature, profile: &[f64]) -> i32 {
+        if signature.k != self.k {
+            return -1;
+        }
+        let first = self.signatures.iter().flatten().next();
+        if first.is_some_and(|first| !first.is_compatible(signature)) {
+            return -1;
+        }
+        self.push(name, Some(signature), profile)
+    }
+
+    /// Add a genome that only has a k-mer profile.
+    ///
+    /// # Returns
+    /// Index of the new genome, or -1 if the profile is empty, its length
+    /// differs from earlier profiles, or collection limits are exceeded.
+    pub fn add_profile(&mut self, name: &str, profile: &[f64]) -> i32 {
+        if profile.is_empty() {
+            return -1;
+        }
+        self.push(name, None, profile)
+    }
+
+    /// Number of genomes.
+    #[wasm_bindgen(getter)]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Name of genome `index` (empty if out of range).
+    pub fn name(&self, index: usize) -> String {
+        self.names.get(index).cloned().unwrap_or_default()
+    }
+
+    /// K-mer size used for the Mash distance.
+    #[wasm_bindgen(getter)]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Prepare an incremental all-vs-all computation.
+    ///
+    /// Call `step()` on the returned matrix until `is_complete`.
+    pub fn start_distance_matrix(&self, metric: DistanceMetric) -> CondensedDistanceMatrix {
+        let n = self.names.len();
+        let mut matrix = CondensedDistanceMatrix {
+            n,
+            metric,
+            values: vec![0.0; condensed_len(n)],
+            next_i: 0,
+            next_j: 1,
+            completed: 0,
+            error: None,
+        };
+        let missing = if metric.uses_signatures() {
+            self.signatures.iter().position(|s| s.is_none())
+        } else {
+            self.profiles.iter().position(|p| p.is_none())
+        };
+        if let Some(index) = missing {
+            matrix.error = Some(format!(
+                "Genome '{}' has no {} for metric {:?}",
+                self.names[index],
+                if metric.uses_signatures() {
+                    "signature"
+                } else {
+                    "profile"
+                },
+                metric
+            ));
+            matrix.values.clear();
+        }
+        matrix
+    }
+
+    /// Compute the full condensed distance matrix in one call.
+    pub fn distance_matrix(&self, metric: DistanceMetric) -> CondensedDistanceMatrix {
+        let mut matrix = self.start_distance_matrix(metric);
+        matrix.step(self, usize::MAX);
+        matrix
+    }
+}
+
+impl GenomeCollection {
+    fn push(&mut self, name: &str, signature: Option<&MinHashSignature>, profile: &[f64]) -> i32 {
+        if self.names.len() >= COLLECTION_MAX_GENOMES
+            || self.profile_values + profile.len() > COLLECTION_MAX_PROFILE_VALUES
+        {
+            return -1;
+        }
+        let profile_len = self.profiles.iter().flatten().map(|p| p.len()).next();
+        if !profile.is_empty() && profile_len.is_some_and(|len| len != profile.len()) {
+            return -1;
+        }
+
+        self.names.push(name.to_string());
+        self.signatures.push(signature.cloned());
+        self.profiles
+            .push((!profile.is_empty()).then(|| profile.to_vec()));
+        self.norms
+            .push(profile.iter().map(|v| v * v).sum::<f64>().sqrt());
+        self.profile_values += profile.len();
+        (self.names.len() - 1) as i32
+    }
+
+    fn pair_distance(&self, metric: DistanceMetric, i: usize, j: usize) -> f32 {
+        match metric {
+            DistanceMetric::Mash | DistanceMetric::Jaccard => {
+                let (Some(a), Some(b)) = (&self.signatures[i], &self.signatures[j]) else {
+                    return 1.0;
+                };
+                let jaccard = minhash_jaccard_from_signatures(&a.signature, &b.signature);
+                if metric == DistanceMetric::Mash {
+                    mash_distance(jaccard, self.k as u32) as f32
+                } else {
+                    (1.0 - jaccard) as f32
+                }
+            }
+            DistanceMetric::Jsd => {
+                let (Some(a), Some(b)) = (&self.profiles[i], &self.profiles[j]) else {
+                    return 1.0;
+                };
+                jensen_shannon_divergence_from_counts(a, b) as f32
+            }
+            DistanceMetric::Cosine => {
+                let (Some(a), Some(b)) = (&self.profiles[i], &self.profiles[j]) else {
+                    return 1.0;
+                };
+                let denom = self.norms[i] * self.norms[j];
+                if denom == 0.0 {
+                    return 1.0;
+                }
+                let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+                (1.0 - dot / denom).clamp(0.0, 2.0) as f32
+            }
+        }
+    }
+}
+
+/// Condensed pairwise distance matrix, possibly still being computed.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct CondensedDistanceMatrix {
+    n: usize,
+    metric: DistanceMetric,
+    values: Vec<f32>,
+    next_i: usize,
+    next_j: usize,
+    completed: usize,
+    error: Option<String>,
+}
+
+#[wasm_bindgen]
+impl CondensedDistanceMatrix {
+    /// Compute up to `max_pairs` more pairs.
+    ///
+    /// `collection` must be the collection this matrix was started from and
+    /// must not have been modified since.
+    ///
+    /// # Returns
+    /// Fraction of pairs completed, in [0, 1].
+    pub fn step(&mut self, collection: &GenomeCollection, max_pairs: usize) -> f64 {
+        if self.error.is_some() || self.is_complete() {
+            return self.progress();
+        }
+        if collection.len() != self.n {
+            self.error = Some("Collection changed during computation".to_string());
+            return self.progress();
+        }
+
+        let mut budget = max_pairs;
+        while budget > 0 && self.next_i + 1 < self.n {
+            let (i, j) = (self.next_i, self.next_j);
+            self.values[condensed_index(self.n, i, j)] =
+                collection.pair_distance(self.metric, i, j);
+            self.completed += 1;
+            budget -= 1;
+
+            self.next_j += 1;
+            if self.next_j == self.n {
+                self.next_i += 1;
+                self.next_j = self.next_i + 1;
+            }
+        }
+        self.progress()
+    }
+
+    /// Fraction of pairs completed, in [0, 1].
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> f64 {
+        let total = condensed_len(self.n);
+        if total == 0 {
+            1.0
+        } else {
+            self.completed as f64 / total as f64
+        }
+    }
+
+    /// True once every pair has been computed.
+    #[wasm_bindgen(getter)]
+    pub fn is_complete(&self) -> bool {
+        self.completed == condensed_len(self.n)
+    }
+
+    /// Number of genomes.
+    #[wasm_bindgen(getter)]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Metric used.
+    #[wasm_bindgen(getter)]
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    /// Condensed distances (row-major upper triangle) as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn values(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.values.len() as u32);
+        arr.copy_from(&self.values);
+        arr
+    }
+
+    /// Distance between genomes `i` and `j` (0 on the diagonal, NaN if out of range).
+    pub fn get(&self, i: usize, j: usize) -> f32 {
+        if i >= self.n || j >= self.n || self.values.is_empty() && self.n > 1 {
+            return f32::NAN;
+        }
+        match i.cmp(&j) {
+            std::cmp::Ordering::Equal => 0.0,
+            std::cmp::Ordering::Less => self.values[condensed_index(self.n, i, j)],
+            std::cmp::Ordering::Greater => self.values[condensed_index(self.n, j, i)],
+        }
+    }
+
+    /// Error message if the matrix could not be computed.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+#[cfg(test)]
+mod distance_matrix_tests {
+    use super::*;
+    use crate::{minhash_signature, minhash_signature_canonical};
+
+    #[test]
+    fn test_condensed_index_layout() {
+        // n = 4: (0,1)(0,2)(0,3)(1,2)(1,3)(2,3)
+        let n = 4;
+        let order: Vec<usize> = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]
+            .iter()
+            .map(|&(i, j)| condensed_index(n, i, j))
+            .collect();
+        assert_eq!(order, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(condensed_len(n), 6);
+    }
+
+    #[test]
+    fn test_signature_metrics() {
+        let a = b"ATGCGTACGTTAGCCGATAGGCTTACGATCGATCGGATCCATTGACCAGTACG";
+        let mut b = a.to_vec();
+        b[20] = b'A';
+        let c = b"TTTTTTTTTTTTTCCCCCCCCCCCGGGGGGGGGGGGAAAAAAAAAAAA";
+
+        let mut collection = GenomeCollection::new(11);
+        for (name, seq) in [("a", &a[..]), ("b", &b[..]), ("c", &c[..])] {
+            let sig = minhash_signature_canonical(seq, 11, 128);
+            assert!(collection.add(name, &sig, &[]) >= 0);
+        }
+        // Signatures must match the collection's k, strand mode and seed.
+        let wrong_k = minhash_signature_canonical(a, 9, 128);
+        assert_eq!(collection.add("k9", &wrong_k, &[]), -1);
+        let forward = minhash_signature(a, 11, 128);
+        assert_eq!(collection.add("fwd", &forward, &[]), -1);
+        assert_eq!(collection.len(), 3);
+
+        let jaccard = collection.distance_matrix(DistanceMetric::Jaccard);
+        assert!(jaccard.error.is_none());
+        assert_eq!(jaccard.values.len(), 3);
+        assert!(jaccard.get(0, 1) > 0.0 && jaccard.get(0, 1) < 1.0);
+        assert_eq!(jaccard.get(1, 0), jaccard.get(0, 1));
+        assert!((jaccard.get(0, 2) - 1.0).abs() < 1e-6);
+
+        let mash = collection.distance_matrix(DistanceMetric::Mash);
+        assert!(mash.get(0, 1) < mash.get(0, 2));
+
+        // Profile metrics need profiles.
+        let jsd = collection.distance_matrix(DistanceMetric::Jsd);
+        assert!(jsd.error.is_some());
+    }
+
+    #[test]
+    fn test_profile_metrics_and_incremental_steps() {
+        let mut collection = GenomeCollection::new(2);
+        collection.add_profile("x", &[1.0, 0.0, 0.0, 1.0]);
+        collection.add_profile("y", &[2.0, 0.0, 0.0, 2.0]);
+        collection.add_profile("z", &[0.0, 1.0, 1.0, 0.0]);
+        collection.add_profile("w", &[1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(collection.add_profile("bad", &[1.0]), -1);
+
+        let cosine = collection.distance_matrix(DistanceMetric::Cosine);
+        assert!(cosine.get(0, 1).abs() < 1e-6);
+        assert!((cosine.get(0, 2) - 1.0).abs() < 1e-6);
+
+        let mut jsd = collection.start_distance_matrix(DistanceMetric::Jsd);
+        assert_eq!(jsd.progress(), 0.0);
+        assert!((jsd.step(&collection, 4) - 4.0 / 6.0).abs() < 1e-12);
+        assert!(!jsd.is_complete());
+        assert_eq!(jsd.step(&collection, 100), 1.0);
+        assert!(jsd.is_complete());
+        assert!(jsd.get(0, 1).abs() < 1e-6);
+        assert!((jsd.get(0, 2) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_collection_genome_cap() {
+        let mut collection = GenomeCollection::new(11);
+        let sig = minhash_signature_canonical(b"ACGTACGTACGTACGT", 11, 1);
+        for _ in 0..COLLECTION_MAX_GENOMES {
+            assert!(collection.add("g", &sig, &[]) >= 0);
+        }
+        assert_eq!(collection.add("over", &sig, &[]), -1);
+        assert_eq!(collection.len(), COLLECTION_MAX_GENOMES);
+    }
+}
@@ -0,0 +1,438 @@
+//! Fast edit distance kernels for nucleotide sequences.
+//!
+//! `levenshtein_distance` runs a full O(n·m) DP over `char`s, which is too slow
+//! for gene-sized inputs. These kernels work on bytes instead:
+//! - Myers/Hyyrö bit-parallel edit distance (O(n·⌈m/64⌉))
+//! - Banded DP with an early exit once the distance is known to exceed `k`
+//! - k-differences approximate search (all occurrences with ≤ k edits)
+//!
+//! Inputs may be ASCII (case-insensitive, U treated as T) or already-encoded
+//! ACGT05 codes (0..=4). N and other ambiguous bases never match anything,
+//! including another N, consistent with `myers_diff`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{encode_base, SEQ_BASE_N};
+
+/// Number of symbols in the match tables (A, C, G, T, N).
+const EDIT_ALPHABET: usize = 5;
+
+/// Map an ASCII or pre-encoded byte to a symbol index (0..=4).
+#[inline(always)]
+fn edit_symbol(raw: u8) -> usize {
+    if raw <= SEQ_BASE_N {
+        raw as usize
+    } else {
+        encode_base(raw) as usize
+    }
+}
+
+/// Pattern preprocessed into 64-bit match bitmasks (Peq) per symbol and block.
+struct BitPattern {
+    /// `peq[symbol * blocks + block]`; N has no bits set so it never matches.
+    peq: Vec<u64>,
+    blocks: usize,
+    len: usize,
+}
+
+impl BitPattern {
+    fn new(pattern: &[u8]) -> BitPattern {
+        let len = pattern.len();
+        let blocks = len.div_ceil(64).max(1);
+        let mut peq = vec![0u64; EDIT_ALPHABET * blocks];
+        for (i, &raw) in pattern.iter().enumerate() {
+            let sym = edit_symbol(raw);
+            if sym < SEQ_BASE_N as usize {
+                peq[sym * blocks + i / 64] |= 1u64 << (i % 64);
+            }
+        }
+        BitPattern { peq, blocks, len }
+    }
+
+    /// Run the column recurrence over `text`, calling `on_column(j, score)`
+    /// with the score D[m][j + 1] after each column. Stops early when the
+    /// callback returns false.
+    ///
+    /// `global` fixes the first row to D[0][j] = j (global alignment);
+    /// otherwise D[0][j] = 0 so the pattern may start anywhere in the text.
+    fn scan(&self, text: &[u8], global: bool, mut on_column: impl FnMut(usize, usize) -> bool) {
+        let blocks = self.blocks;
+        let mut pv = vec![u64::MAX; blocks];
+        let mut mv = vec![0u64; blocks];
+        let last_high = 1u64 << ((self.len.max(1) - 1) % 64);
+        let mut score = self.len as isize;
+
+        for (j, &raw) in text.iter().enumerate() {
+            let sym = edit_symbol(raw);
+            let mut carry: i32 = if global { 1 } else { 0 };
+            for b in 0..blocks {
+                let high = if b + 1 == blocks {
+                    last_high
+                } else {
+                    1u64 << 63
+                };
+                carry = advance_block(
+                    &mut pv[b],
+                    &mut mv[b],
+                    self.peq[sym * blocks + b],
+                    carry,
+                    high,
+                );
+            }
+            score += carry as isize;
+            if !on_column(j, score.max(0) as usize) {
+                return;
+            }
+        }
+    }
+}
+
+/// Advance one 64-row block by one text column (Hyyrö's formulation of
+/// Myers' bit-vector algorithm). `hin` is the horizontal delta entering the
+/// top of the block; the return value is the delta leaving row `high`.
+#[inline(always)]
+fn advance_block(pv: &mut u64, mv: &mut u64, eq: u64, hin: i32, high: u64) -> i32 {
+    let mut eq = eq;
+    let xv = eq | *mv;
+    if hin < 0 {
+        eq |= 1;
+    }
+    let xh = ((eq & *pv).wrapping_add(*pv) ^ *pv) | eq;
+    let mut ph = *mv | !(xh | *pv);
+    let mut mh = *pv & xh;
+
+    let hout = if ph & high != 0 {
+        1
+    } else if mh & high != 0 {
+        -1
+    } else {
+        0
+    };
+
+    ph <<= 1;
+    mh <<= 1;
+    if hin < 0 {
+        mh |= 1;
+    } else if hin > 0 {
+        ph |= 1;
+    }
+
+    *pv = mh | !(xv | ph);
+    *mv = ph & xv;
+    hout
+}
+
+/// Compute the edit (Levenshtein) distance with the bit-parallel algorithm.
+///
+/// O(n·⌈m/64⌉) time and O(m/64) memory, where m is the shorter sequence.
+///
+/// # Arguments
+/// * `seq_a` - First sequence (ASCII or ACGT05 codes)
+/// * `seq_b` - Second sequence (ASCII or ACGT05 codes)
+///
+/// # Returns
+/// Number of substitutions, insertions and deletions needed to turn
+/// `seq_a` into `seq_b`. N never matches.
+#[wasm_bindgen]
+pub fn edit_distance_bitparallel(seq_a: &[u8], seq_b: &[u8]) -> usize {
+    // The pattern (bit-vector side) should be the shorter sequence.
+    let (pattern, text) = if seq_a.len() <= seq_b.len() {
+        (seq_a, seq_b)
+    } else {
+        (seq_b, seq_a)
+    };
+    if pattern.is_empty() {
+        return text.len();
+    }
+
+    let bp = BitPattern::new(pattern);
+    let mut distance = pattern.len();
+    bp.scan(text, true, |_, score| {
+        distance = score;
+        true
+    });
+    distance
+}
+
+/// Compute the edit distance if it is at most `max_k`.
+///
+/// Banded DP restricted to diagonals |i - j| <= max_k, O((2k+1)·n) time and
+/// O(k) memory. Stops as soon as every cell in a row exceeds `max_k`, so
+/// dissimilar sequences are rejected quickly.
+///
+/// # Arguments
+/// * `seq_a` - First sequence (ASCII or ACGT05 codes)
+/// * `seq_b` - Second sequence (ASCII or ACGT05 codes)
+/// * `max_k` - Distance threshold
+///
+/// # Returns
+/// The edit distance, or -1 if it is greater than `max_k`.
+#[wasm_bindgen]
+pub fn edit_distance_banded(seq_a: &[u8], seq_b: &[u8], max_k: usize) -> i32 {
+    let n = seq_a.len();
+    let m = seq_b.len();
+    if n.abs_diff(m) > max_k {
+        return -1;
+    }
+    if n == 0 || m == 0 {
+        return n.max(m) as i32;
+    }
+
+    // The distance never exceeds max(n, m), so a wider band only costs memory.
+    // Row i covers columns j in [i - k, i + k]; band index = j - i + k.
+    let k = max_k.min(n.max(m));
+    let width = 2 * k + 1;
+    let over = k + 1; // Any value > k behaves the same.
+    let mut prev = vec![over; width];
+    let mut curr = vec![over; width];
+
+    // Row 0: D[0][j] = j for j <= k.
+    for j in 0..=k.min(m) {
+        prev[j + k] = j;
+    }
+
+    let b_syms: Vec<usize> = seq_b.iter().map(|&b| edit_symbol(b)).collect();
+
+    for i in 1..=n {
+        let a_sym = edit_symbol(seq_a[i - 1]);
+        let j_lo = i.saturating_sub(k);
+        let j_hi = (i + k).min(m);
+        let mut row_min = over;
+
+        curr.iter_mut().for_each(|c| *c = over);
+        for j in j_lo..=j_hi {
+            let d = j + k - i;
+            let value = if j == 0 {
+                i
+            } else {
+                let mismatch = a_sym == SEQ_BASE_N as usize || a_sym != b_syms[j - 1];
+                // Diagonal (i-1, j-1) shares the band index in the previous row.
+                let diag = prev[d] + usize::from(mismatch);
+                // Up (i-1, j) sits one band slot to the right in the previous row.
+                let up = if d + 1 < width { prev[d + 1] + 1 } else { over };
+                // Left (i, j-1) is one slot to the left in the current row.
+                let left = if d > 0 { curr[d - 1] + 1 } else { over };
+                diag.min(up).min(left)
+            };
+            let value = value.min(over);
+            curr[d] = value;
+            row_min = row_min.min(value);
+        }
+
+        if row_min > k {
+            return -1;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    let result = prev[m + k - n];
+    if result > k {
+        -1
+    } else {
+        result as i32
+    }
+}
+
+/// Result of approximate (k-differences) pattern search.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct ApproxMatchResult {
+    starts: Vec<u32>,
+    ends: Vec<u32>,
+    distances: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl ApproxMatchResult {
+    /// Match start positions in the text (inclusive) as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn starts(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.starts.len() as u32);
+        arr.copy_from(&self.starts);
+        arr
+    }
+
+    /// Match end positions in the text (exclusive) as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn ends(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.ends.len() as u32);
+        arr.copy_from(&self.ends);
+        arr
+    }
+
+    /// Edit distance of each match as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn distances(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.distances.len() as u32);
+        arr.copy_from(&self.distances);
+        arr
+    }
+
+    /// Number of matches.
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> usize {
+        self.ends.len()
+    }
+}
+
+/// Find all occurrences of `pattern` in `text` with at most `max_k` edits.
+///
+/// Uses the bit-parallel recurrence in semi-global mode (the pattern may
+/// start anywhere in the text). Consecutive end positions that all satisfy
+/// the threshold describe the same occurrence, so each such run is reported
+/// once at its lowest-distance end. The start of each occurrence is then
+/// recovered by aligning the reversed pattern back from that end, choosing
+/// the shortest span that achieves the reported distance.
+///
+/// # Arguments
+/// * `pattern` - Pattern sequence (ASCII or ACGT05 codes)
+/// * `text` - Text sequence to search (ASCII or ACGT05 codes)
+/// * `max_k` - Maximum number of edits per occurrence
+///
+/// # Returns
+/// `ApproxMatchResult` with start/end/distance arrays sorted by position.
+#[wasm_bindgen]
+pub fn approximate_pattern_search(pattern: &[u8], text: &[u8], max_k: usize) -> ApproxMatchResult {
+    let mut result = ApproxMatchResult {
+        starts: Vec::new(),
+        ends: Vec::new(),
+        distances: Vec::new(),
+    };
+    if pattern.is_empty() || text.is_empty() || max_k >= pattern.len() {
+        // With k >= m every position matches trivially; treat as invalid.
+        return result;
+    }
+
+    let bp = BitPattern::new(pattern);
+
+    // (end_exclusive, distance) of the best hit in the current run.
+    let mut run_best: Option<(usize, usize)> = None;
+    let mut hits: Vec<(usize, usize)> = Vec::new();
+    bp.scan(text, false, |j, score| {
+        if score <= max_k {
+            match run_best {
+                Some((_, best)) if best <= score => {}
+                _ => run_best = Some((j + 1, score)),
+            }
+        } else if let Some(hit) = run_best.take() {
+            hits.push(hit);
+        }
+        true
+    });
+    if let Some(hit) = run_best {
+        hits.push(hit);
+    }
+
+    let reversed_pattern: Vec<u8> = pattern.iter().rev().copied().collect();
+    let rev_bp = BitPattern::new(&reversed_pattern);
+
+    for (end, distance) in hits {
+        let window_start = end.saturating_sub(pattern.len() + max_k);
+        let window: Vec<u8> = text[window_start..end].iter().rev().copied().collect();
+
+        // Global on the text side: the alignment must end exactly at `end`.
+        let mut span = window.len();
+        rev_bp.scan(&window, true, |j, score| {
+            if score == distance {
+                span = j + 1;
+                return false;
+            }
+            true
+        });
+
+        result.starts.push((end - span) as u32);
+        result.ends.push(end as u32);
+        result.distances.push(distance as u32);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod edit_distance_tests {
+    use super::*;
+    use crate::levenshtein_distance;
+    use crate::rng::{random_dna, SplitMix64};
+
+    fn mutate(seq: &[u8], edits: usize, seed: u64) -> Vec<u8> {
+        let mut out = seq.to_vec();
+        let mut rng = SplitMix64::new(seed);
+        for _ in 0..edits {
+            let pos = rng.below(out.len().max(1));
+            let base = b"ACGT"[rng.below(4)];
+            match rng.below(3) {
+                0 => out[pos] = base,
+                1 => out.insert(pos, base),
+                _ => {
+                    if out.len() > 1 {
+                        out.remove(pos);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_bitparallel_matches_levenshtein() {
+        // Covers single-block and multi-block (>64) patterns.
+        for (len, edits, seed) in [
+            (10, 3, 1),
+            (63, 8, 2),
+            (64, 10, 3),
+            (150, 25, 4),
+            (300, 60, 5),
+        ] {
+            let a = random_dna(len, seed);
+            let b = mutate(&a, edits, seed + 100);
+            let expected = levenshtein_distance(
+                std::str::from_utf8(&a).unwrap(),
+                std::str::from_utf8(&b).unwrap(),
+            );
+            assert_eq!(edit_distance_bitparallel(&a, &b), expected, "len={}", len);
+            assert_eq!(edit_distance_bitparallel(&b, &a), expected, "len={}", len);
+        }
+    }
+
+    #[test]
+    fn test_bitparallel_edge_cases() {
+        assert_eq!(edit_distance_bitparallel(b"", b""), 0);
+        assert_eq!(edit_distance_bitparallel(b"", b"ACGT"), 4);
+        assert_eq!(edit_distance_bitparallel(b"acgu", b"ACGT"), 0);
+        // Encoded input gives the same answer as ASCII.
+        assert_eq!(edit_distance_bitparallel(&[0, 1, 2, 3], b"ACGT"), 0);
+        // N never matches, not even N.
+        assert_eq!(edit_distance_bitparallel(b"ANA", b"ANA"), 1);
+    }
+
+    #[test]
+    fn test_banded_agrees_within_threshold() {
+        let a = random_dna(200, 7);
+        let b = mutate(&a, 12, 8);
+        let exact = edit_distance_bitparallel(&a, &b);
+        assert_eq!(edit_distance_banded(&a, &b, exact), exact as i32);
+        assert_eq!(edit_distance_banded(&a, &b, exact + 5), exact as i32);
+        assert_eq!(edit_distance_banded(&a, &b, exact - 1), -1);
+        // Length difference alone exceeds the threshold.
+        assert_eq!(edit_distance_banded(b"ACGTACGT", b"AC", 3), -1);
+        // An unbounded threshold must not size the band from max_k.
+        assert_eq!(edit_distance_banded(&a, &b, usize::MAX), exact as i32);
+    }
+
+    #[test]
+    fn test_approximate_search_finds_occurrences() {
+        //            0         1         2         3
+        //            0123456789012345678901234567890123
+        let text = b"TTTTGATTACATTTTTTGATCACATTTTGATTACA";
+        let result = approximate_pattern_search(b"GATTACA", text, 1);
+        assert_eq!(result.count(), 3);
+        assert_eq!(result.starts, vec![4, 17, 28]);
+        assert_eq!(result.ends, vec![11, 24, 35]);
+        assert_eq!(result.distances, vec![0, 1, 0]);
+
+        let exact_only = approximate_pattern_search(b"GATTACA", text, 0);
+        assert_eq!(exact_only.starts, vec![4, 28]);
+    }
+}
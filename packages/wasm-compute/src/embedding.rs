@@ -0,0 +1,181 @@
+//! Shared input handling for the nonlinear embeddings (t-SNE, UMAP) and
+//! the flat clusterings (k-means/k-medoids, DBSCAN, HDBSCAN).
+//!
+//! Points come either as a feature matrix (Euclidean distances, e.g. k-mer
+//! profiles or PCA scores) or as a precomputed condensed distance matrix
+//! (e.g. Mash distances from `CondensedDistanceMatrix`). Neighbor search is
+//! exact brute force, O(n² · d), which is fine for the few thousand genomes
+//! the atlas plots.
+
+use wasm_bindgen::JsValue;
+
+use crate::distance_matrix::condensed_index;
+use crate::phylo::taxa_from_condensed;
+
+/// Maximum number of points for an embedding or clustering.
+pub(crate) const EMBED_MAX_POINTS: usize = 20_000;
+
+enum Points {
+    Features { data: Vec<f32>, dim: usize },
+    Distances(Vec<f32>),
+}
+
+/// Points to embed, with pairwise distance access.
+pub(crate) struct PointSet {
+    n: usize,
+    points: Points,
+}
+
+impl PointSet {
+    /// Row-major feature matrix (n × dim).
+    pub(crate) fn from_features(data: &[f32], n: usize, dim: usize) -> Result<PointSet, String> {
+        if dim == 0 || n.checked_mul(dim) != Some(data.len()) {
+            return Err(format!(
+                "data length {} does not match {} × {}",
+                data.len(),
+                n,
+                dim
+            ));
+        }
+        PointSet::checked(
+            n,
+            Points::Features {
+                data: data.to_vec(),
+                dim,
+            },
+        )
+    }
+
+    /// Condensed distance matrix (SciPy order).
+    pub(crate) fn from_condensed(condensed: &[f32]) -> Result<PointSet, String> {
+        let n = taxa_from_condensed(condensed.len())
+            .ok_or_else(|| format!("{} is not a valid condensed matrix length", condensed.len()))?;
+        if condensed.iter().any(|d| !d.is_finite() || *d < 0.0) {
+            return Err("distances must be finite and non-negative".to_string());
+        }
+        PointSet::checked(n, Points::Distances(condensed.to_vec()))
+    }
+
+    fn checked(n: usize, points: Points) -> Result<PointSet, String> {
+        if n < 3 {
+            return Err(format!("need at least 3 points (got {})", n));
+        }
+        if n > EMBED_MAX_POINTS {
+            return Err(format!(
+                "at most {} points supported (got {})",
+                EMBED_MAX_POINTS, n
+            ));
+        }
+        Ok(PointSet { n, points })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Row-major features and their dimension, if built from features.
+    pub(crate) fn features(&self) -> Option<(&[f32], usize)> {
+        match &self.points {
+            Points::Features { data, dim } => Some((data, *dim)),
+            Points::Distances(_) => None,
+        }
+    }
+
+    /// Distance between points `i` and `j`.
+    pub(crate) fn distance(&self, i: usize, j: usize) -> f64 {
+        match &self.points {
+            Points::Features { data, dim } => {
+                let a = &data[i * dim..(i + 1) * dim];
+                let b = &data[j * dim..(j + 1) * dim];
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(&x, &y)| {
+                        let d = x as f64 - y as f64;
+                        d * d
+                    })
+                    .sum::<f64>()
+                    .sqrt()
+            }
+            Points::Distances(condensed) => match i.cmp(&j) {
+                std::cmp::Ordering::Equal => 0.0,
+                std::cmp::Ordering::Less => condensed[condensed_index(self.n, i, j)] as f64,
+                std::cmp::Ordering::Greater => condensed[condensed_index(self.n, j, i)] as f64,
+            },
+        }
+    }
+
+    /// The `k` nearest neighbors of every point (self excluded), nearest
+    /// first, ties broken by index.
+    pub(crate) fn knn(&self, k: usize) -> Vec<Vec<(usize, f64)>> {
+        let k = k.min(self.n - 1);
+        let order = |a: &(usize, f64), b: &(usize, f64)| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0));
+        (0..self.n)
+            .map(|i| {
+                let mut row: Vec<(usize, f64)> = (0..self.n)
+                    .filter(|&j| j != i)
+                    .map(|j| (j, self.distance(i, j)))
+                    .collect();
+                if k < row.len() {
+                    row.select_nth_unstable_by(k, order);
+                    row.truncate(k);
+                }
+                row.sort_unstable_by(order);
+                row
+            })
+            .collect()
+    }
+}
+
+/// 2-D coordinates as a Float32Array (x0, y0, x1, y1, ...).
+pub(crate) fn coords_array(coords: &[f64]) -> js_sys::Float32Array {
+    let values: Vec<f32> = coords.iter().map(|&v| v as f32).collect();
+    let arr = js_sys::Float32Array::new_with_length(values.len() as u32);
+    arr.copy_from(&values);
+    arr
+}
+
+/// Call a layout frame callback as `callback(coords, step)`, with the
+/// coordinates as in `coords_array`; a thrown exception is passed back.
+pub(crate) fn emit_frame(
+    callback: &js_sys::Function,
+    coords: &[f64],
+    step: usize,
+) -> Result<(), JsValue> {
+    callback
+        .call2(
+            &JsValue::NULL,
+            &coords_array(coords),
+            &JsValue::from(step as f64),
+        )
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod embedding_tests {
+    use super::*;
+
+    #[test]
+    fn test_knn_features_and_distances_agree() {
+        let data: Vec<f32> = vec![0.0, 0.0, 1.0, 0.0, 0.0, 2.0, 5.0, 5.0];
+        let features = PointSet::from_features(&data, 4, 2).unwrap();
+        let mut condensed = Vec::new();
+        for i in 0..4 {
+            for j in (i + 1)..4 {
+                condensed.push(features.distance(i, j) as f32);
+            }
+        }
+        let distances = PointSet::from_condensed(&condensed).unwrap();
+        let a = features.knn(2);
+        let b = distances.knn(2);
+        for (ra, rb) in a.iter().zip(b.iter()) {
+            let ia: Vec<usize> = ra.iter().map(|&(j, _)| j).collect();
+            let ib: Vec<usize> = rb.iter().map(|&(j, _)| j).collect();
+            assert_eq!(ia, ib);
+        }
+        assert_eq!(a[0], vec![(1, 1.0), (2, 2.0)]);
+        assert!(PointSet::from_condensed(&[1.0, 2.0]).is_err());
+        assert!(PointSet::from_features(&data, 3, 2).is_err());
+        // 4 + 2^63 rows of 2 wrap around to data.len().
+        assert!(PointSet::from_features(&data, 4 + (1usize << 63), 2).is_err());
+    }
+}
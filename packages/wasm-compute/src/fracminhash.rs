@@ -0,0 +1,505 @@
+//! FracMinHash (scaled) sketches with containment and ANI estimation.
+//!
+//! Bottom-k MinHash keeps a fixed number of hashes per genome, so Jaccard
+//! between a 40 kb prophage and a 4 Mb host is dominated by the size ratio.
+//! FracMinHash keeps every canonical k-mer hash below `u64::MAX / scaled`,
+//! making sketch size proportional to genome size and containment
+//! `|A ∩ B| / |A|` directly estimable (sourmash-style).
+
+use wasm_bindgen::prelude::*;
+
+use crate::{encode_base, SequenceHandle, SEQ_BASE_N};
+
+/// Default confidence level used when an invalid one is passed.
+const FRAC_DEFAULT_CONFIDENCE: f64 = 0.95;
+
+/// 64-bit splitmix finalizer over a k-mer index.
+#[inline(always)]
+pub(crate) fn frac_hash(index: u64, seed: u64) -> u64 {
+    let mut x = index ^ seed;
+    x = x.wrapping_mul(0x9E3779B97F4A7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+    x ^ (x >> 31)
+}
+
+#[inline(always)]
+fn frac_threshold(scaled: u64) -> u64 {
+    u64::MAX / scaled.max(1)
+}
+
+/// A FracMinHash sketch: sorted, de-duplicated canonical k-mer hashes.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct FracMinHashSketch {
+    hashes: Vec<u64>,
+    k: usize,
+    scaled: u64,
+    seed: u64,
+    total_kmers: u64,
+}
+
+impl FracMinHashSketch {
+    /// Sketch an encoded sequence (A=0, C=1, G=2, T=3, N=4).
+    fn from_encoded(encoded: &[u8], k: usize, scaled: u64, seed: u64) -> FracMinHashSketch {
+        let k = k.min(32);
+        let scaled = scaled.max(1);
+        let mut sketch = FracMinHashSketch {
+            hashes: Vec::new(),
+            k,
+            scaled,
+            seed,
+            total_kmers: 0,
+        };
+        if k == 0 || encoded.len() < k {
+            return sketch;
+        }
+
+        let threshold = frac_threshold(scaled);
+        let mask: u64 = if k == 32 {
+            u64::MAX
+        } else {
+            (1u64 << (2 * k)) - 1
+        };
+        let rc_shift = 2 * (k as u64 - 1);
+        let mut fwd: u64 = 0;
+        let mut rc: u64 = 0;
+        let mut valid = 0usize;
+
+        for &code in encoded {
+            if code >= SEQ_BASE_N {
+                valid = 0;
+                fwd = 0;
+                rc = 0;
+                continue;
+            }
+            fwd = ((fwd << 2) | code as u64) & mask;
+            rc = (rc >> 2) | ((3 - code as u64) << rc_shift);
+            valid += 1;
+            if valid >= k {
+                sketch.total_kmers += 1;
+                let h = frac_hash(fwd.min(rc), seed);
+                if h <= threshold {
+                    sketch.hashes.push(h);
+                }
+            }
+        }
+
+        sketch.hashes.sort_unstable();
+        sketch.hashes.dedup();
+        sketch
+    }
+
+    /// Hashes retained at a coarser `scaled` (must be >= self.scaled).
+    fn downsampled(&self, scaled: u64) -> &[u64] {
+        let threshold = frac_threshold(scaled);
+        let end = self.hashes.partition_point(|&h| h <= threshold);
+        &self.hashes[..end]
+    }
+}
+
+#[wasm_bindgen]
+impl FracMinHashSketch {
+    /// Retained hashes (sorted ascending) as BigUint64Array.
+    #[wasm_bindgen(getter)]
+    pub fn hashes(&self) -> js_sys::BigUint64Array {
+        let arr = js_sys::BigUint64Array::new_with_length(self.hashes.len() as u32);
+        arr.copy_from(&self.hashes);
+        arr
+    }
+
+    /// Number of retained hashes.
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.hashes.len()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn scaled(&self) -> u64 {
+        self.scaled
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Total number of valid k-mers hashed.
+    #[wasm_bindgen(getter)]
+    pub fn total_kmers(&self) -> u64 {
+        self.total_kmers
+    }
+
+    /// Estimated number of distinct k-mers (size × scaled).
+    #[wasm_bindgen(getter)]
+    pub fn estimated_distinct_kmers(&self) -> f64 {
+        self.hashes.len() as f64 * self.scaled as f64
+    }
+}
+
+/// Compute a FracMinHash sketch from sequence bytes.
+///
+/// # Arguments
+/// * `seq` - Sequence bytes (ASCII). Case-insensitive, U treated as T.
+/// * `k` - K-mer size (capped at 32); canonical k-mers are hashed
+/// * `scaled` - Keep hashes below 2^64 / scaled (e.g. 1000; 1 keeps all)
+/// * `seed` - Hash seed
+#[wasm_bindgen]
+pub fn frac_minhash_sketch(seq: &[u8], k: usize, scaled: u64, seed: u64) -> FracMinHashSketch {
+    let encoded: Vec<u8> = seq.iter().map(|&b| encode_base(b)).collect();
+    FracMinHashSketch::from_encoded(&encoded, k, scaled, seed)
+}
+
+#[wasm_bindgen]
+impl SequenceHandle {
+    /// Compute a FracMinHash sketch from the pre-encoded sequence.
+    ///
+    /// # Arguments
+    /// * `k` - K-mer size (capped at 32); canonical k-mers are hashed
+    /// * `scaled` - Keep hashes below 2^64 / scaled
+    /// * `seed` - Hash seed
+    pub fn frac_minhash(&self, k: usize, scaled: u64, seed: u64) -> FracMinHashSketch {
+        FracMinHashSketch::from_encoded(&self.encoded, k, scaled, seed)
+    }
+}
+
+/// Result of comparing two FracMinHash sketches.
+///
+/// ANI values are point estimates with confidence bounds derived from a
+/// Wilson score interval on the sampled containment (Jaccard for the
+/// Mash-style estimate), mapped through the same monotone transform.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct FracMinHashComparison {
+    shared: usize,
+    size_a: usize,
+    size_b: usize,
+    k: usize,
+    confidence: f64,
+    error: Option<String>,
+}
+
+impl FracMinHashComparison {
+    fn containment_of(&self, size: usize) -> f64 {
+        if size == 0 {
+            0.0
+        } else {
+            self.shared as f64 / size as f64
+        }
+    }
+
+    fn union(&self) -> usize {
+        self.size_a + self.size_b - self.shared
+    }
+
+    /// Wilson interval for a binomial proportion `hits / n`.
+    fn wilson(&self, hits: usize, n: usize) -> (f64, f64) {
+        if n == 0 {
+            return (0.0, 0.0);
+        }
+        let z = normal_quantile(0.5 + self.confidence / 2.0);
+        let n = n as f64;
+        let p = hits as f64 / n;
+        let denom = 1.0 + z * z / n;
+        let center = (p + z * z / (2.0 * n)) / denom;
+        let half = z * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt() / denom;
+        ((center - half).max(0.0), (center + half).min(1.0))
+    }
+
+    fn max_containment_sample(&self) -> (usize, usize) {
+        (self.shared, self.size_a.min(self.size_b))
+    }
+}
+
+#[wasm_bindgen]
+impl FracMinHashComparison {
+    /// Number of shared hashes.
+    #[wasm_bindgen(getter)]
+    pub fn shared(&self) -> usize {
+        self.shared
+    }
+
+    /// Estimated Jaccard |A ∩ B| / |A ∪ B|.
+    #[wasm_bindgen(getter)]
+    pub fn jaccard(&self) -> f64 {
+        let union = self.union();
+        if union == 0 {
+            0.0
+        } else {
+            self.shared as f64 / union as f64
+        }
+    }
+
+    /// Containment of A in B: |A ∩ B| / |A|.
+    #[wasm_bindgen(getter)]
+    pub fn containment_a(&self) -> f64 {
+        self.containment_of(self.size_a)
+    }
+
+    /// Containment of B in A: |A ∩ B| / |B|.
+    #[wasm_bindgen(getter)]
+    pub fn containment_b(&self) -> f64 {
+        self.containment_of(self.size_b)
+    }
+
+    /// Max containment: shared / min(|A|, |B|).
+    #[wasm_bindgen(getter)]
+    pub fn max_containment(&self) -> f64 {
+        self.containment_of(self.size_a.min(self.size_b))
+    }
+
+    /// ANI from max containment: C^(1/k).
+    #[wasm_bindgen(getter)]
+    pub fn ani(&self) -> f64 {
+        containment_to_ani(self.max_containment(), self.k)
+    }
+
+    /// Lower confidence bound of `ani`.
+    #[wasm_bindgen(getter)]
+    pub fn ani_low(&self) -> f64 {
+        let (hits, n) = self.max_containment_sample();
+        containment_to_ani(self.wilson(hits, n).0, self.k)
+    }
+
+    /// Upper confidence bound of `ani`.
+    #[wasm_bindgen(getter)]
+    pub fn ani_high(&self) -> f64 {
+        let (hits, n) = self.max_containment_sample();
+        containment_to_ani(self.wilson(hits, n).1, self.k)
+    }
+
+    /// Mash-style ANI from Jaccard: 1 - D, D = -1/k · ln(2J / (1 + J)).
+    #[wasm_bindgen(getter)]
+    pub fn ani_jaccard(&self) -> f64 {
+        jaccard_to_ani(self.jaccard(), self.k)
+    }
+
+    /// Lower confidence bound of `ani_jaccard`.
+    #[wasm_bindgen(getter)]
+    pub fn ani_jaccard_low(&self) -> f64 {
+        jaccard_to_ani(self.wilson(self.shared, self.union()).0, self.k)
+    }
+
+    /// Upper confidence bound of `ani_jaccard`.
+    #[wasm_bindgen(getter)]
+    pub fn ani_jaccard_high(&self) -> f64 {
+        jaccard_to_ani(self.wilson(self.shared, self.union()).1, self.k)
+    }
+
+    /// Confidence level used for the bounds.
+    #[wasm_bindgen(getter)]
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+
+    /// Error message if the sketches are incompatible.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// ANI implied by containment under a uniform point-mutation model.
+fn containment_to_ani(containment: f64, k: usize) -> f64 {
+    if containment <= 0.0 || k == 0 {
+        return 0.0;
+    }
+    containment.min(1.0).powf(1.0 / k as f64)
+}
+
+/// ANI implied by Jaccard via the Mash distance.
+fn jaccard_to_ani(jaccard: f64, k: usize) -> f64 {
+    if jaccard <= 0.0 || k == 0 {
+        return 0.0;
+    }
+    let d = -(2.0 * jaccard / (1.0 + jaccard)).ln() / k as f64;
+    (1.0 - d).clamp(0.0, 1.0)
+}
+
+/// Inverse standard normal CDF (Acklam's rational approximation,
+/// relative error < 1.2e-9).
+pub(crate) fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -normal_quantile(1.0 - p)
+    }
+}
+
+/// Compare two FracMinHash sketches.
+///
+/// Sketches with different `scaled` are compared at the coarser one. They
+/// must share `k` and `seed`; otherwise `error` is set and all estimates are 0.
+///
+/// # Arguments
+/// * `a` - First sketch (e.g. prophage or partial contig)
+/// * `b` - Second sketch (e.g. host or complete genome)
+/// * `confidence` - Confidence level for ANI bounds in (0, 1); defaults to 0.95
+#[wasm_bindgen]
+pub fn frac_minhash_compare(
+    a: &FracMinHashSketch,
+    b: &FracMinHashSketch,
+    confidence: f64,
+) -> FracMinHashComparison {
+    let confidence = if confidence > 0.0 && confidence < 1.0 {
+        confidence
+    } else {
+        FRAC_DEFAULT_CONFIDENCE
+    };
+    let mut result = FracMinHashComparison {
+        shared: 0,
+        size_a: 0,
+        size_b: 0,
+        k: a.k,
+        confidence,
+        error: None,
+    };
+    if a.k != b.k || a.seed != b.seed {
+        result.error = Some(format!(
+            "Incompatible sketches: k {} vs {}, seed {} vs {}",
+            a.k, b.k, a.seed, b.seed
+        ));
+        return result;
+    }
+
+    let scaled = a.scaled.max(b.scaled);
+    let ha = a.downsampled(scaled);
+    let hb = b.downsampled(scaled);
+
+    // Merge-count the intersection of two sorted lists.
+    let (mut i, mut j, mut shared) = (0usize, 0usize, 0usize);
+    while i < ha.len() && j < hb.len() {
+        match ha[i].cmp(&hb[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    result.shared = shared;
+    result.size_a = ha.len();
+    result.size_b = hb.len();
+    result
+}
+
+#[cfg(test)]
+mod fracminhash_tests {
+    use super::*;
+    use crate::rng::random_dna;
+
+    #[test]
+    fn test_normal_quantile() {
+        assert!(normal_quantile(0.5).abs() < 1e-9);
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-5);
+        assert!((normal_quantile(0.01) + 2.326348).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_prophage_containment() {
+        let host = random_dna(200_000, 1);
+        let prophage = host[50_000..90_000].to_vec();
+        let a = frac_minhash_sketch(&prophage, 21, 100, 0);
+        let b = frac_minhash_sketch(&host, 21, 100, 0);
+        let cmp = frac_minhash_compare(&a, &b, 0.95);
+        assert!(cmp.error.is_none());
+        assert!((cmp.containment_a() - 1.0).abs() < 1e-12);
+        assert!((cmp.max_containment() - 1.0).abs() < 1e-12);
+        assert!(cmp.containment_b() < 0.35);
+        assert!((cmp.ani() - 1.0).abs() < 1e-12);
+        // Jaccard-based ANI is penalized by the size difference.
+        assert!(cmp.ani_jaccard() < cmp.ani());
+    }
+
+    #[test]
+    fn test_mutated_genome_ani_interval() {
+        let genome = random_dna(100_000, 2);
+        let mut mutated = genome.clone();
+        // ~1% substitutions.
+        for pos in (0..mutated.len()).step_by(100) {
+            mutated[pos] = if mutated[pos] == b'A' { b'C' } else { b'A' };
+        }
+        let a = frac_minhash_sketch(&genome, 21, 10, 0);
+        let b = frac_minhash_sketch(&mutated, 21, 10, 0);
+        let cmp = frac_minhash_compare(&a, &b, 0.95);
+        assert!(cmp.ani_low() <= cmp.ani() && cmp.ani() <= cmp.ani_high());
+        assert!(cmp.ani_low() < 0.99 + 0.002 && cmp.ani_high() > 0.99 - 0.002);
+        assert!((cmp.ani() - 0.99).abs() < 0.003);
+    }
+
+    #[test]
+    fn test_handle_matches_bytes_and_scaled_mismatch() {
+        let seq = random_dna(20_000, 3);
+        let handle = SequenceHandle::new(&seq);
+        let from_handle = handle.frac_minhash(15, 50, 7);
+        let from_bytes = frac_minhash_sketch(&seq, 15, 50, 7);
+        assert_eq!(from_handle.hashes, from_bytes.hashes);
+
+        // Coarser sketch of the same sequence: compared at scaled=200.
+        let coarse = frac_minhash_sketch(&seq, 15, 200, 7);
+        let cmp = frac_minhash_compare(&from_bytes, &coarse, 0.95);
+        assert!((cmp.jaccard() - 1.0).abs() < 1e-12);
+
+        let other_k = frac_minhash_sketch(&seq, 17, 50, 7);
+        assert!(frac_minhash_compare(&from_bytes, &other_k, 0.95)
+            .error
+            .is_some());
+    }
+}
@@ -0,0 +1,633 @@
+//! Approximate nearest-neighbor search with HNSW graphs.
+//!
+//! Vectors (k-mer profiles, PCA scores, protein embeddings) are inserted
+//! one at a time into a hierarchy of proximity graphs (Malkov & Yashunin
+//! 2018); queries descend greedily from the sparse top layer and finish
+//! with a beam search of width `ef` in the bottom layer, so top-k lookups
+//! take roughly O(log n) distance evaluations instead of n. Neighbor lists
+//! are pruned with the diversity heuristic. An index can be built offline,
+//! shipped as bytes with the static data, decoded in the browser and still
+//! accept new vectors.
+//!
+//! Node levels are derived from the seed and the node id, so the same
+//! inserts in the same order always give the same graph.
+//!
+//! # Binary layout (little-endian)
+//!
+//! `PXHN`, version 1:
+//! ```text
+//! magic[4] = "PXHN" | version u16 | metric u16
+//! dim u32 | m u32 | ef_construction u32 | seed u64
+//! count u32 | entry u32 (u32::MAX = empty)
+//! vectors f32 × count × dim
+//! per node: level u32 | per layer 0..=level: degree u32 | ids u32 × degree
+//! ```
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+use wasm_bindgen::prelude::*;
+
+use crate::rng::SplitMix64;
+use crate::sketch::ByteReader;
+
+const HNSW_MAGIC: &[u8; 4] = b"PXHN";
+const HNSW_VERSION: u16 = 1;
+const HNSW_NO_ENTRY: u32 = u32::MAX;
+
+/// Highest layer a node can be assigned to.
+const HNSW_MAX_LEVEL: usize = 16;
+/// Upper bound on `m` (layer 0 keeps up to 2m links).
+const HNSW_MAX_M: usize = 256;
+
+/// Distance between stored vectors.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VectorMetric {
+    /// 1 - cosine similarity (vectors are normalized on insert)
+    Cosine = 0,
+    /// Euclidean (L2) distance
+    Euclidean = 1,
+}
+
+impl VectorMetric {
+    fn from_u16(value: u16) -> Option<VectorMetric> {
+        match value {
+            0 => Some(VectorMetric::Cosine),
+            1 => Some(VectorMetric::Euclidean),
+            _ => None,
+        }
+    }
+}
+
+/// Graph node candidate ordered by distance (ties by id).
+#[derive(Clone, Copy, PartialEq)]
+struct Candidate {
+    dist: f32,
+    id: u32,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist
+            .total_cmp(&other.dist)
+            .then(self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// HNSW index over fixed-dimension float vectors.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct HnswIndex {
+    dim: usize,
+    metric: VectorMetric,
+    m: usize,
+    ef_construction: usize,
+    seed: u64,
+    /// Row-major vectors, unit length for cosine.
+    vectors: Vec<f32>,
+    /// Neighbor lists per node, per layer (layer 0 first).
+    links: Vec<Vec<Vec<u32>>>,
+    entry: Option<u32>,
+    error: Option<String>,
+}
+
+impl HnswIndex {
+    fn vector(&self, id: u32) -> &[f32] {
+        let start = id as usize * self.dim;
+        &self.vectors[start..start + self.dim]
+    }
+
+    /// Internal distance: squared L2 for Euclidean, 1 - dot for cosine.
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self.metric {
+            VectorMetric::Cosine => 1.0 - a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>(),
+            VectorMetric::Euclidean => a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>(),
+        }
+    }
+
+    fn prepare(&self, vector: &[f32]) -> Vec<f32> {
+        let mut v = vector.to_vec();
+        if self.metric == VectorMetric::Cosine {
+            let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                v.iter_mut().for_each(|x| *x /= norm);
+            }
+        }
+        v
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            2 * self.m
+        } else {
+            self.m
+        }
+    }
+
+    /// Level of node `id`: floor(-ln U / ln m), U drawn from (seed, id).
+    fn level_for(&self, id: u32) -> usize {
+        let mut rng = SplitMix64::new(self.seed ^ (id as u64).wrapping_mul(0xD1B5_4A32_D192_ED03));
+        let u = 1.0 - rng.next_f64();
+        ((-u.ln() / (self.m as f64).ln()) as usize).min(HNSW_MAX_LEVEL)
+    }
+
+    fn top_level(&self) -> usize {
+        self.entry
+            .map_or(0, |e| self.links[e as usize].len().saturating_sub(1))
+    }
+
+    /// Beam search in one layer; returns up to `ef` nearest, nearest first.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entries: &[Candidate],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<u32> = entries.iter().map(|c| c.id).collect();
+        let mut frontier: BinaryHeap<Reverse<Candidate>> =
+            entries.iter().map(|&c| Reverse(c)).collect();
+        let mut nearest: BinaryHeap<Candidate> = entries.iter().copied().collect();
+        while nearest.len() > ef {
+            nearest.pop();
+        }
+        while let Some(Reverse(current)) = frontier.pop() {
+            if nearest.len() >= ef && current.dist > nearest.peek().unwrap().dist {
+                break;
+            }
+            for &next in &self.links[current.id as usize][layer] {
+                if !visited.insert(next) {
+                    continue;
+                }
+                let candidate = Candidate {
+                    dist: self.distance(query, self.vector(next)),
+                    id: next,
+                };
+                if nearest.len() < ef || candidate < *nearest.peek().unwrap() {
+                    frontier.push(Reverse(candidate));
+                    nearest.push(candidate);
+                    if nearest.len() > ef {
+                        nearest.pop();
+                    }
+                }
+            }
+        }
+        nearest.into_sorted_vec()
+    }
+
+    /// Diversity heuristic: keep a candidate only if it is closer to the
+    /// base than to every neighbor kept so far; top up with the rest.
+    fn select_neighbors(&self, candidates: &[Candidate], count: usize) -> Vec<u32> {
+        let mut kept: Vec<Candidate> = Vec::with_capacity(count);
+        let mut pruned = Vec::new();
+        for &c in candidates {
+            if kept.len() == count {
+                break;
+            }
+            let v = self.vector(c.id);
+            if kept
+                .iter()
+                .all(|k| self.distance(v, self.vector(k.id)) > c.dist)
+            {
+                kept.push(c);
+            } else {
+                pruned.push(c);
+            }
+        }
+        kept.extend(pruned.into_iter().take(count - kept.len()));
+        kept.iter().map(|c| c.id).collect()
+    }
+
+    /// Greedy descent from the entry point to `layer`.
+    fn descend(&self, query: &[f32], layer: usize) -> Option<Vec<Candidate>> {
+        let entry = self.entry?;
+        let mut best = vec![Candidate {
+            dist: self.distance(query, self.vector(entry)),
+            id: entry,
+        }];
+        for l in (layer + 1..=self.top_level()).rev() {
+            best = self.search_layer(query, &best, 1, l);
+        }
+        Some(best)
+    }
+
+    fn insert_prepared(&mut self, vector: Vec<f32>) -> u32 {
+        let id = self.links.len() as u32;
+        let level = self.level_for(id);
+        self.vectors.extend_from_slice(&vector);
+        self.links.push(vec![Vec::new(); level + 1]);
+
+        let top = self.top_level();
+        let Some(mut entries) = self.descend(&vector, level) else {
+            self.entry = Some(id);
+            return id;
+        };
+        for layer in (0..=level.min(top)).rev() {
+            let candidates = self.search_layer(&vector, &entries, self.ef_construction, layer);
+            let neighbors = self.select_neighbors(&candidates, self.m);
+            for &n in &neighbors {
+                let list = &mut self.links[n as usize][layer];
+                list.push(id);
+                if list.len() > self.max_links(layer) {
+                    let base = self.vector(n).to_vec();
+                    let mut scored: Vec<Candidate> = self.links[n as usize][layer]
+                        .iter()
+                        .map(|&o| Candidate {
+                            dist: self.distance(&base, self.vector(o)),
+                            id: o,
+                        })
+                        .collect();
+                    scored.sort_unstable();
+                    self.links[n as usize][layer] =
+                        self.select_neighbors(&scored, self.max_links(layer));
+                }
+            }
+            self.links[id as usize][layer] = neighbors;
+            entries = candidates;
+        }
+        if level > top {
+            self.entry = Some(id);
+        }
+        id
+    }
+
+    pub(crate) fn search_ids(&self, query: &[f32], k: usize, ef: usize) -> Vec<(u32, f32)> {
+        if query.len() != self.dim || k == 0 {
+            return Vec::new();
+        }
+        let query = self.prepare(query);
+        let Some(entries) = self.descend(&query, 0) else {
+            return Vec::new();
+        };
+        let mut found = self.search_layer(&query, &entries, ef.max(k), 0);
+        found.truncate(k);
+        found
+            .into_iter()
+            .map(|c| {
+                let d = match self.metric {
+                    VectorMetric::Cosine => c.dist,
+                    VectorMetric::Euclidean => c.dist.max(0.0).sqrt(),
+                };
+                (c.id, d)
+            })
+            .collect()
+    }
+}
+
+#[wasm_bindgen]
+impl HnswIndex {
+    /// Create an empty index.
+    ///
+    /// # Arguments
+    /// * `dim` - Vector dimension
+    /// * `metric` - Cosine or Euclidean
+    /// * `m` - Links per node and layer (16 typical; layer 0 keeps 2m)
+    /// * `ef_construction` - Beam width while inserting (200 typical)
+    /// * `seed` - Seed for node levels
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        dim: usize,
+        metric: VectorMetric,
+        m: usize,
+        ef_construction: usize,
+        seed: u64,
+    ) -> HnswIndex {
+        let error = if dim == 0 {
+            Some("dim must be positive".to_string())
+        } else if !(2..=HNSW_MAX_M).contains(&m) {
+            Some(format!("m must be 2..={} (got {})", HNSW_MAX_M, m))
+        } else {
+            None
+        };
+        HnswIndex {
+            dim,
+            metric,
+            m,
+            ef_construction: ef_construction.max(m),
+            seed,
+            vectors: Vec::new(),
+            links: Vec::new(),
+            entry: None,
+            error,
+        }
+    }
+
+    /// Insert one vector; returns its id (insertion order), or undefined
+    /// if the length does not match `dim`.
+    pub fn insert(&mut self, vector: &[f32]) -> Option<u32> {
+        if self.error.is_some() || vector.len() != self.dim {
+            return None;
+        }
+        let prepared = self.prepare(vector);
+        Some(self.insert_prepared(prepared))
+    }
+
+    /// Insert a row-major batch of vectors; returns the number inserted
+    /// (0 if the length is not a multiple of `dim`).
+    pub fn insert_batch(&mut self, data: &[f32]) -> usize {
+        if self.error.is_some() || !data.len().is_multiple_of(self.dim) {
+            return 0;
+        }
+        for row in data.chunks_exact(self.dim) {
+            let prepared = self.prepare(row);
+            self.insert_prepared(prepared);
+        }
+        data.len() / self.dim
+    }
+
+    /// The `k` approximate nearest neighbors of `query`, nearest first.
+    ///
+    /// `ef` is the search beam width (>= k; larger is slower and more
+    /// exact, 50-100 typical). A query of the wrong length returns no hits.
+    pub fn search(&self, query: &[f32], k: usize, ef: usize) -> HnswSearchResult {
+        let hits = self.search_ids(query, k, ef);
+        HnswSearchResult {
+            ids: hits.iter().map(|&(id, _)| id).collect(),
+            distances: hits.iter().map(|&(_, d)| d).collect(),
+        }
+    }
+
+    /// Number of stored vectors.
+    #[wasm_bindgen(getter)]
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn metric(&self) -> VectorMetric {
+        self.metric
+    }
+
+    /// Highest layer in the graph.
+    #[wasm_bindgen(getter)]
+    pub fn max_level(&self) -> usize {
+        self.top_level()
+    }
+
+    /// Error message for invalid construction parameters.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+
+    /// Serialize to the `PXHN` v1 binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(36 + 4 * self.vectors.len());
+        out.extend_from_slice(HNSW_MAGIC);
+        out.extend_from_slice(&HNSW_VERSION.to_le_bytes());
+        out.extend_from_slice(&(self.metric as u16).to_le_bytes());
+        out.extend_from_slice(&(self.dim as u32).to_le_bytes());
+        out.extend_from_slice(&(self.m as u32).to_le_bytes());
+        out.extend_from_slice(&(self.ef_construction as u32).to_le_bytes());
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        out.extend_from_slice(&(self.links.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.entry.unwrap_or(HNSW_NO_ENTRY).to_le_bytes());
+        for &v in &self.vectors {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        for layers in &self.links {
+            out.extend_from_slice(&(layers.len() as u32 - 1).to_le_bytes());
+            for list in layers {
+                out.extend_from_slice(&(list.len() as u32).to_le_bytes());
+                for &id in list {
+                    out.extend_from_slice(&id.to_le_bytes());
+                }
+            }
+        }
+        out
+    }
+
+    /// Deserialize from `to_bytes` output; `None` if malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<HnswIndex> {
+        let mut reader = ByteReader::new(bytes);
+        if reader.take(4)? != HNSW_MAGIC || reader.u16()? != HNSW_VERSION {
+            return None;
+        }
+        let metric = VectorMetric::from_u16(reader.u16()?)?;
+        let dim = reader.u32()? as usize;
+        let m = reader.u32()? as usize;
+        // Clamped as in `new`: a beam narrower than m cannot fill the links.
+        let ef_construction = (reader.u32()? as usize).max(m);
+        let seed = reader.u64()?;
+        let count = reader.u32()?;
+        let entry = reader.u32()?;
+        if dim == 0 || !(2..=HNSW_MAX_M).contains(&m) {
+            return None;
+        }
+        let vectors = reader
+            .take((count as usize).checked_mul(dim)?.checked_mul(4)?)?
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        let mut links = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let level = reader.u32()? as usize;
+            if level > HNSW_MAX_LEVEL {
+                return None;
+            }
+            let mut layers = Vec::with_capacity(level + 1);
+            for layer in 0..=level {
+                let degree = reader.u32()? as usize;
+                if degree > if layer == 0 { 2 * m } else { m } {
+                    return None;
+                }
+                let list: Vec<u32> = reader
+                    .take(degree * 4)?
+                    .chunks_exact(4)
+                    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                layers.push(list);
+            }
+            links.push(layers);
+        }
+        // Every link must point at a node that has that layer.
+        let valid = links.iter().all(|layers| {
+            layers.iter().enumerate().all(|(layer, list)| {
+                list.iter()
+                    .all(|&id| id < count && links[id as usize].len() > layer)
+            })
+        });
+        let top = links.iter().map(|l| l.len()).max();
+        let entry = match (entry, top) {
+            (HNSW_NO_ENTRY, None) => None,
+            (e, Some(top)) if e < count && links[e as usize].len() == top => Some(e),
+            _ => return None,
+        };
+        if !valid || !reader.is_done() {
+            return None;
+        }
+        Some(HnswIndex {
+            dim,
+            metric,
+            m,
+            ef_construction,
+            seed,
+            vectors,
+            links,
+            entry,
+            error: None,
+        })
+    }
+}
+
+/// Top-k hits of an HNSW query, nearest first.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct HnswSearchResult {
+    ids: Vec<u32>,
+    distances: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl HnswSearchResult {
+    /// Ids (insertion order) of the hits as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn ids(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.ids.len() as u32);
+        arr.copy_from(&self.ids);
+        arr
+    }
+
+    /// Distance per hit (1 - cosine similarity, or Euclidean) as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn distances(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.distances.len() as u32);
+        arr.copy_from(&self.distances);
+        arr
+    }
+
+    /// Number of hits.
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> usize {
+        self.ids.len()
+    }
+}
+
+#[cfg(test)]
+mod hnsw_tests {
+    use super::*;
+
+    fn random_vectors(n: usize, dim: usize, seed: u64) -> Vec<f32> {
+        let mut rng = SplitMix64::new(seed);
+        (0..n * dim).map(|_| rng.gaussian() as f32).collect()
+    }
+
+    fn brute_force(data: &[f32], dim: usize, query: &[f32], k: usize) -> Vec<u32> {
+        let mut all: Vec<(f32, u32)> = data
+            .chunks_exact(dim)
+            .enumerate()
+            .map(|(i, row)| {
+                let d: f32 = row.iter().zip(query).map(|(a, b)| (a - b) * (a - b)).sum();
+                (d, i as u32)
+            })
+            .collect();
+        all.sort_by(|a, b| a.0.total_cmp(&b.0));
+        all.iter().take(k).map(|&(_, i)| i).collect()
+    }
+
+    #[test]
+    fn test_recall_against_brute_force() {
+        let dim = 16;
+        let data = random_vectors(1000, dim, 7);
+        let mut index = HnswIndex::new(dim, VectorMetric::Euclidean, 12, 100, 1);
+        assert_eq!(index.insert_batch(&data), 1000);
+        assert!(index.max_level() >= 1);
+
+        let queries = random_vectors(50, dim, 8);
+        let mut hits = 0;
+        for query in queries.chunks_exact(dim) {
+            let truth = brute_force(&data, dim, query, 10);
+            let found = index.search_ids(query, 10, 64);
+            assert!(found.windows(2).all(|w| w[0].1 <= w[1].1));
+            hits += found.iter().filter(|(id, _)| truth.contains(id)).count();
+        }
+        let recall = hits as f64 / 500.0;
+        assert!(recall > 0.9, "recall {}", recall);
+
+        // A stored vector finds itself at distance 0.
+        let found = index.search_ids(&data[42 * dim..43 * dim], 1, 32);
+        assert_eq!(found[0], (42, 0.0));
+        assert!(index.insert(&[1.0; 3]).is_none());
+    }
+
+    #[test]
+    fn test_cosine_ignores_scale() {
+        let data = random_vectors(200, 8, 3);
+        let mut index = HnswIndex::new(8, VectorMetric::Cosine, 8, 64, 2);
+        index.insert_batch(&data);
+        let scaled: Vec<f32> = data[17 * 8..18 * 8].iter().map(|v| v * 5.0).collect();
+        let found = index.search_ids(&scaled, 3, 32);
+        assert_eq!(found[0].0, 17);
+        assert!(found[0].1.abs() < 1e-5);
+        assert!(HnswIndex::new(8, VectorMetric::Cosine, 1, 64, 2)
+            .error
+            .is_some());
+    }
+
+    #[test]
+    fn test_bytes_roundtrip_and_incremental_insert() {
+        let dim = 6;
+        let data = random_vectors(300, dim, 11);
+        let mut index = HnswIndex::new(dim, VectorMetric::Euclidean, 8, 64, 5);
+        index.insert_batch(&data[..200 * dim]);
+        let bytes = index.to_bytes();
+        assert_eq!(&bytes[..4], b"PXHN");
+        let mut back = HnswIndex::from_bytes(&bytes).expect("decode");
+        assert_eq!(back.to_bytes(), bytes);
+
+        // Both copies keep growing identically after the round trip.
+        index.insert_batch(&data[200 * dim..]);
+        back.insert_batch(&data[200 * dim..]);
+        assert_eq!(back.len(), 300);
+        assert_eq!(back.to_bytes(), index.to_bytes());
+        let query = &data[250 * dim..251 * dim];
+        assert_eq!(back.search_ids(query, 1, 32)[0].0, 250);
+
+        assert!(HnswIndex::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 4;
+        corrupt[last..].copy_from_slice(&9999u32.to_le_bytes());
+        assert!(HnswIndex::from_bytes(&corrupt).is_none());
+        let empty = HnswIndex::new(dim, VectorMetric::Cosine, 8, 64, 5);
+        assert!(HnswIndex::from_bytes(&empty.to_bytes()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tampered_ef_construction_still_inserts() {
+        let dim = 4;
+        let data = random_vectors(60, dim, 12);
+        let mut index = HnswIndex::new(dim, VectorMetric::Euclidean, 6, 32, 1);
+        index.insert_batch(&data[..40 * dim]);
+        // ef_construction follows magic, version, metric, dim and m.
+        let mut bytes = index.to_bytes();
+        bytes[16..20].copy_from_slice(&0u32.to_le_bytes());
+        let mut back = HnswIndex::from_bytes(&bytes).expect("decode");
+        assert_eq!(back.ef_construction, 6);
+        back.insert_batch(&data[40 * dim..]);
+        assert_eq!(back.len(), 60);
+        let query = &data[50 * dim..51 * dim];
+        assert_eq!(back.search_ids(query, 1, 32)[0].0, 50);
+    }
+}
@@ -0,0 +1,337 @@
+//! HyperLogLog cardinality sketches over k-mers.
+//!
+//! Estimates the number of distinct k-mers with 2^p one-byte registers
+//! (relative standard error ≈ 1.04 / √(2^p)), independent of genome size.
+//! Sketches with the same parameters merge by register-wise max, so a
+//! pangenome's distinct k-mer count is the estimate of the merged sketch;
+//! intersections follow by inclusion–exclusion.
+//!
+//! # Binary layout (little-endian)
+//!
+//! `PXHL`, version 1:
+//! ```text
+//! magic[4] = "PXHL" | version u16 | flags u16 (bit 0 = canonical)
+//! precision u32 | k u32 | seed u64 | registers u8 × 2^precision
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::fracminhash::frac_hash;
+use crate::minimizers::for_each_kmer;
+use crate::sketch::ByteReader;
+use crate::{encode_base, SequenceHandle};
+
+const HLL_MAGIC: &[u8; 4] = b"PXHL";
+const HLL_VERSION: u16 = 1;
+const HLL_FLAG_CANONICAL: u16 = 1;
+
+/// Supported precision range (16 B to 256 KB of registers).
+pub(crate) const HLL_MIN_PRECISION: u32 = 4;
+pub(crate) const HLL_MAX_PRECISION: u32 = 18;
+
+/// HyperLogLog sketch of k-mer hashes.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+    precision: u32,
+    k: usize,
+    canonical: bool,
+    seed: u64,
+}
+
+impl HyperLogLog {
+    #[inline]
+    fn insert_hash(&mut self, hash: u64) {
+        let p = self.precision;
+        let index = (hash >> (64 - p)) as usize;
+        let rank = ((hash << p).leading_zeros() + 1).min(64 - p + 1) as u8;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    pub(crate) fn add_encoded(&mut self, encoded: &[u8]) {
+        let (k, canonical, seed) = (self.k, self.canonical, self.seed);
+        for_each_kmer(encoded, k, |_, fwd, rc| {
+            self.insert_hash(frac_hash(if canonical { fwd.min(rc) } else { fwd }, seed));
+        });
+    }
+
+    fn is_compatible(&self, other: &HyperLogLog) -> bool {
+        self.precision == other.precision
+            && self.k == other.k
+            && self.canonical == other.canonical
+            && self.seed == other.seed
+    }
+
+    /// Cardinality estimate from a register array (classic HLL with
+    /// linear counting for small cardinalities; 64-bit hashes need no
+    /// large-range correction).
+    fn estimate_registers(registers: &[u8]) -> f64 {
+        let m = registers.len() as f64;
+        let alpha = match registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+        let zeros = registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl HyperLogLog {
+    /// Create an empty sketch.
+    ///
+    /// # Arguments
+    /// * `precision` - log2 of the register count (clamped to 4..=18)
+    /// * `k` - K-mer size (1..=32)
+    /// * `canonical` - Hash min(forward, reverse complement)
+    /// * `seed` - Hash seed (sketches only merge with equal seeds)
+    #[wasm_bindgen(constructor)]
+    pub fn new(precision: u32, k: usize, canonical: bool, seed: u64) -> HyperLogLog {
+        let precision = precision.clamp(HLL_MIN_PRECISION, HLL_MAX_PRECISION);
+        HyperLogLog {
+            registers: vec![0; 1 << precision],
+            precision,
+            k: k.min(32),
+            canonical,
+            seed,
+        }
+    }
+
+    /// Add the k-mers of a sequence (ASCII bytes).
+    pub fn add_sequence(&mut self, seq: &[u8]) {
+        let encoded: Vec<u8> = seq.iter().map(|&b| encode_base(b)).collect();
+        self.add_encoded(&encoded);
+    }
+
+    /// Estimated number of distinct k-mers.
+    pub fn estimate(&self) -> f64 {
+        HyperLogLog::estimate_registers(&self.registers)
+    }
+
+    /// Merge another sketch into this one (union).
+    ///
+    /// Returns false (and leaves this sketch unchanged) if the precision,
+    /// k, strand mode or seed differ.
+    pub fn merge(&mut self, other: &HyperLogLog) -> bool {
+        if !self.is_compatible(other) {
+            return false;
+        }
+        for (a, &b) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *a = (*a).max(b);
+        }
+        true
+    }
+
+    /// Estimated |A ∪ B| (NaN if incompatible).
+    pub fn union_estimate(&self, other: &HyperLogLog) -> f64 {
+        if !self.is_compatible(other) {
+            return f64::NAN;
+        }
+        let merged: Vec<u8> = self
+            .registers
+            .iter()
+            .zip(other.registers.iter())
+            .map(|(&a, &b)| a.max(b))
+            .collect();
+        HyperLogLog::estimate_registers(&merged)
+    }
+
+    /// Estimated |A ∩ B| = |A| + |B| - |A ∪ B|, clamped at 0 (NaN if incompatible).
+    pub fn intersection_estimate(&self, other: &HyperLogLog) -> f64 {
+        let union = self.union_estimate(other);
+        (self.estimate() + other.estimate() - union).max(0.0)
+    }
+
+    /// Estimated Jaccard index |A ∩ B| / |A ∪ B| (NaN if incompatible).
+    pub fn jaccard_estimate(&self, other: &HyperLogLog) -> f64 {
+        let union = self.union_estimate(other);
+        if union <= 0.0 {
+            return if union.is_nan() { f64::NAN } else { 0.0 };
+        }
+        (self.intersection_estimate(other) / union).min(1.0)
+    }
+
+    /// Expected relative standard error, 1.04 / √(2^p).
+    #[wasm_bindgen(getter)]
+    pub fn relative_error(&self) -> f64 {
+        1.04 / (self.registers.len() as f64).sqrt()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn canonical(&self) -> bool {
+        self.canonical
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Registers as Uint8Array.
+    #[wasm_bindgen(getter)]
+    pub fn registers(&self) -> js_sys::Uint8Array {
+        let arr = js_sys::Uint8Array::new_with_length(self.registers.len() as u32);
+        arr.copy_from(&self.registers);
+        arr
+    }
+
+    /// Serialize to the `PXHL` v1 binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(24 + self.registers.len());
+        out.extend_from_slice(HLL_MAGIC);
+        out.extend_from_slice(&HLL_VERSION.to_le_bytes());
+        let flags = if self.canonical {
+            HLL_FLAG_CANONICAL
+        } else {
+            0
+        };
+        out.extend_from_slice(&flags.to_le_bytes());
+        out.extend_from_slice(&self.precision.to_le_bytes());
+        out.extend_from_slice(&(self.k as u32).to_le_bytes());
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        out.extend_from_slice(&self.registers);
+        out
+    }
+
+    /// Deserialize from `to_bytes` output; `None` if malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<HyperLogLog> {
+        let mut reader = ByteReader::new(bytes);
+        if reader.take(4)? != HLL_MAGIC || reader.u16()? != HLL_VERSION {
+            return None;
+        }
+        let flags = reader.u16()?;
+        let precision = reader.u32()?;
+        let k = reader.u32()? as usize;
+        let seed = reader.u64()?;
+        if !(HLL_MIN_PRECISION..=HLL_MAX_PRECISION).contains(&precision) || k > 32 {
+            return None;
+        }
+        let registers = reader.take(1 << precision)?.to_vec();
+        if !reader.is_done() || registers.iter().any(|&r| r as u32 > 65 - precision) {
+            return None;
+        }
+        Some(HyperLogLog {
+            registers,
+            precision,
+            k,
+            canonical: flags & HLL_FLAG_CANONICAL != 0,
+            seed,
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl SequenceHandle {
+    /// HyperLogLog sketch of the canonical k-mers of the pre-encoded sequence.
+    ///
+    /// # Arguments
+    /// * `precision` - log2 of the register count (4..=18)
+    /// * `k` - K-mer size (1..=32)
+    /// * `seed` - Hash seed
+    pub fn hyperloglog(&self, precision: u32, k: usize, seed: u64) -> HyperLogLog {
+        let mut hll = HyperLogLog::new(precision, k, true, seed);
+        hll.add_encoded(&self.encoded);
+        hll
+    }
+}
+
+#[cfg(test)]
+mod hyperloglog_tests {
+    use super::*;
+    use crate::rng::random_dna;
+
+    #[test]
+    fn test_estimate_accuracy() {
+        for &len in &[200usize, 5_000, 200_000] {
+            let seq = random_dna(len, len as u64);
+            let mut hll = HyperLogLog::new(12, 21, true, 0);
+            hll.add_sequence(&seq);
+            let truth = (len - 20) as f64;
+            let err = (hll.estimate() - truth).abs() / truth;
+            assert!(err < 4.0 * hll.relative_error(), "len {} err {}", len, err);
+        }
+    }
+
+    #[test]
+    fn test_merge_union_and_intersection() {
+        let shared = random_dna(50_000, 1);
+        let mut a_seq = shared.clone();
+        a_seq.extend(random_dna(50_000, 2));
+        let mut b_seq = shared;
+        b_seq.extend(random_dna(50_000, 3));
+
+        let mut a = HyperLogLog::new(14, 21, true, 9);
+        a.add_sequence(&a_seq);
+        let mut b = HyperLogLog::new(14, 21, true, 9);
+        b.add_sequence(&b_seq);
+
+        let union = a.union_estimate(&b);
+        assert!(
+            (union - 150_000.0).abs() / 150_000.0 < 0.05,
+            "union {}",
+            union
+        );
+        let inter = a.intersection_estimate(&b);
+        assert!(
+            (inter - 50_000.0).abs() / 50_000.0 < 0.15,
+            "inter {}",
+            inter
+        );
+        let jaccard = a.jaccard_estimate(&b);
+        assert!((jaccard - 1.0 / 3.0).abs() < 0.05, "jaccard {}", jaccard);
+
+        assert!(a.merge(&b));
+        assert!((a.estimate() - union).abs() < 1e-9);
+        assert!(!a.merge(&HyperLogLog::new(14, 21, true, 10)));
+    }
+
+    #[test]
+    fn test_canonical_and_roundtrip() {
+        let seq = random_dna(10_000, 4);
+        let rc: Vec<u8> = seq
+            .iter()
+            .rev()
+            .map(|&b| match b {
+                b'A' => b'T',
+                b'C' => b'G',
+                b'G' => b'C',
+                _ => b'A',
+            })
+            .collect();
+        let mut fwd = HyperLogLog::new(10, 15, true, 1);
+        fwd.add_sequence(&seq);
+        let mut rev = HyperLogLog::new(10, 15, true, 1);
+        rev.add_sequence(&rc);
+        assert_eq!(fwd.registers, rev.registers);
+
+        let restored = HyperLogLog::from_bytes(&fwd.to_bytes()).unwrap();
+        assert_eq!(restored.registers, fwd.registers);
+        assert_eq!(restored.k, 15);
+        assert!(restored.canonical);
+        assert!(HyperLogLog::from_bytes(&fwd.to_bytes()[..30]).is_none());
+    }
+}
@@ -0,0 +1,461 @@
+//! Pairwise information measures between sequence positions.
+//!
+//! - Mutual information between alignment columns, with the average
+//!   product correction (APC, Dunn et al. 2008) that removes the
+//!   background shared by every pair, so coevolving columns stand out
+//! - The mutual information function I(d) of a genome: dependence between
+//!   bases d apart (period-3 peaks in coding regions, long-range decay)
+//! - Block entropies H_k, conditional entropies h_k = H_k - H_{k-1} and an
+//!   entropy rate estimate
+//!
+//! All values are in bits.
+
+use wasm_bindgen::prelude::*;
+
+use crate::kmer_counter::count_sequence;
+use crate::msa::{parse_fasta, MsaAlphabet};
+use crate::{encode_base, shannon_entropy_from_counts, SEQ_BASE_N};
+
+/// Maximum alignment columns for the all-pairs MI matrix (4.5M pairs).
+pub(crate) const ALIGNMENT_MI_MAX_COLUMNS: usize = 3_000;
+
+/// Largest block length for block entropies.
+pub(crate) const BLOCK_ENTROPY_MAX_K: usize = 16;
+
+/// Windows per possible k-mer needed before h_k counts as reliable.
+const BLOCK_ENTROPY_MIN_COVERAGE: f64 = 10.0;
+
+/// Marker for gaps and unknown residues in encoded columns.
+const NO_SYMBOL: u8 = u8::MAX;
+
+/// Mutual information between all pairs of alignment columns.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct ColumnCoupling {
+    columns: usize,
+    num_sequences: usize,
+    mi: Vec<f32>,
+    apc: Vec<f32>,
+    error: Option<String>,
+}
+
+impl ColumnCoupling {
+    fn failed(error: String) -> ColumnCoupling {
+        ColumnCoupling {
+            columns: 0,
+            num_sequences: 0,
+            mi: Vec::new(),
+            apc: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ColumnCoupling {
+    /// Number of alignment columns.
+    #[wasm_bindgen(getter)]
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn num_sequences(&self) -> usize {
+        self.num_sequences
+    }
+
+    /// Raw MI per column pair, condensed (SciPy order), as Float32Array.
+    /// NaN for pairs involving an excluded (too gappy) column.
+    #[wasm_bindgen(getter)]
+    pub fn mi(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.mi.len() as u32);
+        arr.copy_from(&self.mi);
+        arr
+    }
+
+    /// APC-corrected MI, MI(i,j) - MI(i,·)·MI(j,·)/MI(·,·), condensed.
+    #[wasm_bindgen(getter)]
+    pub fn apc(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.apc.len() as u32);
+        arr.copy_from(&self.apc);
+        arr
+    }
+
+    /// The `count` column pairs with the highest APC score as
+    /// [i0, j0, i1, j1, ...] (i < j), best first.
+    pub fn top_pairs(&self, count: usize) -> js_sys::Uint32Array {
+        let mut scored: Vec<(f32, u32, u32)> = Vec::new();
+        let mut idx = 0;
+        for i in 0..self.columns {
+            for j in (i + 1)..self.columns {
+                let v = self.apc[idx];
+                if !v.is_nan() {
+                    scored.push((v, i as u32, j as u32));
+                }
+                idx += 1;
+            }
+        }
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+        let flat: Vec<u32> = scored
+            .iter()
+            .take(count)
+            .flat_map(|&(_, i, j)| [i, j])
+            .collect();
+        let arr = js_sys::Uint32Array::new_with_length(flat.len() as u32);
+        arr.copy_from(&flat);
+        arr
+    }
+
+    /// Error message for invalid input.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// MI (bits) between two encoded columns over rows where both have a
+/// residue.
+fn column_mi(a: &[u8], b: &[u8], symbols: usize, joint: &mut [f64]) -> f64 {
+    joint.iter_mut().for_each(|c| *c = 0.0);
+    let mut row_counts = vec![0.0; symbols];
+    let mut col_counts = vec![0.0; symbols];
+    for (&x, &y) in a.iter().zip(b) {
+        if x != NO_SYMBOL && y != NO_SYMBOL {
+            joint[x as usize * symbols + y as usize] += 1.0;
+            row_counts[x as usize] += 1.0;
+            col_counts[y as usize] += 1.0;
+        }
+    }
+    let mi = shannon_entropy_from_counts(&row_counts) + shannon_entropy_from_counts(&col_counts)
+        - shannon_entropy_from_counts(joint);
+    mi.max(0.0)
+}
+
+/// Mutual information between every pair of alignment columns, with APC.
+///
+/// Residues are compared over the sequences that have a residue in both
+/// columns (gaps and N/X are skipped).
+///
+/// # Arguments
+/// * `aligned_fasta` - Aligned sequences (`-` or `.` as gaps), equal lengths
+/// * `protein` - true for amino acids, false for nucleotides
+/// * `max_gap_fraction` - Columns with a larger share of gaps/unknowns are
+///   excluded (their pairs are NaN and they do not enter the APC averages)
+///
+/// # Returns
+/// `ColumnCoupling`; `error` is set for empty or ragged input or more than
+/// 3000 columns.
+#[wasm_bindgen]
+pub fn alignment_mutual_information(
+    aligned_fasta: &str,
+    protein: bool,
+    max_gap_fraction: f64,
+) -> ColumnCoupling {
+    let alphabet = if protein {
+        MsaAlphabet::Protein
+    } else {
+        MsaAlphabet::Dna
+    };
+    let symbols = alphabet.unknown();
+    let records = parse_fasta(aligned_fasta, true);
+    if records.is_empty() {
+        return ColumnCoupling::failed("No sequences found in FASTA input".to_string());
+    }
+    let cols = records[0].residues.len();
+    if let Some(bad) = records.iter().find(|r| r.residues.len() != cols) {
+        return ColumnCoupling::failed(format!(
+            "Row '{}' has {} columns, expected {}",
+            bad.name,
+            bad.residues.len(),
+            cols
+        ));
+    }
+    if cols > ALIGNMENT_MI_MAX_COLUMNS {
+        return ColumnCoupling::failed(format!(
+            "at most {} columns supported (got {})",
+            ALIGNMENT_MI_MAX_COLUMNS, cols
+        ));
+    }
+    let rows = records.len();
+
+    // Column-major symbols.
+    let columns: Vec<Vec<u8>> = (0..cols)
+        .map(|c| {
+            records
+                .iter()
+                .map(|r| match r.residues[c] {
+                    b'-' => NO_SYMBOL,
+                    b => {
+                        let sym = alphabet.symbol(b);
+                        if sym < symbols {
+                            sym as u8
+                        } else {
+                            NO_SYMBOL
+                        }
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let included: Vec<bool> = columns
+        .iter()
+        .map(|col| {
+            let missing = col.iter().filter(|&&s| s == NO_SYMBOL).count();
+            (missing as f64) <= max_gap_fraction * rows as f64
+        })
+        .collect();
+
+    let pairs = cols * cols.saturating_sub(1) / 2;
+    let mut mi = vec![f32::NAN; pairs];
+    let mut row_sums = vec![0.0f64; cols];
+    let mut total = 0.0f64;
+    let mut joint = vec![0.0; symbols * symbols];
+    let mut idx = 0;
+    for i in 0..cols {
+        for j in (i + 1)..cols {
+            if included[i] && included[j] {
+                let v = column_mi(&columns[i], &columns[j], symbols, &mut joint);
+                mi[idx] = v as f32;
+                row_sums[i] += v;
+                row_sums[j] += v;
+                total += v;
+            }
+            idx += 1;
+        }
+    }
+
+    let kept = included.iter().filter(|&&b| b).count();
+    let mut apc = mi.clone();
+    if kept >= 3 {
+        let mean_all = total / (kept * (kept - 1) / 2) as f64;
+        let mean_col: Vec<f64> = row_sums.iter().map(|s| s / (kept - 1) as f64).collect();
+        let mut idx = 0;
+        for i in 0..cols {
+            for j in (i + 1)..cols {
+                if !apc[idx].is_nan() && mean_all > 0.0 {
+                    apc[idx] = (mi[idx] as f64 - mean_col[i] * mean_col[j] / mean_all) as f32;
+                }
+                idx += 1;
+            }
+        }
+    }
+
+    ColumnCoupling {
+        columns: cols,
+        num_sequences: rows,
+        mi,
+        apc,
+        error: None,
+    }
+}
+
+/// I(d) for d = 1..=max_distance, in bits.
+fn mutual_information_by_distance(seq: &[u8], max_distance: usize) -> Vec<f64> {
+    let encoded: Vec<u8> = seq.iter().map(|&b| encode_base(b)).collect();
+    (1..=max_distance)
+        .map(|d| {
+            let mut joint = [0.0f64; 16];
+            let mut left = [0.0f64; 4];
+            let mut right = [0.0f64; 4];
+            for (&a, &b) in encoded.iter().zip(encoded.iter().skip(d)) {
+                if a != SEQ_BASE_N && b != SEQ_BASE_N {
+                    joint[(a as usize) << 2 | b as usize] += 1.0;
+                    left[a as usize] += 1.0;
+                    right[b as usize] += 1.0;
+                }
+            }
+            (shannon_entropy_from_counts(&left) + shannon_entropy_from_counts(&right)
+                - shannon_entropy_from_counts(&joint))
+            .max(0.0)
+        })
+        .collect()
+}
+
+/// Mutual information function I(d) of a nucleotide sequence.
+///
+/// I(d) is the MI (bits) between the bases at positions i and i + d, over
+/// all i where both are A/C/G/T.
+///
+/// # Returns
+/// Float64Array of length `max_distance`; entry d - 1 holds I(d).
+#[wasm_bindgen]
+pub fn mutual_information_function(seq: &[u8], max_distance: usize) -> js_sys::Float64Array {
+    let values = mutual_information_by_distance(seq, max_distance);
+    let arr = js_sys::Float64Array::new_with_length(values.len() as u32);
+    arr.copy_from(&values);
+    arr
+}
+
+/// Block entropies and conditional entropies for block lengths 1..=k.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct BlockEntropies {
+    block: Vec<f64>,
+    conditional: Vec<f64>,
+    reliable_k: usize,
+    error: Option<String>,
+}
+
+#[wasm_bindgen]
+impl BlockEntropies {
+    /// H_k (bits) of overlapping k-mers for k = 1..=max_k, as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn block_entropies(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.block.len() as u32);
+        arr.copy_from(&self.block);
+        arr
+    }
+
+    /// h_k = H_k - H_{k-1} (h_1 = H_1): bits of uncertainty about the next
+    /// base given the previous k - 1, as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn conditional_entropies(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.conditional.len() as u32);
+        arr.copy_from(&self.conditional);
+        arr
+    }
+
+    /// Largest k with at least 10 windows per possible k-mer; beyond it H_k
+    /// is biased low by undersampling.
+    #[wasm_bindgen(getter)]
+    pub fn reliable_k(&self) -> usize {
+        self.reliable_k
+    }
+
+    /// Entropy rate estimate: h_k at `reliable_k` (bits per base).
+    #[wasm_bindgen(getter)]
+    pub fn entropy_rate(&self) -> f64 {
+        self.conditional
+            .get(self.reliable_k.saturating_sub(1))
+            .copied()
+            .unwrap_or(f64::NAN)
+    }
+
+    /// Error message for invalid input.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Block entropies H_1..H_max_k and conditional entropies of a nucleotide
+/// sequence (windows containing non-ACGT bases are skipped).
+///
+/// # Arguments
+/// * `seq` - Nucleotide sequence
+/// * `max_k` - Largest block length (1..=16)
+#[wasm_bindgen]
+pub fn block_entropies(seq: &[u8], max_k: usize) -> BlockEntropies {
+    if !(1..=BLOCK_ENTROPY_MAX_K).contains(&max_k) {
+        return BlockEntropies {
+            block: Vec::new(),
+            conditional: Vec::new(),
+            reliable_k: 0,
+            error: Some(format!(
+                "max_k must be 1..={} (got {})",
+                BLOCK_ENTROPY_MAX_K, max_k
+            )),
+        };
+    }
+    let mut block = Vec::with_capacity(max_k);
+    let mut reliable_k = 0;
+    for k in 1..=max_k {
+        let table = count_sequence(seq, k, false);
+        let counts: Vec<f64> = table.iter().map(|(_, c)| c as f64).collect();
+        let windows: f64 = counts.iter().sum();
+        if windows >= BLOCK_ENTROPY_MIN_COVERAGE * 4f64.powi(k as i32) {
+            reliable_k = k;
+        }
+        block.push(shannon_entropy_from_counts(&counts));
+    }
+    let conditional = block
+        .iter()
+        .enumerate()
+        .map(|(i, &h)| if i == 0 { h } else { h - block[i - 1] })
+        .collect();
+    BlockEntropies {
+        block,
+        conditional,
+        reliable_k,
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod information_tests {
+    use super::*;
+    use crate::rng::{random_dna, SplitMix64};
+
+    #[test]
+    fn test_apc_finds_coevolving_columns() {
+        let mut rng = SplitMix64::new(12);
+        let mut fasta = String::new();
+        for s in 0..60 {
+            let mut row: Vec<u8> = (0..8).map(|_| b"ACGT"[rng.below(4)]).collect();
+            // Columns 1 and 5 covary (A<->C, G<->T); column 7 is mostly gaps.
+            row[5] = match row[1] {
+                b'A' => b'C',
+                b'C' => b'A',
+                b'G' => b'T',
+                _ => b'G',
+            };
+            if s % 4 != 0 {
+                row[7] = b'-';
+            }
+            fasta.push_str(&format!(">s{}\n{}\n", s, String::from_utf8(row).unwrap()));
+        }
+        let coupling = alignment_mutual_information(&fasta, false, 0.5);
+        assert!(coupling.error.is_none());
+        assert_eq!(coupling.mi.len(), 28);
+        let best = (0..28)
+            .filter(|&i| !coupling.apc[i].is_nan())
+            .max_by(|&a, &b| coupling.apc[a].total_cmp(&coupling.apc[b]))
+            .unwrap();
+        // Condensed index of (1, 5) with n = 8: 7 + (5 - 2) = 10.
+        assert_eq!(best, 10);
+        assert!((coupling.mi[10] - 2.0).abs() < 0.1);
+        // Pairs with the gappy column are excluded.
+        assert!(coupling.mi[6].is_nan());
+        assert!(alignment_mutual_information(">a\nAC\n>b\nA\n", false, 0.5)
+            .error
+            .is_some());
+    }
+
+    #[test]
+    fn test_block_and_conditional_entropies() {
+        let random = random_dna(200_000, 3);
+        let result = block_entropies(&random, 6);
+        assert_eq!(result.reliable_k, 6);
+        for (k, &h) in result.block.iter().enumerate() {
+            assert!((h - 2.0 * (k + 1) as f64).abs() < 0.03);
+        }
+        assert!((result.entropy_rate() - 2.0).abs() < 0.03);
+
+        // Alternating AC: 1 bit of composition, fully predictable after that.
+        let alternating = b"AC".repeat(1_000);
+        let result = block_entropies(&alternating, 4);
+        assert!((result.block[0] - 1.0).abs() < 1e-9);
+        assert!(result.conditional[1].abs() < 1e-3);
+        assert!(block_entropies(&alternating, 0).error.is_some());
+    }
+
+    #[test]
+    fn test_mutual_information_function() {
+        // In a period-4 repeat each base determines the one d later.
+        let repeat = b"ACGT".repeat(500);
+        for value in mutual_information_by_distance(&repeat, 6) {
+            assert!((value - 2.0).abs() < 1e-3);
+        }
+        let random = random_dna(50_000, 8);
+        assert!(mutual_information_by_distance(&random, 5)
+            .iter()
+            .all(|&v| v < 0.005));
+        // N breaks pairs instead of counting as a symbol.
+        let values = mutual_information_by_distance(b"ANANANAN", 2);
+        assert_eq!(values, vec![0.0, 0.0]);
+    }
+}
@@ -0,0 +1,571 @@
+//! VIRIDIC-style intergenomic similarity for phage taxonomy.
+//!
+//! VIRIDIC (Moraru et al. 2020) defines the similarity of genomes A and B as
+//!
+//! ```text
+//! SIM = (idAB + idBA) · 100 / (lenA + lenB)
+//! ```
+//!
+//! where `idAB` / `idBA` are the identical bases in the local alignments of
+//! A against B and B against A. Instead of running BLASTN, alignments are
+//! approximated here by chaining exact k-mer anchors (maximal exact matches)
+//! on both strands into local hits; identical bases are the anchor bases plus
+//! matching bases in equal-length gaps between consecutive anchors. Bases
+//! are counted once per genome even when several hits overlap them.
+//!
+//! ICTV thresholds: >= 95% similarity for species and >= 70% for genera.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::clustering::{Dendrogram, Linkage};
+use crate::distance_matrix::condensed_index;
+use crate::msa::parse_fasta;
+use crate::{encode_base, json_string_array, SequenceHandle};
+
+/// ICTV species threshold (percent intergenomic similarity).
+pub(crate) const VIRIDIC_SPECIES_THRESHOLD: f64 = 95.0;
+
+/// ICTV genus threshold (percent intergenomic similarity).
+pub(crate) const VIRIDIC_GENUS_THRESHOLD: f64 = 70.0;
+
+/// Maximum number of genomes for `intergenomic_similarity_matrix`.
+pub(crate) const VIRIDIC_MAX_GENOMES: usize = 1_000;
+
+/// Anchor seed length.
+const VIRIDIC_SEED_K: usize = 13;
+
+/// Seeds occurring more often than this in the target are ignored (repeats).
+const VIRIDIC_SEED_MAX_OCCURRENCES: usize = 32;
+
+/// Largest gap (in either genome) bridged between chained anchors.
+const VIRIDIC_MAX_GAP: usize = 250;
+
+/// Largest indel (difference between the two gap sizes) inside a hit.
+const VIRIDIC_MAX_INDEL: usize = 40;
+
+/// Number of preceding anchors considered when chaining.
+const VIRIDIC_CHAIN_LOOKBACK: usize = 64;
+
+/// Hits with fewer identical bases are treated as random seed matches.
+const VIRIDIC_MIN_HIT_IDENTITIES: usize = 40;
+
+/// Seed index over one genome.
+struct SeedIndex {
+    seeds: HashMap<u32, Vec<u32>>,
+}
+
+impl SeedIndex {
+    fn new(seq: &[u8]) -> SeedIndex {
+        let mut seeds: HashMap<u32, Vec<u32>> = HashMap::new();
+        for_each_seed(seq, |pos, code| {
+            seeds.entry(code).or_default().push(pos as u32)
+        });
+        SeedIndex { seeds }
+    }
+}
+
+/// Call `f(start, code)` for every seed without ambiguous bases.
+fn for_each_seed(seq: &[u8], mut f: impl FnMut(usize, u32)) {
+    let mask = (1u32 << (2 * VIRIDIC_SEED_K)) - 1;
+    let mut code = 0u32;
+    let mut valid = 0usize;
+    for (i, &b) in seq.iter().enumerate() {
+        if b > 3 {
+            valid = 0;
+            continue;
+        }
+        code = ((code << 2) | b as u32) & mask;
+        valid += 1;
+        if valid >= VIRIDIC_SEED_K {
+            f(i + 1 - VIRIDIC_SEED_K, code);
+        }
+    }
+}
+
+/// Maximal exact match between query and target.
+#[derive(Clone, Copy)]
+struct Anchor {
+    q: usize,
+    t: usize,
+    len: usize,
+}
+
+fn find_anchors(query: &[u8], target: &[u8], index: &SeedIndex) -> Vec<Anchor> {
+    let mut anchors = Vec::new();
+    // Query end of the last anchor per diagonal, to report each MEM once.
+    let mut covered: HashMap<i64, usize> = HashMap::new();
+    for_each_seed(query, |q, code| {
+        let Some(hits) = index.seeds.get(&code) else {
+            return;
+        };
+        if hits.len() > VIRIDIC_SEED_MAX_OCCURRENCES {
+            return;
+        }
+        for &t in hits {
+            let t = t as usize;
+            let diagonal = t as i64 - q as i64;
+            if covered.get(&diagonal).is_some_and(|&end| end > q) {
+                continue;
+            }
+            let (mut qs, mut ts) = (q, t);
+            while qs > 0 && ts > 0 && query[qs - 1] == target[ts - 1] && query[qs - 1] <= 3 {
+                qs -= 1;
+                ts -= 1;
+            }
+            let (mut qe, mut te) = (q + VIRIDIC_SEED_K, t + VIRIDIC_SEED_K);
+            while qe < query.len() && te < target.len() && query[qe] == target[te] && query[qe] <= 3
+            {
+                qe += 1;
+                te += 1;
+            }
+            covered.insert(diagonal, qe);
+            anchors.push(Anchor {
+                q: qs,
+                t: ts,
+                len: qe - qs,
+            });
+        }
+    });
+    anchors.sort_by_key(|a| (a.q, a.t));
+    anchors
+}
+
+/// Per-genome coverage masks accumulated over all hits.
+struct Coverage {
+    identical: Vec<bool>,
+    aligned: Vec<bool>,
+}
+
+impl Coverage {
+    fn new(len: usize) -> Coverage {
+        Coverage {
+            identical: vec![false; len],
+            aligned: vec![false; len],
+        }
+    }
+}
+
+/// Chain anchors into local hits and mark identical/aligned bases.
+///
+/// `map_q` translates a query coordinate to the query genome's forward strand
+/// (identity for the forward pass, `len - 1 - p` for the reverse complement).
+fn mark_hits(
+    query: &[u8],
+    target: &[u8],
+    anchors: &[Anchor],
+    map_q: impl Fn(usize) -> usize,
+    cov_q: &mut Coverage,
+    cov_t: &mut Coverage,
+) {
+    let m = anchors.len();
+    let mut score = vec![0i64; m];
+    let mut prev = vec![usize::MAX; m];
+    for j in 0..m {
+        let aj = anchors[j];
+        score[j] = aj.len as i64;
+        for i in j.saturating_sub(VIRIDIC_CHAIN_LOOKBACK)..j {
+            let ai = anchors[i];
+            let (qe, te) = (ai.q + ai.len, ai.t + ai.len);
+            if qe > aj.q || te > aj.t {
+                continue;
+            }
+            let (gq, gt) = (aj.q - qe, aj.t - te);
+            let indel = gq.abs_diff(gt);
+            if gq.max(gt) > VIRIDIC_MAX_GAP || indel > VIRIDIC_MAX_INDEL {
+                continue;
+            }
+            let s = score[i] + aj.len as i64 - indel as i64;
+            if s > score[j] {
+                score[j] = s;
+                prev[j] = i;
+            }
+        }
+    }
+
+    // Extract hits greedily from the best-scoring chain ends.
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&a, &b| score[b].cmp(&score[a]).then(a.cmp(&b)));
+    let mut used = vec![false; m];
+    for end in order {
+        if used[end] {
+            continue;
+        }
+        let mut members = Vec::new();
+        let mut cur = end;
+        while cur != usize::MAX && !used[cur] {
+            used[cur] = true;
+            members.push(cur);
+            cur = prev[cur];
+        }
+        members.reverse();
+
+        // Identical (query, target) pairs of this hit.
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+        for (idx, &a) in members.iter().enumerate() {
+            let anchor = anchors[a];
+            pairs.extend((0..anchor.len).map(|o| (anchor.q + o, anchor.t + o)));
+            if let Some(&next) = members.get(idx + 1) {
+                let next = anchors[next];
+                let (qe, te) = (anchor.q + anchor.len, anchor.t + anchor.len);
+                if next.q - qe == next.t - te {
+                    for o in 0..(next.q - qe) {
+                        if query[qe + o] == target[te + o] && query[qe + o] <= 3 {
+                            pairs.push((qe + o, te + o));
+                        }
+                    }
+                }
+            }
+        }
+        if pairs.len() < VIRIDIC_MIN_HIT_IDENTITIES {
+            continue;
+        }
+
+        let first = anchors[members[0]];
+        let last = anchors[members[members.len() - 1]];
+        for q in first.q..last.q + last.len {
+            cov_q.aligned[map_q(q)] = true;
+        }
+        for t in first.t..last.t + last.len {
+            cov_t.aligned[t] = true;
+        }
+        for (q, t) in pairs {
+            cov_q.identical[map_q(q)] = true;
+            cov_t.identical[t] = true;
+        }
+    }
+}
+
+fn reverse_complement_encoded(seq: &[u8]) -> Vec<u8> {
+    seq.iter()
+        .rev()
+        .map(|&b| if b <= 3 { 3 - b } else { b })
+        .collect()
+}
+
+/// Identical and aligned base counts for one genome pair.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct PairIdentity {
+    pub(crate) identical_a: usize,
+    pub(crate) identical_b: usize,
+    pub(crate) aligned_a: usize,
+    pub(crate) aligned_b: usize,
+}
+
+impl PairIdentity {
+    /// VIRIDIC similarity in percent.
+    pub(crate) fn similarity(&self, len_a: usize, len_b: usize) -> f64 {
+        if len_a + len_b == 0 {
+            return 0.0;
+        }
+        (self.identical_a + self.identical_b) as f64 * 100.0 / (len_a + len_b) as f64
+    }
+}
+
+fn compare_indexed(a: &[u8], b: &[u8], index_b: &SeedIndex) -> PairIdentity {
+    let mut cov_a = Coverage::new(a.len());
+    let mut cov_b = Coverage::new(b.len());
+
+    let forward = find_anchors(a, b, index_b);
+    mark_hits(a, b, &forward, |p| p, &mut cov_a, &mut cov_b);
+
+    let a_rc = reverse_complement_encoded(a);
+    let reverse = find_anchors(&a_rc, b, index_b);
+    let last = a.len().saturating_sub(1);
+    mark_hits(&a_rc, b, &reverse, |p| last - p, &mut cov_a, &mut cov_b);
+
+    let count = |mask: &[bool]| mask.iter().filter(|&&x| x).count();
+    PairIdentity {
+        identical_a: count(&cov_a.identical),
+        identical_b: count(&cov_b.identical),
+        aligned_a: count(&cov_a.aligned),
+        aligned_b: count(&cov_b.aligned),
+    }
+}
+
+/// Compare two encoded genomes (A=0, C=1, G=2, T=3, N=4).
+pub(crate) fn pair_identity(a: &[u8], b: &[u8]) -> PairIdentity {
+    compare_indexed(a, b, &SeedIndex::new(b))
+}
+
+#[wasm_bindgen]
+impl SequenceHandle {
+    /// VIRIDIC-style intergenomic similarity (percent) with another sequence.
+    pub fn intergenomic_similarity(&self, other: &SequenceHandle) -> f64 {
+        pair_identity(&self.encoded, &other.encoded)
+            .similarity(self.encoded.len(), other.encoded.len())
+    }
+}
+
+/// All-vs-all intergenomic similarities with species and genus clusters.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct IntergenomicResult {
+    names: Vec<String>,
+    lengths: Vec<u32>,
+    similarity: Vec<f32>,
+    aligned_fraction: Vec<f32>,
+    species: Vec<i32>,
+    genus: Vec<i32>,
+    dendrogram: Option<Dendrogram>,
+    error: Option<String>,
+}
+
+impl IntergenomicResult {
+    fn failed(message: String) -> IntergenomicResult {
+        IntergenomicResult {
+            names: Vec::new(),
+            lengths: Vec::new(),
+            similarity: Vec::new(),
+            aligned_fraction: Vec::new(),
+            species: Vec::new(),
+            genus: Vec::new(),
+            dendrogram: None,
+            error: Some(message),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl IntergenomicResult {
+    /// Number of genomes.
+    #[wasm_bindgen(getter)]
+    pub fn n(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Genome names as a JSON array.
+    #[wasm_bindgen(getter)]
+    pub fn names_json(&self) -> String {
+        json_string_array(&self.names)
+    }
+
+    /// Genome lengths as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn lengths(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.lengths.len() as u32);
+        arr.copy_from(&self.lengths);
+        arr
+    }
+
+    /// Condensed similarity matrix in percent as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn similarity(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.similarity.len() as u32);
+        arr.copy_from(&self.similarity);
+        arr
+    }
+
+    /// Condensed distance matrix (100 - similarity) as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn distance(&self) -> js_sys::Float32Array {
+        let distance: Vec<f32> = self.similarity.iter().map(|s| 100.0 - s).collect();
+        let arr = js_sys::Float32Array::new_with_length(distance.len() as u32);
+        arr.copy_from(&distance);
+        arr
+    }
+
+    /// Fraction of genome i covered by hits against genome j, as a full
+    /// row-major n × n Float32Array (diagonal = 1).
+    #[wasm_bindgen(getter)]
+    pub fn aligned_fraction(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.aligned_fraction.len() as u32);
+        arr.copy_from(&self.aligned_fraction);
+        arr
+    }
+
+    /// Species cluster per genome (>= 95% similarity) as Int32Array.
+    #[wasm_bindgen(getter)]
+    pub fn species(&self) -> js_sys::Int32Array {
+        let arr = js_sys::Int32Array::new_with_length(self.species.len() as u32);
+        arr.copy_from(&self.species);
+        arr
+    }
+
+    /// Genus cluster per genome (>= 70% similarity) as Int32Array.
+    #[wasm_bindgen(getter)]
+    pub fn genus(&self) -> js_sys::Int32Array {
+        let arr = js_sys::Int32Array::new_with_length(self.genus.len() as u32);
+        arr.copy_from(&self.genus);
+        arr
+    }
+
+    /// Number of species clusters.
+    #[wasm_bindgen(getter)]
+    pub fn species_count(&self) -> usize {
+        self.species.iter().max().map_or(0, |&m| m as usize + 1)
+    }
+
+    /// Number of genus clusters.
+    #[wasm_bindgen(getter)]
+    pub fn genus_count(&self) -> usize {
+        self.genus.iter().max().map_or(0, |&m| m as usize + 1)
+    }
+
+    /// Clusters at a custom similarity threshold (percent) as Int32Array.
+    pub fn clusters_at(&self, similarity_threshold: f64) -> js_sys::Int32Array {
+        let labels = self
+            .dendrogram
+            .as_ref()
+            .map(|d| d.labels(100.0 - similarity_threshold))
+            .unwrap_or_default();
+        let arr = js_sys::Int32Array::new_with_length(labels.len() as u32);
+        arr.copy_from(&labels);
+        arr
+    }
+
+    /// Error message if the computation failed.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// All-vs-all VIRIDIC-style similarity with ICTV species/genus clustering.
+///
+/// # Arguments
+/// * `fasta` - Multi-FASTA of complete genomes
+/// * `linkage` - Linkage used to cluster on distance `100 - similarity`
+///
+/// # Returns
+/// `IntergenomicResult` with condensed similarities and cluster labels at
+/// the 95% (species) and 70% (genus) thresholds.
+#[wasm_bindgen]
+pub fn intergenomic_similarity_matrix(fasta: &str, linkage: Linkage) -> IntergenomicResult {
+    let records = parse_fasta(fasta, false);
+    let n = records.len();
+    if n == 0 {
+        return IntergenomicResult::failed("No sequences found".to_string());
+    }
+    if n > VIRIDIC_MAX_GENOMES {
+        return IntergenomicResult::failed(format!(
+            "Too many genomes: {} (max {})",
+            n, VIRIDIC_MAX_GENOMES
+        ));
+    }
+
+    let genomes: Vec<Vec<u8>> = records
+        .iter()
+        .map(|r| r.residues.iter().map(|&b| encode_base(b)).collect())
+        .collect();
+    let indexes: Vec<SeedIndex> = genomes.iter().map(|g| SeedIndex::new(g)).collect();
+
+    let mut similarity = vec![0.0f32; n * (n - 1) / 2];
+    let mut aligned_fraction = vec![1.0f32; n * n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let pair = compare_indexed(&genomes[i], &genomes[j], &indexes[j]);
+            let (li, lj) = (genomes[i].len(), genomes[j].len());
+            similarity[condensed_index(n, i, j)] = pair.similarity(li, lj) as f32;
+            aligned_fraction[i * n + j] = pair.aligned_a as f32 / li.max(1) as f32;
+            aligned_fraction[j * n + i] = pair.aligned_b as f32 / lj.max(1) as f32;
+        }
+    }
+
+    let distance: Vec<f64> = similarity.iter().map(|&s| 100.0 - s as f64).collect();
+    let dendrogram = Dendrogram::from_condensed(&distance, n, linkage);
+    IntergenomicResult {
+        names: records.iter().map(|r| r.name.clone()).collect(),
+        lengths: genomes.iter().map(|g| g.len() as u32).collect(),
+        species: dendrogram.labels(100.0 - VIRIDIC_SPECIES_THRESHOLD),
+        genus: dendrogram.labels(100.0 - VIRIDIC_GENUS_THRESHOLD),
+        similarity,
+        aligned_fraction,
+        dendrogram: Some(dendrogram),
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod intergenomic_tests {
+    use super::*;
+    use crate::encode_base;
+    use crate::rng::{random_dna, SplitMix64};
+
+    /// Random sequence as 2-bit codes.
+    fn random_codes(len: usize, seed: u64) -> Vec<u8> {
+        random_dna(len, seed)
+            .iter()
+            .map(|&b| encode_base(b))
+            .collect()
+    }
+
+    /// Substitute roughly `rate` of the bases deterministically.
+    fn mutate(seq: &[u8], rate: f64, seed: u64) -> Vec<u8> {
+        let mut rng = SplitMix64::new(seed);
+        seq.iter()
+            .map(|&b| {
+                if rng.next_f64() < rate {
+                    (b + 1 + rng.below(3) as u8) % 4
+                } else {
+                    b
+                }
+            })
+            .collect()
+    }
+
+    fn to_fasta(records: &[(&str, &[u8])]) -> String {
+        records
+            .iter()
+            .map(|(name, seq)| {
+                let text: String = seq.iter().map(|&b| b"ACGT"[b as usize] as char).collect();
+                format!(">{}\n{}\n", name, text)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_identical_and_unrelated() {
+        let a = random_codes(5000, 1);
+        let same = pair_identity(&a, &a);
+        assert!((same.similarity(a.len(), a.len()) - 100.0).abs() < 1e-9);
+
+        let b = random_codes(5000, 2);
+        let unrelated = pair_identity(&a, &b).similarity(a.len(), b.len());
+        assert!(unrelated < 2.0, "unrelated similarity {}", unrelated);
+    }
+
+    #[test]
+    fn test_reverse_complement_is_similar() {
+        let a = random_codes(4000, 3);
+        let rc = reverse_complement_encoded(&a);
+        let sim = pair_identity(&a, &rc).similarity(a.len(), rc.len());
+        assert!((sim - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_similarity_tracks_divergence() {
+        let a = random_codes(8000, 4);
+        let b = mutate(&a, 0.03, 5);
+        let sim = pair_identity(&a, &b).similarity(a.len(), b.len());
+        assert!(sim > 95.0 && sim < 98.5, "similarity {}", sim);
+
+        // Only half of B is shared with A.
+        let mut half = a[..4000].to_vec();
+        half.extend(random_codes(4000, 6));
+        let sim = pair_identity(&a, &half).similarity(a.len(), half.len());
+        assert!((sim - 50.0).abs() < 2.0, "similarity {}", sim);
+    }
+
+    #[test]
+    fn test_species_and_genus_clusters() {
+        let root = random_codes(6000, 7);
+        let sp1 = mutate(&root, 0.01, 8);
+        let sp2 = mutate(&root, 0.01, 9);
+        let cousin = mutate(&root, 0.12, 10);
+        let other = random_codes(6000, 11);
+        let fasta = to_fasta(&[
+            ("sp1", &sp1),
+            ("sp2", &sp2),
+            ("cousin", &cousin),
+            ("other", &other),
+        ]);
+        let result = intergenomic_similarity_matrix(&fasta, Linkage::Average);
+        assert!(result.error.is_none());
+        assert_eq!(result.species, vec![0, 0, 1, 2]);
+        assert_eq!(result.genus, vec![0, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,397 @@
+//! Significance calling on `scan_kl_windows` output.
+//!
+//! Each window gets a one-sided p-value for "more divergent from the genome
+//! background than expected", from one of two nulls:
+//!
+//! - Robust z-score: (KL - median) / MAD over all windows, normal tail.
+//!   Fast and needs only the scan, but assumes most windows are typical
+//! - Dinucleotide-preserving shuffles (Altschul & Erickson 1985): the
+//!   genome is shuffled keeping every dinucleotide count, rescanned with
+//!   the same parameters, and the pooled shuffled KL values form an
+//!   empirical null. It captures the sampling noise of a window of this
+//!   size at this k, which the robust null cannot
+//!
+//! P-values are Benjamini–Hochberg adjusted across windows; windows with
+//! q <= alpha that overlap or touch are merged into regions.
+
+use wasm_bindgen::prelude::*;
+
+use crate::rng::SplitMix64;
+use crate::stats::{benjamini_hochberg, median_and_mad, normal_sf};
+use crate::{scan_kl_windows, KLScanResult};
+
+/// Significant regions of a KL window scan.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct KlAnomalyCalls {
+    window_p_values: Vec<f64>,
+    window_q_values: Vec<f64>,
+    starts: Vec<u32>,
+    ends: Vec<u32>,
+    peak_kl: Vec<f64>,
+    peak_positions: Vec<u32>,
+    p_values: Vec<f64>,
+    q_values: Vec<f64>,
+    window_counts: Vec<u32>,
+    error: Option<String>,
+}
+
+impl KlAnomalyCalls {
+    fn failed(error: String) -> KlAnomalyCalls {
+        KlAnomalyCalls {
+            window_p_values: Vec::new(),
+            window_q_values: Vec::new(),
+            starts: Vec::new(),
+            ends: Vec::new(),
+            peak_kl: Vec::new(),
+            peak_positions: Vec::new(),
+            p_values: Vec::new(),
+            q_values: Vec::new(),
+            window_counts: Vec::new(),
+            error: Some(error),
+        }
+    }
+
+    /// Adjust window p-values and merge significant windows into regions.
+    fn from_p_values(scan: &KLScanResult, p_values: Vec<f64>, alpha: f64) -> KlAnomalyCalls {
+        let q_values = benjamini_hochberg(&p_values);
+        let mut calls = KlAnomalyCalls::failed(String::new());
+        calls.error = None;
+        for i in 0..scan.kl_values.len() {
+            if q_values[i].is_nan() || q_values[i] > alpha {
+                continue;
+            }
+            let start = scan.positions[i];
+            let end = start + scan.window_size as u32;
+            let kl = scan.kl_values[i] as f64;
+            let extends = calls.ends.last().is_some_and(|&last| start <= last);
+            if extends {
+                let r = calls.starts.len() - 1;
+                calls.ends[r] = end;
+                if kl > calls.peak_kl[r] {
+                    calls.peak_kl[r] = kl;
+                    calls.peak_positions[r] = start;
+                }
+                calls.p_values[r] = calls.p_values[r].min(p_values[i]);
+                calls.q_values[r] = calls.q_values[r].min(q_values[i]);
+                calls.window_counts[r] += 1;
+            } else {
+                calls.starts.push(start);
+                calls.ends.push(end);
+                calls.peak_kl.push(kl);
+                calls.peak_positions.push(start);
+                calls.p_values.push(p_values[i]);
+                calls.q_values.push(q_values[i]);
+                calls.window_counts.push(1);
+            }
+        }
+        calls.window_p_values = p_values;
+        calls.window_q_values = q_values;
+        calls
+    }
+}
+
+#[wasm_bindgen]
+impl KlAnomalyCalls {
+    /// One-sided p-value per scanned window as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn window_p_values(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.window_p_values.len() as u32);
+        arr.copy_from(&self.window_p_values);
+        arr
+    }
+
+    /// Benjamini–Hochberg q-value per scanned window as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn window_q_values(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.window_q_values.len() as u32);
+        arr.copy_from(&self.window_q_values);
+        arr
+    }
+
+    /// Region start positions (bases) as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn starts(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.starts.len() as u32);
+        arr.copy_from(&self.starts);
+        arr
+    }
+
+    /// Region end positions (bases, exclusive) as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn ends(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.ends.len() as u32);
+        arr.copy_from(&self.ends);
+        arr
+    }
+
+    /// Highest window KL in each region as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn peak_kl(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.peak_kl.len() as u32);
+        arr.copy_from(&self.peak_kl);
+        arr
+    }
+
+    /// Start of the peak window in each region as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn peak_positions(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.peak_positions.len() as u32);
+        arr.copy_from(&self.peak_positions);
+        arr
+    }
+
+    /// Smallest window p-value in each region as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn p_values(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.p_values.len() as u32);
+        arr.copy_from(&self.p_values);
+        arr
+    }
+
+    /// Smallest window q-value in each region as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn q_values(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.q_values.len() as u32);
+        arr.copy_from(&self.q_values);
+        arr
+    }
+
+    /// Number of significant windows merged into each region.
+    #[wasm_bindgen(getter)]
+    pub fn window_counts(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.window_counts.len() as u32);
+        arr.copy_from(&self.window_counts);
+        arr
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn region_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Error message for invalid input.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+fn check_scan(scan: &KLScanResult, alpha: f64) -> Result<(), String> {
+    if scan.kl_values.is_empty() {
+        return Err("scan has no windows".to_string());
+    }
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err(format!("alpha must be in [0, 1] (got {})", alpha));
+    }
+    Ok(())
+}
+
+/// Call anomalous windows against a robust z-score null.
+///
+/// z = (KL - median) / (1.4826 · MAD) over the scan's windows; the p-value
+/// is the upper normal tail. KL values are right-skewed, so treat these
+/// p-values as a ranking with approximate calibration.
+///
+/// # Arguments
+/// * `scan` - Result of `scan_kl_windows`
+/// * `alpha` - FDR level for calling windows
+#[wasm_bindgen]
+pub fn call_kl_anomalies_robust(scan: &KLScanResult, alpha: f64) -> KlAnomalyCalls {
+    if let Err(error) = check_scan(scan, alpha) {
+        return KlAnomalyCalls::failed(error);
+    }
+    let values: Vec<f64> = scan.kl_values.iter().map(|&v| v as f64).collect();
+    let (center, scale) = median_and_mad(&values);
+    let p_values = values
+        .iter()
+        .map(|&v| {
+            if scale > 0.0 {
+                normal_sf((v - center) / scale)
+            } else if v > center {
+                0.0
+            } else {
+                1.0
+            }
+        })
+        .collect();
+    KlAnomalyCalls::from_p_values(scan, p_values, alpha)
+}
+
+/// Random sequence with exactly the dinucleotide counts of `seq`, the same
+/// first and last byte (Altschul–Erickson via a random Eulerian walk).
+pub(crate) fn dinucleotide_shuffle(seq: &[u8], rng: &mut SplitMix64) -> Vec<u8> {
+    if seq.len() < 3 {
+        return seq.to_vec();
+    }
+    let mut edges: Vec<Vec<u8>> = vec![Vec::new(); 256];
+    for pair in seq.windows(2) {
+        edges[pair[0] as usize].push(pair[1]);
+    }
+    // Random arborescence of last edges towards the final byte (Wilson's
+    // loop-erased walks), so the walk below never gets stuck early.
+    let last = *seq.last().unwrap() as usize;
+    let mut in_tree = vec![false; 256];
+    let mut next = vec![0usize; 256];
+    in_tree[last] = true;
+    for u in 0..256 {
+        let mut cur = u;
+        while !in_tree[cur] && !edges[cur].is_empty() {
+            next[cur] = rng.below(edges[cur].len());
+            cur = edges[cur][next[cur]] as usize;
+        }
+        let mut cur = u;
+        while !in_tree[cur] && !edges[cur].is_empty() {
+            in_tree[cur] = true;
+            cur = edges[cur][next[cur]] as usize;
+        }
+    }
+    for (u, list) in edges.iter_mut().enumerate() {
+        let mut free = list.len();
+        if u != last && free > 0 {
+            list.swap(next[u], free - 1);
+            free -= 1;
+        }
+        for i in (1..free).rev() {
+            let j = rng.below(i + 1);
+            list.swap(i, j);
+        }
+    }
+
+    let mut used = vec![0usize; 256];
+    let mut out = Vec::with_capacity(seq.len());
+    let mut cur = seq[0];
+    out.push(cur);
+    for _ in 1..seq.len() {
+        let u = cur as usize;
+        cur = edges[u][used[u]];
+        used[u] += 1;
+        out.push(cur);
+    }
+    out
+}
+
+/// Call anomalous windows against dinucleotide-shuffled genomes.
+///
+/// Each shuffle is rescanned with the scan's k, window and step; a window's
+/// p-value is (1 + #null ≥ KL) / (1 + #null) over the pooled null windows,
+/// so the smallest attainable p-value is about 1 / (shuffles × windows).
+///
+/// # Arguments
+/// * `seq` - The sequence that was scanned
+/// * `scan` - Result of `scan_kl_windows(seq, ...)`
+/// * `shuffles` - Number of shuffled genomes (>= 1)
+/// * `seed` - RNG seed
+/// * `alpha` - FDR level for calling windows
+#[wasm_bindgen]
+pub fn call_kl_anomalies_shuffled(
+    seq: &[u8],
+    scan: &KLScanResult,
+    shuffles: usize,
+    seed: u64,
+    alpha: f64,
+) -> KlAnomalyCalls {
+    if let Err(error) = check_scan(scan, alpha) {
+        return KlAnomalyCalls::failed(error);
+    }
+    if shuffles == 0 {
+        return KlAnomalyCalls::failed("shuffles must be at least 1".to_string());
+    }
+    let last_window = scan.positions.last().map_or(0, |&p| p as usize) + scan.window_size;
+    if last_window > seq.len() {
+        return KlAnomalyCalls::failed(format!(
+            "scan covers {} bases but the sequence has {}",
+            last_window,
+            seq.len()
+        ));
+    }
+
+    let mut rng = SplitMix64::new(seed);
+    let mut null: Vec<f32> = Vec::with_capacity(shuffles * scan.kl_values.len());
+    for _ in 0..shuffles {
+        let shuffled = dinucleotide_shuffle(seq, &mut rng);
+        let rescan = scan_kl_windows(&shuffled, scan.k, scan.window_size, scan.step_size);
+        null.extend_from_slice(&rescan.kl_values);
+    }
+    null.sort_by(f32::total_cmp);
+    let total = null.len() as f64;
+    let p_values = scan
+        .kl_values
+        .iter()
+        .map(|&v| {
+            let below = null.partition_point(|&x| x < v);
+            (1.0 + (null.len() - below) as f64) / (1.0 + total)
+        })
+        .collect();
+    KlAnomalyCalls::from_p_values(scan, p_values, alpha)
+}
+
+#[cfg(test)]
+mod kl_anomaly_tests {
+    use super::*;
+
+    /// 60 kb of uniform sequence with an AT-rich island at 30-33 kb.
+    fn genome_with_island() -> Vec<u8> {
+        let mut rng = SplitMix64::new(21);
+        (0..60_000)
+            .map(|i| {
+                if (30_000..33_000).contains(&i) && rng.next_f64() < 0.8 {
+                    b"AT"[rng.below(2)]
+                } else {
+                    b"ACGT"[rng.below(4)]
+                }
+            })
+            .collect()
+    }
+
+    fn overlaps_island(calls: &KlAnomalyCalls) -> bool {
+        calls
+            .starts
+            .iter()
+            .zip(&calls.ends)
+            .any(|(&s, &e)| s < 33_000 && e > 30_000)
+    }
+
+    #[test]
+    fn test_dinucleotide_shuffle_preserves_pairs() {
+        let mut rng = SplitMix64::new(4);
+        let seq: Vec<u8> = (0..5_000).map(|_| b"ACGTN"[rng.below(5)]).collect();
+        let shuffled = dinucleotide_shuffle(&seq, &mut rng);
+        assert_ne!(shuffled, seq);
+        let pairs = |s: &[u8]| {
+            let mut counts = std::collections::HashMap::new();
+            for w in s.windows(2) {
+                *counts.entry((w[0], w[1])).or_insert(0) += 1;
+            }
+            counts
+        };
+        assert_eq!(pairs(&shuffled), pairs(&seq));
+        assert_eq!(shuffled.first(), seq.first());
+        assert_eq!(shuffled.last(), seq.last());
+    }
+
+    #[test]
+    fn test_robust_and_shuffled_calls_find_island() {
+        let seq = genome_with_island();
+        let scan = scan_kl_windows(&seq, 3, 1_000, 1_000);
+        let robust = call_kl_anomalies_robust(&scan, 0.05);
+        assert!(robust.error.is_none());
+        assert!(overlaps_island(&robust));
+        assert!(robust.starts.len() <= 2);
+
+        let shuffled = call_kl_anomalies_shuffled(&seq, &scan, 5, 9, 0.05);
+        assert!(shuffled.error.is_none());
+        assert!(overlaps_island(&shuffled));
+        assert_eq!(shuffled.window_p_values.len(), scan.kl_values.len());
+        let strongest = (0..shuffled.peak_kl.len())
+            .max_by(|&a, &b| shuffled.peak_kl[a].total_cmp(&shuffled.peak_kl[b]))
+            .unwrap();
+        let island = shuffled.peak_positions[strongest] as usize;
+        assert!((30_000..33_000).contains(&island));
+        assert!(call_kl_anomalies_shuffled(&seq[..1_000], &scan, 5, 9, 0.05)
+            .error
+            .is_some());
+    }
+}
@@ -0,0 +1,492 @@
+//! Sparse k-mer counting for k up to 31.
+//!
+//! `count_kmers_dense` allocates 4^k counters and stops at
+//! `DENSE_KMER_MAX_K`. For larger k only the observed k-mers are stored:
+//! 2-bit packed u64 codes in an open-addressing (linear probing) table,
+//! so memory scales with the number of distinct k-mers instead of 4^k.
+//!
+//! Canonical mode counts min(forward, reverse complement), merging both
+//! strands. Windows containing ambiguous bases are skipped.
+
+use wasm_bindgen::prelude::*;
+
+use crate::fracminhash::frac_hash;
+use crate::minimizers::for_each_kmer;
+use crate::spaced_seeds::{count_spaced_into, parse_seeds, SpacedSeed};
+use crate::{encode_base, SequenceHandle};
+
+/// Maximum k for sparse counting (2k bits must leave the empty sentinel free).
+pub(crate) const SPARSE_KMER_MAX_K: usize = 31;
+
+/// Slot marker for empty table entries (never a valid code for k <= 31).
+const EMPTY_KEY: u64 = u64::MAX;
+
+/// Initial table capacity (power of two).
+const TABLE_MIN_CAPACITY: usize = 1024;
+
+/// Open-addressing k-mer → count table.
+#[derive(Clone)]
+pub(crate) struct KmerTable {
+    keys: Vec<u64>,
+    counts: Vec<u32>,
+    len: usize,
+}
+
+impl KmerTable {
+    pub(crate) fn with_capacity(expected: usize) -> KmerTable {
+        let capacity = (expected * 10 / 7 + 1)
+            .next_power_of_two()
+            .max(TABLE_MIN_CAPACITY);
+        KmerTable {
+            keys: vec![EMPTY_KEY; capacity],
+            counts: vec![0; capacity],
+            len: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn slot(&self, key: u64) -> usize {
+        let mask = self.keys.len() - 1;
+        let mut i = frac_hash(key, 0) as usize & mask;
+        while self.keys[i] != EMPTY_KEY && self.keys[i] != key {
+            i = (i + 1) & mask;
+        }
+        i
+    }
+
+    fn grow(&mut self) {
+        let old_keys = std::mem::take(&mut self.keys);
+        let old_counts = std::mem::take(&mut self.counts);
+        self.keys = vec![EMPTY_KEY; old_keys.len() * 2];
+        self.counts = vec![0; old_keys.len() * 2];
+        for (key, count) in old_keys.into_iter().zip(old_counts) {
+            if key != EMPTY_KEY {
+                let i = self.slot(key);
+                self.keys[i] = key;
+                self.counts[i] = count;
+            }
+        }
+    }
+
+    /// Add `by` to the count of `key` (saturating).
+    #[inline]
+    pub(crate) fn add(&mut self, key: u64, by: u32) {
+        if (self.len + 1) * 10 > self.keys.len() * 7 {
+            self.grow();
+        }
+        let i = self.slot(key);
+        if self.keys[i] == EMPTY_KEY {
+            self.keys[i] = key;
+            self.len += 1;
+        }
+        self.counts[i] = self.counts[i].saturating_add(by);
+    }
+
+    /// Count of `key` (0 if absent).
+    #[inline]
+    pub(crate) fn get(&self, key: u64) -> u32 {
+        let i = self.slot(key);
+        if self.keys[i] == key {
+            self.counts[i]
+        } else {
+            0
+        }
+    }
+
+    /// Number of distinct k-mers.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// (code, count) for every stored k-mer, in table order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        self.keys
+            .iter()
+            .zip(self.counts.iter())
+            .filter(|(&key, _)| key != EMPTY_KEY)
+            .map(|(&key, &count)| (key, count))
+    }
+}
+
+/// Decode a 2-bit packed k-mer to ACGT text.
+pub(crate) fn decode_kmer(code: u64, k: usize) -> String {
+    (0..k)
+        .map(|i| b"ACGT"[((code >> (2 * (k - 1 - i))) & 3) as usize] as char)
+        .collect()
+}
+
+/// Encode ACGT text to a 2-bit packed code (None on ambiguous bases or k > 31).
+pub(crate) fn encode_kmer(kmer: &[u8]) -> Option<u64> {
+    if kmer.is_empty() || kmer.len() > SPARSE_KMER_MAX_K {
+        return None;
+    }
+    kmer.iter().try_fold(0u64, |code, &b| {
+        let base = encode_base(b);
+        (base <= 3).then_some((code << 2) | base as u64)
+    })
+}
+
+/// Count k-mers of an encoded sequence into `table`; returns valid windows.
+pub(crate) fn count_encoded_into(
+    table: &mut KmerTable,
+    encoded: &[u8],
+    k: usize,
+    canonical: bool,
+) -> u64 {
+    let mut total = 0u64;
+    for_each_kmer(encoded, k, |_, fwd, rc| {
+        table.add(if canonical { fwd.min(rc) } else { fwd }, 1);
+        total += 1;
+    });
+    total
+}
+
+/// Count k-mers of an ASCII sequence (1 <= k <= 31).
+pub(crate) fn count_sequence(seq: &[u8], k: usize, canonical: bool) -> KmerTable {
+    let encoded: Vec<u8> = seq.iter().map(|&b| encode_base(b)).collect();
+    let mut table = KmerTable::with_capacity(seq.len().min(1 << 20));
+    count_encoded_into(&mut table, &encoded, k, canonical);
+    table
+}
+
+/// Paired counts over the union of two tables: A's k-mers first, then B-only.
+pub(crate) fn union_counts(a: &KmerTable, b: &KmerTable) -> Vec<(u32, u32)> {
+    let mut pairs: Vec<(u32, u32)> = a.iter().map(|(key, ca)| (ca, b.get(key))).collect();
+    pairs.extend(
+        b.iter()
+            .filter(|&(key, _)| a.get(key) == 0)
+            .map(|(_, cb)| (0, cb)),
+    );
+    pairs
+}
+
+/// Sparse k-mer counts.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct SparseKmerCounts {
+    pub(crate) table: KmerTable,
+    pub(crate) k: usize,
+    canonical: bool,
+    total_valid: u64,
+    /// Spaced seeds (empty = contiguous k-mers).
+    seeds: Vec<SpacedSeed>,
+    error: Option<String>,
+}
+
+impl SparseKmerCounts {
+    pub(crate) fn new(k: usize, canonical: bool) -> SparseKmerCounts {
+        let error = (k == 0 || k > SPARSE_KMER_MAX_K)
+            .then(|| format!("k must be 1..={} (got {})", SPARSE_KMER_MAX_K, k));
+        SparseKmerCounts {
+            table: KmerTable::with_capacity(0),
+            k,
+            canonical,
+            total_valid: 0,
+            seeds: Vec::new(),
+            error,
+        }
+    }
+
+    /// Counter over spaced-seed words; `k` is the seed weight.
+    pub(crate) fn spaced(patterns: &str, canonical: bool) -> SparseKmerCounts {
+        match parse_seeds(patterns) {
+            Ok(seeds) => SparseKmerCounts {
+                k: seeds[0].weight(),
+                seeds,
+                ..SparseKmerCounts::new(1, canonical)
+            },
+            Err(error) => SparseKmerCounts {
+                k: 0,
+                error: Some(error),
+                ..SparseKmerCounts::new(1, canonical)
+            },
+        }
+    }
+
+    pub(crate) fn add_encoded(&mut self, encoded: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        self.total_valid += if self.seeds.is_empty() {
+            count_encoded_into(&mut self.table, encoded, self.k, self.canonical)
+        } else {
+            count_spaced_into(&mut self.table, encoded, &self.seeds, self.canonical)
+        };
+    }
+
+    /// Distinct k-mers per abundance, index = count, last bin = `>= max_count`.
+    pub(crate) fn histogram(&self, max_count: usize) -> Vec<u32> {
+        let max_count = max_count.max(1);
+        let mut hist = vec![0u32; max_count + 1];
+        for (_, count) in self.table.iter() {
+            hist[(count as usize).min(max_count)] += 1;
+        }
+        hist
+    }
+
+    /// The `n` most abundant k-mers (count descending, code ascending).
+    pub(crate) fn top(&self, n: usize) -> Vec<(u64, u32)> {
+        let mut entries: Vec<(u64, u32)> = self.table.iter().collect();
+        entries.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        entries.truncate(n);
+        entries
+    }
+}
+
+#[wasm_bindgen]
+impl SparseKmerCounts {
+    /// Count more sequence (ASCII bytes) into the table, e.g. one read at a time.
+    pub fn add_sequence(&mut self, seq: &[u8]) {
+        let encoded: Vec<u8> = seq.iter().map(|&b| encode_base(b)).collect();
+        self.add_encoded(&encoded);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Total valid k-mer windows counted.
+    #[wasm_bindgen(getter)]
+    pub fn total_valid(&self) -> u64 {
+        self.total_valid
+    }
+
+    /// Number of distinct k-mers.
+    #[wasm_bindgen(getter)]
+    pub fn distinct_count(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Count of a k-mer given as text (reverse complement folded in canonical mode).
+    ///
+    /// For spaced seeds the text is the care-position bases of the first
+    /// seed; with canonical asymmetric seeds the opposite-strand word depends
+    /// on other bases, so only the forward word is looked up.
+    pub fn get(&self, kmer: &str) -> u32 {
+        if kmer.len() != self.k {
+            return 0;
+        }
+        let Some(fwd) = encode_kmer(kmer.as_bytes()) else {
+            return 0;
+        };
+        let folds = self.seeds.first().is_none_or(|seed| seed.is_symmetric());
+        let key = if self.canonical && folds {
+            let rc = (0..self.k).fold(0u64, |acc, i| (acc << 2) | (3 - ((fwd >> (2 * i)) & 3)));
+            fwd.min(rc)
+        } else {
+            fwd
+        };
+        self.table.get(key)
+    }
+
+    /// Number of solid k-mers (count >= `min_count`).
+    pub fn solid_count(&self, min_count: u32) -> usize {
+        self.table.iter().filter(|&(_, c)| c >= min_count).count()
+    }
+
+    /// Abundance histogram as Uint32Array of length `max_count + 1`:
+    /// entry c = number of distinct k-mers seen exactly c times, last entry
+    /// aggregates counts >= `max_count`. Entry 0 is always 0.
+    pub fn histogram_counts(&self, max_count: usize) -> js_sys::Uint32Array {
+        let hist = self.histogram(max_count);
+        let arr = js_sys::Uint32Array::new_with_length(hist.len() as u32);
+        arr.copy_from(&hist);
+        arr
+    }
+
+    /// The `n` most abundant k-mers.
+    pub fn top_kmers(&self, n: usize) -> TopKmers {
+        let top = self.top(n);
+        TopKmers {
+            kmers: top
+                .iter()
+                .map(|&(code, _)| decode_kmer(code, self.k))
+                .collect(),
+            codes: top.iter().map(|&(code, _)| code).collect(),
+            counts: top.iter().map(|&(_, count)| count).collect(),
+        }
+    }
+
+    /// Error message if k was invalid.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Most abundant k-mers, ordered by count descending.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct TopKmers {
+    kmers: Vec<String>,
+    codes: Vec<u64>,
+    counts: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl TopKmers {
+    /// K-mer strings as a JSON array.
+    #[wasm_bindgen(getter)]
+    pub fn kmers_json(&self) -> String {
+        let quoted: Vec<String> = self.kmers.iter().map(|k| format!("\"{}\"", k)).collect();
+        format!("[{}]", quoted.join(","))
+    }
+
+    /// 2-bit packed k-mer codes (A=0, C=1, G=2, T=3) as BigUint64Array.
+    #[wasm_bindgen(getter)]
+    pub fn codes(&self) -> js_sys::BigUint64Array {
+        let arr = js_sys::BigUint64Array::new_with_length(self.codes.len() as u32);
+        arr.copy_from(&self.codes);
+        arr
+    }
+
+    /// Counts as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn counts(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.counts.len() as u32);
+        arr.copy_from(&self.counts);
+        arr
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> usize {
+        self.counts.len()
+    }
+}
+
+/// Sparse k-mer counting for 1 <= k <= 31.
+///
+/// # Arguments
+/// * `seq` - Sequence as bytes (ASCII, case-insensitive, U counts as T)
+/// * `k` - K-mer size (1..=31)
+/// * `canonical` - Merge each k-mer with its reverse complement
+///
+/// # Returns
+/// `SparseKmerCounts`; `error` is set for invalid k. More sequence can be
+/// added with `add_sequence`.
+#[wasm_bindgen]
+pub fn count_kmers_sparse(seq: &[u8], k: usize, canonical: bool) -> SparseKmerCounts {
+    let mut counts = SparseKmerCounts::new(k, canonical);
+    counts.add_sequence(seq);
+    counts
+}
+
+#[wasm_bindgen]
+impl SequenceHandle {
+    /// Sparse k-mer counts of the pre-encoded sequence (1 <= k <= 31).
+    pub fn count_kmers_sparse(&self, k: usize, canonical: bool) -> SparseKmerCounts {
+        let mut counts = SparseKmerCounts::new(k, canonical);
+        counts.add_encoded(&self.encoded);
+        counts
+    }
+}
+
+#[cfg(test)]
+mod kmer_counter_tests {
+    use super::*;
+    use crate::rng::random_dna;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_matches_naive_counts() {
+        let mut seq = random_dna(3000, 1);
+        seq.extend_from_slice(&seq.clone()[..500]);
+        seq[1200] = b'N';
+        let k = 21;
+        let counts = count_kmers_sparse(&seq, k, false);
+        let mut naive: HashMap<&[u8], u32> = HashMap::new();
+        for window in seq.windows(k) {
+            if !window.contains(&b'N') {
+                *naive.entry(window).or_default() += 1;
+            }
+        }
+        assert_eq!(counts.distinct_count(), naive.len());
+        assert_eq!(counts.total_valid() as u32, naive.values().sum::<u32>());
+        for (kmer, &c) in &naive {
+            assert_eq!(counts.get(std::str::from_utf8(kmer).unwrap()), c);
+        }
+    }
+
+    #[test]
+    fn test_canonical_merges_strands() {
+        let counts = count_kmers_sparse(b"AACCGGTT", 3, true);
+        // AAC/GTT, ACC/GGT, CCG/CGG, CGG/CCG, GGT/ACC, GTT/AAC
+        assert_eq!(counts.get("AAC"), 2);
+        assert_eq!(counts.get("GTT"), 2);
+        assert_eq!(counts.get("CCG"), 2);
+        assert_eq!(counts.distinct_count(), 3);
+        assert!(count_kmers_sparse(b"ACGT", 40, false).error.is_some());
+    }
+
+    #[test]
+    fn test_top_and_histogram() {
+        let mut counts = count_kmers_sparse(b"ACGTACGTACGT", 4, false);
+        counts.add_sequence(b"TTTTT");
+        let top = counts.top(2);
+        assert_eq!(decode_kmer(top[0].0, 4), "ACGT");
+        assert_eq!(top[0].1, 3);
+        assert_eq!(counts.histogram(3), vec![0, 0, 4, 1]);
+        assert_eq!(counts.solid_count(2), 5);
+        assert_eq!(counts.solid_count(3), 1);
+    }
+
+    #[test]
+    fn test_analyze_kmers_matches_string_counts() {
+        let a = String::from_utf8(random_dna(2000, 2)).unwrap();
+        let b = String::from_utf8(random_dna(2000, 3)).unwrap();
+        let k = 6;
+        let result = crate::analyze_kmers(&a, &b, k);
+        let freqs_a = crate::extract_kmer_freqs(&a, k);
+        let freqs_b = crate::extract_kmer_freqs(&b, k);
+        let shared = freqs_a.keys().filter(|km| freqs_b.contains_key(*km)).count();
+        assert_eq!(result.unique_kmers_a, freqs_a.len());
+        assert_eq!(result.unique_kmers_b, freqs_b.len());
+        assert_eq!(result.shared_kmers, shared);
+
+        let d = crate::kmer_hoeffdings_d(&a, &b, k);
+        assert_eq!(d.n, freqs_a.len() + freqs_b.len() - shared);
+    }
+
+    #[test]
+    fn test_analyze_kmers_skips_only_n_windows() {
+        // IUPAC codes, U and gaps stay part of the k-mer; only N windows drop.
+        let a = "ACGTRACGTUACGT-ACGTNACGT";
+        let b = "acgtracgtnACGTYACGT";
+        let k = 4;
+        let result = crate::analyze_kmers(a, b, k);
+        let freqs_a = crate::extract_kmer_freqs(a, k);
+        let freqs_b = crate::extract_kmer_freqs(b, k);
+        assert!(freqs_a.contains_key("GTRA") && freqs_a.contains_key("GTUA"));
+        assert!(!freqs_a.keys().any(|km| km.contains('N')));
+        let shared = freqs_a
+            .keys()
+            .filter(|km| freqs_b.contains_key(*km))
+            .count();
+        assert_eq!(result.unique_kmers_a, freqs_a.len());
+        assert_eq!(result.unique_kmers_b, freqs_b.len());
+        assert_eq!(result.shared_kmers, shared);
+    }
+
+    #[test]
+    fn test_table_growth_and_roundtrip() {
+        let mut table = KmerTable::with_capacity(0);
+        for key in 0..10_000u64 {
+            table.add(key * 7919, (key % 5) as u32 + 1);
+        }
+        assert_eq!(table.len(), 10_000);
+        assert_eq!(table.get(7919 * 42), 3);
+        assert_eq!(table.get(1), 0);
+        let code = encode_kmer(b"GATTACA").unwrap();
+        assert_eq!(decode_kmer(code, 7), "GATTACA");
+    }
+}
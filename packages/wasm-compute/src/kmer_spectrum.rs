@@ -0,0 +1,329 @@
+//! K-mer abundance spectra and genome-size / repeat estimates.
+//!
+//! The spectrum h(c) is the number of distinct k-mers seen exactly c times.
+//! For an assembled genome nearly all k-mers are single-copy, so h(1)
+//! dominates and repeats show up as h(c >= 2). For raw reads the spectrum
+//! has an error peak at low c, a valley, and a coverage peak at c ≈ λ
+//! (k-mer coverage); GenomeScope-style estimates follow from the peak:
+//!
+//! - genome size ≈ (k-mer occurrences above the valley) / λ
+//! - repeat fraction ≈ share of that size carried by k-mers with
+//!   c >= 1.5 λ (two or more genomic copies)
+//!
+//! The model is haploid; heterozygous half-coverage peaks are not fitted.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::kmer_counter::SparseKmerCounts;
+use crate::DenseKmerResult;
+
+/// Default number of histogram bins when 0 is passed.
+const SPECTRUM_DEFAULT_MAX_COUNT: usize = 1_000;
+
+/// K-mers at or above this multiple of the peak count as repeats.
+const SPECTRUM_REPEAT_MULTIPLE: f64 = 1.5;
+
+/// Abundance spectrum with derived metrics.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct KmerSpectrum {
+    k: usize,
+    /// Binned histogram (index = count, last bin aggregates >= max_count).
+    histogram: Vec<u32>,
+    total_kmers: u64,
+    distinct_kmers: u64,
+    singleton_kmers: u64,
+    valley: u32,
+    peak: u32,
+    genome_size: f64,
+    error_kmers: u64,
+    repeat_fraction: f64,
+}
+
+impl KmerSpectrum {
+    /// Build a spectrum from per-k-mer counts (zero counts are ignored).
+    pub(crate) fn from_counts(
+        counts: impl Iterator<Item = u32>,
+        k: usize,
+        max_count: usize,
+    ) -> KmerSpectrum {
+        let max_count = if max_count == 0 {
+            SPECTRUM_DEFAULT_MAX_COUNT
+        } else {
+            max_count
+        };
+
+        // Full-resolution sparse histogram for the estimates.
+        let mut sparse: HashMap<u32, u64> = HashMap::new();
+        for c in counts.filter(|&c| c > 0) {
+            *sparse.entry(c).or_default() += 1;
+        }
+        let mut full: Vec<(u32, u64)> = sparse.into_iter().collect();
+        full.sort_unstable();
+
+        let mut histogram = vec![0u32; max_count + 1];
+        for &(c, n) in &full {
+            let bin = (c as usize).min(max_count);
+            histogram[bin] = histogram[bin].saturating_add(n.min(u32::MAX as u64) as u32);
+        }
+
+        let total_kmers: u64 = full.iter().map(|&(c, n)| c as u64 * n).sum();
+        let distinct_kmers: u64 = full.iter().map(|&(_, n)| n).sum();
+        let singleton_kmers = full
+            .first()
+            .filter(|&&(c, _)| c == 1)
+            .map_or(0, |&(_, n)| n);
+
+        let (valley, peak) = find_valley_and_peak(&full);
+        let error_kmers: u64 = full
+            .iter()
+            .filter(|&&(c, _)| c <= valley)
+            .map(|&(c, n)| c as u64 * n)
+            .sum();
+        let (genome_size, repeat_fraction) = if peak == 0 {
+            (0.0, 0.0)
+        } else {
+            let lambda = peak as f64;
+            let size = (total_kmers - error_kmers) as f64 / lambda;
+            let repeat: f64 = full
+                .iter()
+                .filter(|&&(c, _)| c as f64 >= SPECTRUM_REPEAT_MULTIPLE * lambda)
+                .map(|&(c, n)| c as f64 * n as f64 / lambda)
+                .sum();
+            (size, if size > 0.0 { repeat / size } else { 0.0 })
+        };
+
+        KmerSpectrum {
+            k,
+            histogram,
+            total_kmers,
+            distinct_kmers,
+            singleton_kmers,
+            valley,
+            peak,
+            genome_size,
+            error_kmers,
+            repeat_fraction,
+        }
+    }
+}
+
+/// Valley after the error peak and the coverage peak beyond it.
+///
+/// `full` is the sorted sparse histogram (missing counts are zero). The
+/// valley is the count just before the histogram first rises. A rise only
+/// counts as a coverage peak if most k-mer occurrences lie beyond it;
+/// otherwise (assembled sequence, a few high-copy repeats) the valley is 0.
+fn find_valley_and_peak(full: &[(u32, u64)]) -> (u32, u32) {
+    let total: u64 = full.iter().map(|&(c, n)| c as u64 * n).sum();
+    let mut valley = 0;
+    let mut prev = (0u32, u64::MAX);
+    for &(c, n) in full {
+        // h(c - 1) is zero when there is a gap before c.
+        let before = if c == prev.0 + 1 { prev.1 } else { 0 };
+        if c > 1 && n > before {
+            valley = c - 1;
+            break;
+        }
+        prev = (c, n);
+    }
+    let beyond: u64 = full
+        .iter()
+        .filter(|&&(c, _)| c > valley)
+        .map(|&(c, n)| c as u64 * n)
+        .sum();
+    if beyond * 2 < total {
+        valley = 0;
+    }
+
+    let peak = full
+        .iter()
+        .filter(|&&(c, _)| c > valley)
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map_or(0, |&(c, _)| c);
+    (valley, peak)
+}
+
+#[wasm_bindgen]
+impl KmerSpectrum {
+    #[wasm_bindgen(getter)]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Histogram as Uint32Array: entry c = distinct k-mers seen c times,
+    /// last entry aggregates counts >= its index. Entry 0 is always 0.
+    #[wasm_bindgen(getter)]
+    pub fn histogram(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.histogram.len() as u32);
+        arr.copy_from(&self.histogram);
+        arr
+    }
+
+    /// Total k-mer occurrences.
+    #[wasm_bindgen(getter)]
+    pub fn total_kmers(&self) -> u64 {
+        self.total_kmers
+    }
+
+    /// Number of distinct k-mers.
+    #[wasm_bindgen(getter)]
+    pub fn distinct_kmers(&self) -> u64 {
+        self.distinct_kmers
+    }
+
+    /// Distinct k-mers / total occurrences.
+    #[wasm_bindgen(getter)]
+    pub fn distinct_fraction(&self) -> f64 {
+        if self.total_kmers == 0 {
+            0.0
+        } else {
+            self.distinct_kmers as f64 / self.total_kmers as f64
+        }
+    }
+
+    /// K-mers seen exactly once / distinct k-mers.
+    #[wasm_bindgen(getter)]
+    pub fn unique_fraction(&self) -> f64 {
+        if self.distinct_kmers == 0 {
+            0.0
+        } else {
+            self.singleton_kmers as f64 / self.distinct_kmers as f64
+        }
+    }
+
+    /// Count at the end of the error peak (0 if there is none).
+    #[wasm_bindgen(getter)]
+    pub fn valley(&self) -> u32 {
+        self.valley
+    }
+
+    /// K-mer coverage peak λ (1 for an assembled genome).
+    #[wasm_bindgen(getter)]
+    pub fn peak_coverage(&self) -> u32 {
+        self.peak
+    }
+
+    /// Estimated genome size in bases (haploid).
+    #[wasm_bindgen(getter)]
+    pub fn genome_size(&self) -> f64 {
+        self.genome_size
+    }
+
+    /// Fraction of k-mer occurrences at or below the valley (likely errors).
+    #[wasm_bindgen(getter)]
+    pub fn error_fraction(&self) -> f64 {
+        if self.total_kmers == 0 {
+            0.0
+        } else {
+            self.error_kmers as f64 / self.total_kmers as f64
+        }
+    }
+
+    /// Estimated fraction of the genome in multi-copy k-mers.
+    #[wasm_bindgen(getter)]
+    pub fn repeat_fraction(&self) -> f64 {
+        self.repeat_fraction
+    }
+}
+
+#[wasm_bindgen]
+impl SparseKmerCounts {
+    /// Abundance spectrum of the counted k-mers.
+    ///
+    /// # Arguments
+    /// * `max_count` - Number of histogram bins (0 = 1000); estimates always
+    ///   use full resolution
+    pub fn spectrum(&self, max_count: usize) -> KmerSpectrum {
+        KmerSpectrum::from_counts(self.table.iter().map(|(_, c)| c), self.k, max_count)
+    }
+}
+
+#[wasm_bindgen]
+impl DenseKmerResult {
+    /// Abundance spectrum of the dense counts.
+    ///
+    /// # Arguments
+    /// * `max_count` - Number of histogram bins (0 = 1000)
+    pub fn spectrum(&self, max_count: usize) -> KmerSpectrum {
+        KmerSpectrum::from_counts(self.counts.iter().copied(), self.k, max_count)
+    }
+}
+
+#[cfg(test)]
+mod kmer_spectrum_tests {
+    use super::*;
+    use crate::count_kmers_dense;
+    use crate::kmer_counter::count_kmers_sparse;
+    use crate::rng::{random_dna, SplitMix64};
+
+    #[test]
+    fn test_assembled_genome() {
+        let genome = random_dna(20_000, 1);
+        let spectrum = count_kmers_sparse(&genome, 21, true).spectrum(0);
+        assert_eq!(spectrum.peak, 1);
+        assert_eq!(spectrum.valley, 0);
+        assert!((spectrum.genome_size - 19_980.0).abs() < 50.0);
+        assert!(spectrum.unique_fraction() > 0.99);
+        assert!(spectrum.repeat_fraction < 0.01);
+
+        // Duplicate a 2 kb segment: ~4 kb of 22 kb is now two-copy.
+        let mut repeated = genome.clone();
+        repeated.extend_from_slice(&genome[5_000..7_000]);
+        let spectrum = count_kmers_sparse(&repeated, 21, true).spectrum(0);
+        let expected = 2.0 * 1_980.0 / 21_980.0;
+        assert!(
+            (spectrum.repeat_fraction - expected).abs() < 0.01,
+            "repeat fraction {}",
+            spectrum.repeat_fraction
+        );
+    }
+
+    #[test]
+    fn test_reads_coverage_peak_and_genome_size() {
+        let genome = random_dna(10_000, 2);
+        let mut rng = SplitMix64::new(3);
+        let (read_len, k, coverage) = (100, 21, 30);
+        let mut counts = count_kmers_sparse(b"", k, true);
+        for _ in 0..(genome.len() * coverage / read_len) {
+            let start = rng.below(genome.len() - read_len);
+            let mut read = genome[start..start + read_len].to_vec();
+            for base in read.iter_mut() {
+                if rng.below(100) == 0 {
+                    *base = b"ACGT"[rng.below(4)];
+                }
+            }
+            counts.add_sequence(&read);
+        }
+        let spectrum = counts.spectrum(200);
+        // Expected k-mer coverage: 30 × (100 - 21 + 1) / 100 × (0.99^21) ≈ 19.4
+        assert!(spectrum.valley >= 1 && spectrum.valley < 10);
+        assert!((15..=25).contains(&spectrum.peak), "peak {}", spectrum.peak);
+        let size = spectrum.genome_size;
+        assert!((size - 10_000.0).abs() < 1_500.0, "genome size {}", size);
+        assert!(spectrum.error_fraction() > 0.05);
+    }
+
+    #[test]
+    fn test_valley_requires_coverage_peak() {
+        // A few high-copy repeats in an assembly are not a coverage peak.
+        assert_eq!(find_valley_and_peak(&[(1, 1000), (2, 10), (5, 1)]), (0, 1));
+        // Error peak, valley at 3, coverage peak at 20.
+        let reads = [(1, 500), (2, 100), (3, 20), (4, 30), (20, 400), (21, 350)];
+        assert_eq!(find_valley_and_peak(&reads), (3, 20));
+    }
+
+    #[test]
+    fn test_dense_spectrum_histogram() {
+        let dense = count_kmers_dense(b"AAAAACCC", 2);
+        let spectrum = dense.spectrum(3);
+        // AA x4, AC x1, CC x2
+        assert_eq!(spectrum.histogram, vec![0, 1, 1, 1]);
+        assert_eq!(spectrum.total_kmers, 7);
+        assert_eq!(spectrum.distinct_kmers, 3);
+    }
+}
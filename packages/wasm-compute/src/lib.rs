@@ -2,9 +2,82 @@ use wasm_bindgen::prelude::*;
 use std::collections::HashMap;
 use js_sys;
 
+mod alignment_tracks;
+mod clustering;
+mod density;
+mod distance_matrix;
+mod edit_distance;
+mod embedding;
+mod fracminhash;
+mod hnsw;
+mod hyperloglog;
+mod information;
+mod intergenomic;
+mod kl_anomaly;
+mod kmer_counter;
+mod kmer_spectrum;
+mod minimizers;
+mod msa;
+mod partitioning;
+mod pca;
+mod phylo;
+mod rank_correlation;
 mod renderer;
-
+mod rng;
+mod segmentation;
+mod sketch;
+mod spaced_seeds;
+mod stats;
+mod tsne;
+mod umap;
+mod variants;
+
+pub use alignment_tracks::{alignment_tracks, AlignmentTracks};
+pub use clustering::{hierarchical_cluster, Dendrogram, Linkage};
+pub use density::{dbscan, dbscan_from_distances, hdbscan, hdbscan_from_distances};
+pub use distance_matrix::{CondensedDistanceMatrix, DistanceMetric, GenomeCollection};
+pub use edit_distance::{
+    approximate_pattern_search, edit_distance_banded, edit_distance_bitparallel,
+    ApproxMatchResult,
+};
+pub use fracminhash::{
+    frac_minhash_compare, frac_minhash_sketch, FracMinHashComparison, FracMinHashSketch,
+};
+pub use hnsw::{HnswIndex, HnswSearchResult, VectorMetric};
+pub use hyperloglog::HyperLogLog;
+pub use information::{
+    alignment_mutual_information, block_entropies, mutual_information_function, BlockEntropies,
+    ColumnCoupling,
+};
+pub use intergenomic::{intergenomic_similarity_matrix, IntergenomicResult};
+pub use kl_anomaly::{call_kl_anomalies_robust, call_kl_anomalies_shuffled, KlAnomalyCalls};
+pub use kmer_counter::{count_kmers_sparse, SparseKmerCounts, TopKmers};
+pub use kmer_spectrum::KmerSpectrum;
+pub use minimizers::KmerSample;
+pub use msa::{progressive_msa, MsaResult};
+pub use partitioning::{
+    kmeans, kmeans_silhouette_scan, kmedoids_from_distances, kmedoids_silhouette_scan,
+    silhouette_scores, silhouette_scores_from_distances, ClusterAssignment,
+};
+pub use pca::{pca_randomized, IncrementalPca, PcaModel, PcaTransform};
+pub use phylo::{build_tree, PhyloTree, TreeLayout, TreeMethod};
+pub use rank_correlation::{
+    distance_correlation, kendall_tau_b, kmer_distance_correlation, kmer_kendall_tau_b,
+    kmer_maximal_information_coefficient, kmer_spearman_rho, maximal_information_coefficient,
+    spearman_rho, CorrelationResult,
+};
 pub use renderer::{render_ascii_model, Model3D, Vector3};
+pub use segmentation::{
+    segment_composition_hmm, segment_track_binary, segment_track_pelt, Segmentation,
+};
+pub use sketch::{minhash_sketch, MinHashSketch, SketchDatabase, SketchQueryResult};
+pub use spaced_seeds::{analyze_spaced_kmers, count_spaced_kmers, minhash_signature_spaced};
+pub use stats::{adjust_p_values, storey_pi0, storey_q_values, PValueAdjustment};
+pub use tsne::{tsne_from_distances, tsne_from_features, TsneEmbedding};
+pub use umap::{umap_from_distances, umap_from_features, UmapEmbedding};
+pub use variants::{
+    call_variants, call_variants_from_diff, VariantCallResult, VariantEffect, VariantKind,
+};
 
 // ============================================================================
 // Core Genetics Functions - HOT PATH optimizations
@@ -220,6 +293,47 @@ fn serde_json_lite_encode(map: &HashMap<String, usize>) -> String {
     result
 }
 
+/// Encode strings as a JSON array, escaping quotes, backslashes and all
+/// control characters.
+pub(crate) fn json_string_array<S: AsRef<str>>(items: &[S]) -> String {
+    let mut out = String::from("[");
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('"');
+        for c in item.as_ref().chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+    out.push(']');
+    out
+}
+
+#[cfg(test)]
+mod json_tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string_array_escapes_control_characters() {
+        let names = ["plain", "tab\there", "line\nbreak", "quote\"back\\slash", "bell\u{7}"];
+        assert_eq!(
+            json_string_array(&names),
+            r#"["plain","tab\there","line\nbreak","quote\"back\\slash","bell\u0007"]"#
+        );
+        assert_eq!(json_string_array::<String>(&[]), "[]");
+    }
+}
+
 #[wasm_bindgen]
 pub fn init_panic_hook() {
     console_error_panic_hook::set_once();
@@ -323,24 +437,63 @@ fn extract_kmer_freqs(sequence: &str, k: usize) -> HashMap<String, usize> {
     freqs
 }
 
-#[wasm_bindgen]
-pub fn analyze_kmers(sequence_a: &str, sequence_b: &str, k: usize) -> KmerAnalysisResult {
+/// Paired (count_a, count_b) over the union of k-mers of two sequences,
+/// plus the number of distinct k-mers in each.
+///
+/// Only windows containing `N` are skipped; any other symbol is part of the
+/// k-mer (case-insensitive). Uses the sparse 2-bit counter for 1 <= k <= 31
+/// when both sequences are plain ACGT/N, and string keys otherwise.
+fn paired_kmer_counts(
+    sequence_a: &str,
+    sequence_b: &str,
+    k: usize,
+) -> (usize, usize, Vec<(u32, u32)>) {
+    let two_bit = |seq: &str| {
+        seq.bytes()
+            .all(|b| matches!(b.to_ascii_uppercase(), b'A' | b'C' | b'G' | b'T' | b'N'))
+    };
+    if (1..=kmer_counter::SPARSE_KMER_MAX_K).contains(&k)
+        && two_bit(sequence_a)
+        && two_bit(sequence_b)
+    {
+        let counts_a = kmer_counter::count_sequence(sequence_a.as_bytes(), k, false);
+        let counts_b = kmer_counter::count_sequence(sequence_b.as_bytes(), k, false);
+        let pairs = kmer_counter::union_counts(&counts_a, &counts_b);
+        return (counts_a.len(), counts_b.len(), pairs);
+    }
+
     let freqs_a = extract_kmer_freqs(sequence_a, k);
     let freqs_b = extract_kmer_freqs(sequence_b, k);
+    let mut pairs: Vec<(u32, u32)> = freqs_a
+        .iter()
+        .map(|(kmer, &count_a)| (count_a as u32, *freqs_b.get(kmer).unwrap_or(&0) as u32))
+        .collect();
+    pairs.extend(
+        freqs_b
+            .iter()
+            .filter(|(kmer, _)| !freqs_a.contains_key(*kmer))
+            .map(|(_, &count_b)| (0, count_b as u32)),
+    );
+    (freqs_a.len(), freqs_b.len(), pairs)
+}
 
-    let set_a_len = freqs_a.len();
-    let set_b_len = freqs_b.len();
-
-    // Iterate over the smaller map for intersection
-    let (smaller, larger) = if set_a_len < set_b_len {
-        (&freqs_a, &freqs_b)
-    } else {
-        (&freqs_b, &freqs_a)
-    };
+#[wasm_bindgen]
+pub fn analyze_kmers(sequence_a: &str, sequence_b: &str, k: usize) -> KmerAnalysisResult {
+    let (set_a_len, set_b_len, pairs) = paired_kmer_counts(sequence_a, sequence_b, k);
+    kmer_analysis_from_pairs(k, set_a_len, set_b_len, &pairs)
+}
 
-    let intersection_count = smaller.keys().filter(|kmer| larger.contains_key(*kmer)).count();
+/// Set and abundance metrics from paired counts over the union of k-mers
+/// (shared by contiguous and spaced-seed analysis).
+pub(crate) fn kmer_analysis_from_pairs(
+    k: usize,
+    set_a_len: usize,
+    set_b_len: usize,
+    pairs: &[(u32, u32)],
+) -> KmerAnalysisResult {
+    let intersection_count = pairs.iter().filter(|&&(a, b)| a > 0 && b > 0).count();
     let union_size = set_a_len + set_b_len - intersection_count;
-    
+
     let jaccard = if union_size > 0 {
         intersection_count as f64 / union_size as f64
     } else {
@@ -359,45 +512,24 @@ pub fn analyze_kmers(sequence_a: &str, sequence_b: &str, k: usize) -> KmerAnalys
         0.0
     };
 
-    // For Cosine/Bray-Curtis, we need to iterate the union.
-    // Optimization: iterate keys of A, calculate partials. Iterate keys of B, calculate partials for keys NOT in A.
-    // Or just collect union keys.
-    
+    // Cosine and Bray-Curtis over the union of k-mers (absent = 0).
     let mut dot_product = 0.0;
     let mut norm_a = 0.0;
     let mut norm_b = 0.0;
     let mut sum_diff = 0.0;
     let mut sum_total = 0.0;
 
-    // Iterate all keys in A
-    for (kmer, &count_a) in &freqs_a {
+    for &(count_a, count_b) in pairs {
         let count_a = count_a as f64;
-        let count_b = *freqs_b.get(kmer).unwrap_or(&0) as f64;
-        
+        let count_b = count_b as f64;
+
         dot_product += count_a * count_b;
         norm_a += count_a * count_a;
-        // norm_b will be calculated fully later? No, we need to sum all B.
-        // Let's do it simply to avoid mistakes.
-        
+        norm_b += count_b * count_b;
         sum_diff += (count_a - count_b).abs();
         sum_total += count_a + count_b;
     }
 
-    // Iterate keys in B that are NOT in A
-    for (kmer, &count_b) in &freqs_b {
-        if !freqs_a.contains_key(kmer) {
-            let count_b = count_b as f64;
-            // count_a is 0
-            // dot_product += 0 * count_b -> 0
-            // norm_a += 0 -> 0
-            
-            sum_diff += count_b; // abs(0 - count_b)
-            sum_total += count_b;
-        }
-        // Calculate norm_b separately
-        norm_b += (count_b as f64).powi(2);
-    }
-
     let cosine_sim = if norm_a > 0.0 && norm_b > 0.0 {
         dot_product / (norm_a.sqrt() * norm_b.sqrt())
     } else {
@@ -707,6 +839,7 @@ pub fn get_dense_kmer_max_k() -> usize {
 /// # Ownership
 /// The caller must call `.free()` to release WASM memory.
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct MinHashSignature {
     /// Signature values (minimum hash values for each seed)
     signature: Vec<u32>,
@@ -714,6 +847,21 @@ pub struct MinHashSignature {
     total_kmers: u64,
     /// K value used
     k: usize,
+    /// Whether k-mers were hashed as min(forward, reverse complement)
+    canonical: bool,
+    /// Hash family seed (0 for the public signature functions; spaced seeds
+    /// store a hash of their patterns here, see `spaced_seeds::seed_family`)
+    hash_seed: u32,
+}
+
+impl MinHashSignature {
+    /// True if both signatures use the same k, hash count, seed and strand mode.
+    pub(crate) fn is_compatible(&self, other: &MinHashSignature) -> bool {
+        self.k == other.k
+            && self.canonical == other.canonical
+            && self.hash_seed == other.hash_seed
+            && self.signature.len() == other.signature.len()
+    }
 }
 
 #[wasm_bindgen]
@@ -745,6 +893,27 @@ impl MinHashSignature {
     pub fn num_hashes(&self) -> usize {
         self.signature.len()
     }
+
+    /// Whether k-mers were hashed strand-independently.
+    #[wasm_bindgen(getter)]
+    pub fn canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Hash family seed.
+    #[wasm_bindgen(getter)]
+    pub fn hash_seed(&self) -> u32 {
+        self.hash_seed
+    }
+
+    /// Estimated Jaccard similarity to `other`.
+    /// Returns 0.0 if k, hash count, seed or strand mode differ.
+    pub fn jaccard(&self, other: &MinHashSignature) -> f64 {
+        if !self.is_compatible(other) {
+            return 0.0;
+        }
+        minhash_jaccard_from_signatures(&self.signature, &other.signature)
+    }
 }
 
 /// Fast 64-bit to 32-bit hash mixing (splitmix-style).
@@ -782,69 +951,7 @@ fn mix_hash(index: u64, seed: u32) -> u32 {
 /// 4. Ambiguous bases reset rolling state (no k-mer spans N)
 #[wasm_bindgen]
 pub fn minhash_signature(seq: &[u8], k: usize, num_hashes: usize) -> MinHashSignature {
-    // Limit k to 32 for u64 index (4^32 = 2^64)
-    let k = k.min(32);
-
-    // Edge cases
-    if k == 0 || num_hashes == 0 || seq.len() < k {
-        return MinHashSignature {
-            signature: vec![u32::MAX; num_hashes],
-            total_kmers: 0,
-            k,
-        };
-    }
-
-    let mut signature = vec![u32::MAX; num_hashes];
-    let mut total_kmers: u64 = 0;
-
-    // Rolling state
-    let mut rolling_index: u64 = 0;
-    let mut valid_bases: usize = 0;
-    let mask: u64 = if k >= 32 { u64::MAX } else { (1u64 << (2 * k)) - 1 };
-
-    // Pre-compute seeds for each hash function
-    let seeds: Vec<u32> = (0..num_hashes)
-        .map(|i| (i as u32).wrapping_mul(0x9e3779b9))
-        .collect();
-
-    for &byte in seq {
-        // Encode base: A=0, C=1, G=2, T/U=3
-        let base_code: u64 = match byte {
-            b'A' | b'a' => 0,
-            b'C' | b'c' => 1,
-            b'G' | b'g' => 2,
-            b'T' | b't' | b'U' | b'u' => 3,
-            _ => {
-                // Ambiguous base - reset rolling state
-                rolling_index = 0;
-                valid_bases = 0;
-                continue;
-            }
-        };
-
-        // Update rolling index: shift left by 2 bits, add new base
-        rolling_index = ((rolling_index << 2) | base_code) & mask;
-        valid_bases += 1;
-
-        // Once we have k valid bases, we have a valid k-mer
-        if valid_bases >= k {
-            total_kmers += 1;
-
-            // Compute hash for each seed and update minimum
-            for (i, &seed) in seeds.iter().enumerate() {
-                let h = mix_hash(rolling_index, seed);
-                if h < signature[i] {
-                    signature[i] = h;
-                }
-            }
-        }
-    }
-
-    MinHashSignature {
-        signature,
-        total_kmers,
-        k,
-    }
+    minhash_signature_seeded(seq, k, num_hashes, false, 0)
 }
 
 /// Compute MinHash signature using canonical k-mers (strand-independent).
@@ -862,7 +969,22 @@ pub fn minhash_signature(seq: &[u8], k: usize, num_hashes: usize) -> MinHashSign
 /// MinHashSignature with strand-independent hashes.
 #[wasm_bindgen]
 pub fn minhash_signature_canonical(seq: &[u8], k: usize, num_hashes: usize) -> MinHashSignature {
-    // Limit k to 32 for u64 index
+    minhash_signature_seeded(seq, k, num_hashes, true, 0)
+}
+
+/// Shared MinHash kernel.
+///
+/// Hash function `i` uses seed `(i * 0x9e3779b9) ^ hash_seed`, so a
+/// `hash_seed` of 0 reproduces the public signature functions. With
+/// `canonical`, each k-mer is hashed as min(forward, reverse complement).
+pub(crate) fn minhash_signature_seeded(
+    seq: &[u8],
+    k: usize,
+    num_hashes: usize,
+    canonical: bool,
+    hash_seed: u32,
+) -> MinHashSignature {
+    // Limit k to 32 for u64 index (4^32 = 2^64)
     let k = k.min(32);
 
     // Edge cases
@@ -871,6 +993,8 @@ pub fn minhash_signature_canonical(seq: &[u8], k: usize, num_hashes: usize) -> M
             signature: vec![u32::MAX; num_hashes],
             total_kmers: 0,
             k,
+            canonical,
+            hash_seed,
         };
     }
 
@@ -884,9 +1008,9 @@ pub fn minhash_signature_canonical(seq: &[u8], k: usize, num_hashes: usize) -> M
     let mask: u64 = if k >= 32 { u64::MAX } else { (1u64 << (2 * k)) - 1 };
     let rc_shift = 2 * (k - 1);
 
-    // Pre-compute seeds
+    // Pre-compute seeds for each hash function
     let seeds: Vec<u32> = (0..num_hashes)
-        .map(|i| (i as u32).wrapping_mul(0x9e3779b9))
+        .map(|i| (i as u32).wrapping_mul(0x9e3779b9) ^ hash_seed)
         .collect();
 
     for &byte in seq {
@@ -897,7 +1021,7 @@ pub fn minhash_signature_canonical(seq: &[u8], k: usize, num_hashes: usize) -> M
             b'G' | b'g' => (2, 1), // G complement is C (1)
             b'T' | b't' | b'U' | b'u' => (3, 0), // T complement is A (0)
             _ => {
-                // Ambiguous base - reset
+                // Ambiguous base - reset rolling state
                 fwd_index = 0;
                 rc_index = 0;
                 valid_bases = 0;
@@ -913,15 +1037,20 @@ pub fn minhash_signature_canonical(seq: &[u8], k: usize, num_hashes: usize) -> M
 
         valid_bases += 1;
 
+        // Once we have k valid bases, we have a valid k-mer
         if valid_bases >= k {
             total_kmers += 1;
 
             // Use canonical (smaller) index for strand independence
-            let canonical = fwd_index.min(rc_index);
+            let index = if canonical {
+                fwd_index.min(rc_index)
+            } else {
+                fwd_index
+            };
 
-            // Compute hash for each seed
+            // Compute hash for each seed and update minimum
             for (i, &seed) in seeds.iter().enumerate() {
-                let h = mix_hash(canonical, seed);
+                let h = mix_hash(index, seed);
                 if h < signature[i] {
                     signature[i] = h;
                 }
@@ -933,6 +1062,8 @@ pub fn minhash_signature_canonical(seq: &[u8], k: usize, num_hashes: usize) -> M
         signature,
         total_kmers,
         k,
+        canonical,
+        hash_seed,
     }
 }
 
@@ -1146,10 +1277,329 @@ pub fn dotplot_self_buffers(seq: &[u8], bins: usize, window: usize) -> DotPlotBu
     }
 }
 
+/// Skip seed k-mers that occur more than this many times in the first sequence
+/// (low-complexity repeats would otherwise flood the plot with hits).
+const DOTPLOT_SEED_MAX_OCCURRENCES: usize = 128;
+
+/// Result buffers for an A-vs-B dot plot.
+///
+/// Same layout as `DotPlotBuffers`, but with independent bin counts per axis:
+/// row-major `bins_a x bins_b`, index `i*bins_b + j` (i over A, j over B).
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct DotPlotPairBuffers {
+    direct: Vec<f32>,
+    inverted: Vec<f32>,
+    bins_a: usize,
+    bins_b: usize,
+    window: usize,
+    word_size: usize,
+}
+
+#[wasm_bindgen]
+impl DotPlotPairBuffers {
+    /// Flattened forward-strand values (row-major, bins_a*bins_b).
+    #[wasm_bindgen(getter)]
+    pub fn direct(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.direct.len() as u32);
+        arr.copy_from(&self.direct);
+        arr
+    }
+
+    /// Flattened reverse-complement values (row-major, bins_a*bins_b).
+    #[wasm_bindgen(getter)]
+    pub fn inverted(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.inverted.len() as u32);
+        arr.copy_from(&self.inverted);
+        arr
+    }
+
+    /// Bins along sequence A (rows).
+    #[wasm_bindgen(getter)]
+    pub fn bins_a(&self) -> usize {
+        self.bins_a
+    }
+
+    /// Bins along sequence B (columns).
+    #[wasm_bindgen(getter)]
+    pub fn bins_b(&self) -> usize {
+        self.bins_b
+    }
+
+    /// Window size used for identity scoring (0 in word-match mode).
+    #[wasm_bindgen(getter)]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Word size used for k-mer seed scoring (0 in identity mode).
+    #[wasm_bindgen(getter)]
+    pub fn word_size(&self) -> usize {
+        self.word_size
+    }
+}
+
+/// Window start positions for `bins` evenly spaced windows (JS parity:
+/// `floor(i * step)`, clamped so every window fits).
+fn dotplot_window_starts(len: usize, bins: usize, window: usize) -> Vec<usize> {
+    let mut starts: Vec<usize> = vec![0; bins];
+    if bins > 1 {
+        let span = (len - window) as f64;
+        let step = span / (bins as f64 - 1.0);
+        for (i, start) in starts.iter_mut().enumerate() {
+            let s = ((i as f64) * step).floor() as usize;
+            *start = std::cmp::min(s, len - window);
+        }
+    }
+    starts
+}
+
+/// Dot plot kernel over encoded sequences (A=0, C=1, G=2, T=3, N=4).
+fn dotplot_pair_encoded(
+    a: &[u8],
+    b: &[u8],
+    bins_a: usize,
+    bins_b: usize,
+    window: usize,
+    word_size: usize,
+) -> DotPlotPairBuffers {
+    let len_a = a.len();
+    let len_b = b.len();
+    if len_a == 0 || len_b == 0 || bins_a == 0 || bins_b == 0 {
+        return DotPlotPairBuffers {
+            direct: Vec::new(),
+            inverted: Vec::new(),
+            bins_a: 0,
+            bins_b: 0,
+            window: 0,
+            word_size: 0,
+        };
+    }
+
+    let n = bins_a * bins_b;
+    let mut direct = vec![0.0f32; n];
+    let mut inverted = vec![0.0f32; n];
+
+    if word_size > 0 {
+        // Word-match mode: count shared k-mer seeds per (bin_a, bin_b) cell.
+        let k = word_size.min(32).min(len_a).min(len_b);
+        let mask: u64 = if k == 32 {
+            u64::MAX
+        } else {
+            (1u64 << (2 * k)) - 1
+        };
+        let rc_shift = 2 * (k as u64 - 1);
+
+        let mut index: HashMap<u64, Vec<u32>> = HashMap::new();
+        let mut code: u64 = 0;
+        let mut valid = 0usize;
+        for (p, &x) in a.iter().enumerate() {
+            if x >= SEQ_BASE_N {
+                valid = 0;
+                code = 0;
+                continue;
+            }
+            code = ((code << 2) | x as u64) & mask;
+            valid += 1;
+            if valid >= k {
+                index.entry(code).or_default().push((p + 1 - k) as u32);
+            }
+        }
+
+        let mut hits_dir = vec![0u32; n];
+        let mut hits_inv = vec![0u32; n];
+        let bin_of_a = |p: usize| (p * bins_a / len_a).min(bins_a - 1);
+        let mut fwd: u64 = 0;
+        let mut rc: u64 = 0;
+        valid = 0;
+        for (q, &x) in b.iter().enumerate() {
+            if x >= SEQ_BASE_N {
+                valid = 0;
+                fwd = 0;
+                rc = 0;
+                continue;
+            }
+            fwd = ((fwd << 2) | x as u64) & mask;
+            rc = (rc >> 2) | (((3 - x) as u64) << rc_shift);
+            valid += 1;
+            if valid < k {
+                continue;
+            }
+            let col = ((q + 1 - k) * bins_b / len_b).min(bins_b - 1);
+            for (key, hits) in [(fwd, &mut hits_dir), (rc, &mut hits_inv)] {
+                if let Some(positions) = index.get(&key) {
+                    if positions.len() <= DOTPLOT_SEED_MAX_OCCURRENCES {
+                        for &p in positions {
+                            hits[bin_of_a(p as usize) * bins_b + col] += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        // A diagonal crossing a cell yields about min(bin length) seeds.
+        let bin_len = |len: usize, bins: usize, i: usize| {
+            ((i + 1) * len).div_ceil(bins) - (i * len).div_ceil(bins)
+        };
+        for i in 0..bins_a {
+            let la = bin_len(len_a, bins_a, i);
+            for j in 0..bins_b {
+                let denom = la.min(bin_len(len_b, bins_b, j)).max(1) as f32;
+                let idx = i * bins_b + j;
+                direct[idx] = (hits_dir[idx] as f32 / denom).min(1.0);
+                inverted[idx] = (hits_inv[idx] as f32 / denom).min(1.0);
+            }
+        }
+
+        return DotPlotPairBuffers {
+            direct,
+            inverted,
+            bins_a,
+            bins_b,
+            window: 0,
+            word_size: k,
+        };
+    }
+
+    // Windowed-identity mode. Default window mirrors `dotplot_self_buffers`
+    // using the coarser of the two axes.
+    let derived_window = {
+        let base = std::cmp::max(len_a / bins_a, len_b / bins_b);
+        let base = if base == 0 { len_a.min(len_b) } else { base };
+        std::cmp::max(20usize, base)
+    };
+    let window = if window == 0 { derived_window } else { window };
+    let window = std::cmp::max(1usize, std::cmp::min(len_a.min(len_b), window));
+
+    let starts_a = dotplot_window_starts(len_a, bins_a, window);
+    let starts_b = dotplot_window_starts(len_b, bins_b, window);
+    let denom = window as f32;
+
+    for (i, &a0) in starts_a.iter().enumerate() {
+        let win_a = &a[a0..a0 + window];
+        for (j, &b0) in starts_b.iter().enumerate() {
+            let win_b = &b[b0..b0 + window];
+            let mut same_dir: u32 = 0;
+            let mut same_inv: u32 = 0;
+            for (kk, &y) in win_b.iter().enumerate() {
+                let x = win_a[kk];
+                if x < SEQ_BASE_N && x == y {
+                    same_dir += 1;
+                }
+                let x_rc = win_a[window - 1 - kk];
+                if x_rc < SEQ_BASE_N && 3 - x_rc == y {
+                    same_inv += 1;
+                }
+            }
+            let idx = i * bins_b + j;
+            direct[idx] = same_dir as f32 / denom;
+            inverted[idx] = same_inv as f32 / denom;
+        }
+    }
+
+    DotPlotPairBuffers {
+        direct,
+        inverted,
+        bins_a,
+        bins_b,
+        window,
+        word_size: 0,
+    }
+}
+
+/// Compute a dot plot of sequence A against sequence B.
+///
+/// Two scoring modes share the output layout:
+/// - `word_size == 0`: windowed identity. Each cell compares a window of A
+///   with a window of B position by position (direct) and the reverse
+///   complement of the A window with the B window (inverted).
+/// - `word_size > 0`: word match. Each cell is the number of shared k-mer
+///   seeds (forward, or A k-mer equal to the reverse complement of the B
+///   k-mer) between the A bin and the B bin, divided by the shorter bin
+///   length and clamped to 1. Seeds occurring more than
+///   `DOTPLOT_SEED_MAX_OCCURRENCES` times in A are skipped.
+///
+/// Ambiguous bases (N etc.) never match, unlike the self plot.
+///
+/// # Arguments
+/// * `seq_a` - Sequence A bytes (ASCII), plotted along rows
+/// * `seq_b` - Sequence B bytes (ASCII), plotted along columns
+/// * `bins_a` - Number of bins for A. If 0, returns empty buffers.
+/// * `bins_b` - Number of bins for B. If 0, returns empty buffers.
+/// * `window` - Identity window in bases. If 0, derives a default like the self plot.
+/// * `word_size` - Seed k-mer size (1..=32), or 0 for windowed identity.
+///
+/// # Output layout
+/// Row-major, with index `i*bins_b + j`.
+#[wasm_bindgen]
+pub fn dotplot_pair_buffers(
+    seq_a: &[u8],
+    seq_b: &[u8],
+    bins_a: usize,
+    bins_b: usize,
+    window: usize,
+    word_size: usize,
+) -> DotPlotPairBuffers {
+    let a: Vec<u8> = seq_a.iter().map(|&x| encode_base(x)).collect();
+    let b: Vec<u8> = seq_b.iter().map(|&x| encode_base(x)).collect();
+    dotplot_pair_encoded(&a, &b, bins_a, bins_b, window, word_size)
+}
+
 #[cfg(test)]
 mod dotplot_tests {
     use super::*;
 
+    #[test]
+    fn dotplot_pair_identity_matches_self_plot() {
+        let seq = b"ATGCGTACGTTAGCCGATAGGCTTACGATCGATCGGATCCA";
+        let self_plot = dotplot_self_buffers(seq, 4, 8);
+        let pair = dotplot_pair_buffers(seq, seq, 4, 4, 8, 0);
+        assert_eq!(pair.bins_a, 4);
+        assert_eq!(pair.bins_b, 4);
+        assert_eq!(pair.window, 8);
+        assert_eq!(pair.direct, self_plot.direct);
+        assert_eq!(pair.inverted, self_plot.inverted);
+    }
+
+    #[test]
+    fn dotplot_pair_independent_bins() {
+        let a = b"ACGTACGTACGTACGTACGTACGTACGTACGT";
+        let b = b"ACGTACGTACGTACGT";
+        let pair = dotplot_pair_buffers(a, b, 8, 3, 4, 0);
+        assert_eq!(pair.direct.len(), 24);
+        assert_eq!(pair.inverted.len(), 24);
+        let empty = dotplot_pair_buffers(a, b, 0, 3, 4, 0);
+        assert!(empty.direct.is_empty());
+    }
+
+    #[test]
+    fn dotplot_pair_word_match_layers() {
+        let a = b"ATGCGTACGTTAGCCGATAGGCTTACGATCGATCGGATCCATTGACCA";
+        let rc: Vec<u8> = a
+            .iter()
+            .rev()
+            .map(|&x| dotplot_complement_upper(x))
+            .collect();
+
+        // A vs A: the forward diagonal is saturated.
+        let fwd = dotplot_pair_buffers(a, a, 4, 4, 0, 6);
+        assert_eq!(fwd.word_size, 6);
+        for i in 0..4 {
+            assert!(fwd.direct[i * 4 + i] > 0.5, "diag {}", i);
+        }
+
+        // A vs revcomp(A): hits move to the inverted layer's anti-diagonal.
+        let inv = dotplot_pair_buffers(a, &rc, 4, 4, 0, 6);
+        for i in 0..4 {
+            assert!(inv.inverted[i * 4 + (3 - i)] > 0.5, "anti-diag {}", i);
+        }
+        let direct_total: f32 = inv.direct.iter().sum();
+        let inverted_total: f32 = inv.inverted.iter().sum();
+        assert!(inverted_total > direct_total);
+    }
+
     #[test]
     fn dotplot_self_buffers_matches_expected_acgt() {
         // Mirrors `packages/core/src/analysis/dot-plot.test.ts` expectations.
@@ -1781,6 +2231,26 @@ pub struct HoeffdingResult {
     pub d: f64,
     /// Number of observations used
     pub n: usize,
+    /// Asymptotic p-value (Blum–Kiefer–Rosenblatt null); NaN for n < 5
+    pub p_value: f64,
+    /// Permutation p-value, (1 + #{D_perm >= D}) / (1 + permutations);
+    /// NaN unless permutations were run
+    pub permutation_p_value: f64,
+    /// Number of permutations behind `permutation_p_value`
+    pub permutations: usize,
+}
+
+impl HoeffdingResult {
+    /// Result without p-values (too few observations).
+    fn untested(d: f64, n: usize) -> HoeffdingResult {
+        HoeffdingResult {
+            d,
+            n,
+            p_value: f64::NAN,
+            permutation_p_value: f64::NAN,
+            permutations: 0,
+        }
+    }
 }
 
 /// Compute Hoeffding's D statistic for measuring statistical dependence.
@@ -1795,7 +2265,8 @@ pub struct HoeffdingResult {
 /// * `y` - Second vector of observations (must have same length as x)
 ///
 /// # Returns
-/// HoeffdingResult containing the D statistic and sample size.
+/// HoeffdingResult containing the D statistic, sample size and asymptotic
+/// p-value (see `hoeffdings_d_test` for a permutation p-value).
 /// D ranges approximately from -0.5 to 1, where:
 /// - D ≈ 0: variables are independent
 /// - D > 0: variables are dependent
@@ -1811,18 +2282,69 @@ pub struct HoeffdingResult {
 /// - Measure codon usage similarity accounting for complex dependencies
 #[wasm_bindgen]
 pub fn hoeffdings_d(x: &[f64], y: &[f64]) -> HoeffdingResult {
+    hoeffding_test(x, y, 0, 0)
+}
+
+/// Hoeffding's D with its asymptotic p-value and a seeded permutation test.
+///
+/// # Arguments
+/// * `x`, `y` - Paired observations (same length, at least 5)
+/// * `permutations` - Random shuffles of `y` for the permutation p-value
+///   (0 = asymptotic p-value only; 999 typical). Costs O(n²) each.
+/// * `seed` - Seed for the shuffles
+///
+/// # Returns
+/// HoeffdingResult with `p_value` from the Blum–Kiefer–Rosenblatt
+/// limit (good for n >= 20 or so) and `permutation_p_value`, exact up to
+/// Monte Carlo error at any n.
+#[wasm_bindgen]
+pub fn hoeffdings_d_test(x: &[f64], y: &[f64], permutations: usize, seed: u64) -> HoeffdingResult {
+    hoeffding_test(x, y, permutations, seed)
+}
+
+fn hoeffding_test(x: &[f64], y: &[f64], permutations: usize, seed: u64) -> HoeffdingResult {
     let n = x.len();
 
     // Handle edge cases
     if n != y.len() || n < 5 {
-        return HoeffdingResult { d: 0.0, n };
+        return HoeffdingResult::untested(0.0, n);
     }
 
-    let nf = n as f64;
-
     // Compute ranks (1-based, with ties handled via average)
     let r = average_rank(x);
-    let s = average_rank(y);
+    let mut s = average_rank(y);
+    let d = hoeffding_statistic(&r, &s);
+
+    // Shuffling the y ranks is shuffling y: ties are preserved.
+    let permutation_p_value = if permutations > 0 {
+        let mut rng = rng::SplitMix64::new(seed);
+        let mut at_least = 0usize;
+        for _ in 0..permutations {
+            for i in (1..n).rev() {
+                s.swap(i, rng.below(i + 1));
+            }
+            if hoeffding_statistic(&r, &s) >= d - 1e-12 {
+                at_least += 1;
+            }
+        }
+        (1 + at_least) as f64 / (1 + permutations) as f64
+    } else {
+        f64::NAN
+    };
+
+    HoeffdingResult {
+        d,
+        n,
+        p_value: stats::hoeffding_p_value(d, n),
+        permutation_p_value,
+        permutations,
+    }
+}
+
+/// Hoeffding's D from average ranks `r` and `s` (n >= 5).
+fn hoeffding_statistic(r: &[f64], s: &[f64]) -> f64 {
+    let n = r.len();
+    let nf = n as f64;
 
     // Compute Q values for each observation
     // Q[i] = 1 + (number of points with both x and y less than point i)
@@ -1862,9 +2384,8 @@ pub fn hoeffdings_d(x: &[f64], y: &[f64]) -> HoeffdingResult {
     }
 
     // Compute the three D terms
-    // D1 = sum of (Q[i] - 1) * (Q[i] - 3)
-    // Note: This is (Q-1) * ((Q-1) - 2), matching the standard Hoeffding's D formula
-    let d1: f64 = q.iter().map(|&qi| (qi - 1.0) * (qi - 3.0)).sum();
+    // D1 = sum of (Q[i] - 1) * (Q[i] - 2)
+    let d1: f64 = q.iter().map(|&qi| (qi - 1.0) * (qi - 2.0)).sum();
 
     // D2 = sum of (R[i] - 1) * (R[i] - 2) * (S[i] - 1) * (S[i] - 2)
     let d2: f64 = r.iter()
@@ -1872,11 +2393,11 @@ pub fn hoeffdings_d(x: &[f64], y: &[f64]) -> HoeffdingResult {
         .map(|(&ri, &si)| (ri - 1.0) * (ri - 2.0) * (si - 1.0) * (si - 2.0))
         .sum();
 
-    // D3 = sum of (R[i] - 1) * (S[i] - 1) * (Q[i] - 1)
+    // D3 = sum of (R[i] - 2) * (S[i] - 2) * (Q[i] - 1)
     let d3: f64 = r.iter()
         .zip(s.iter())
         .zip(q.iter())
-        .map(|((&ri, &si), &qi)| (ri - 1.0) * (si - 1.0) * (qi - 1.0))
+        .map(|((&ri, &si), &qi)| (ri - 2.0) * (si - 2.0) * (qi - 1.0))
         .sum();
 
     // Hoeffding's D formula
@@ -1884,13 +2405,11 @@ pub fn hoeffdings_d(x: &[f64], y: &[f64]) -> HoeffdingResult {
     let denom = nf * (nf - 1.0) * (nf - 2.0) * (nf - 3.0) * (nf - 4.0);
     let numerator = 30.0 * ((nf - 2.0) * (nf - 3.0) * d1 + d2 - 2.0 * (nf - 2.0) * d3);
 
-    let d = if denom.abs() > 1e-10 {
+    if denom.abs() > 1e-10 {
         numerator / denom
     } else {
         0.0
-    };
-
-    HoeffdingResult { d, n }
+    }
 }
 
 /// Compute Hoeffding's D between two k-mer frequency vectors derived from sequences.
@@ -1910,36 +2429,119 @@ pub fn hoeffdings_d(x: &[f64], y: &[f64]) -> HoeffdingResult {
 /// Higher values indicate more similar frequency patterns (non-linear similarity).
 #[wasm_bindgen]
 pub fn kmer_hoeffdings_d(sequence_a: &str, sequence_b: &str, k: usize) -> HoeffdingResult {
+    kmer_hoeffdings_d_test(sequence_a, sequence_b, k, 0, 0)
+}
+
+/// `kmer_hoeffdings_d` with a seeded permutation test over the paired
+/// k-mer counts (see `hoeffdings_d_test`).
+#[wasm_bindgen]
+pub fn kmer_hoeffdings_d_test(
+    sequence_a: &str,
+    sequence_b: &str,
+    k: usize,
+    permutations: usize,
+    seed: u64,
+) -> HoeffdingResult {
     if k == 0 {
-        return HoeffdingResult { d: 0.0, n: 0 };
+        return HoeffdingResult::untested(0.0, 0);
     }
 
-    let freqs_a = extract_kmer_freqs(sequence_a, k);
-    let freqs_b = extract_kmer_freqs(sequence_b, k);
+    let (_, _, pairs) = paired_kmer_counts(sequence_a, sequence_b, k);
 
-    if freqs_a.is_empty() && freqs_b.is_empty() {
-        return HoeffdingResult { d: 1.0, n: 0 }; // Both empty = perfect agreement
+    if pairs.is_empty() {
+        return HoeffdingResult::untested(1.0, 0); // Both empty = perfect agreement
     }
 
-    // Build aligned vectors: include all k-mers from both sequences
-    let mut all_kmers: Vec<&String> = freqs_a.keys().chain(freqs_b.keys()).collect();
-    all_kmers.sort();
-    all_kmers.dedup();
-
-    let n = all_kmers.len();
+    // Aligned vectors over all k-mers from both sequences (D is invariant
+    // to the order of the paired observations).
+    let n = pairs.len();
     if n < 5 {
         // Not enough data points for meaningful Hoeffding's D
-        return HoeffdingResult { d: 0.0, n };
+        return HoeffdingResult::untested(0.0, n);
     }
 
-    let x: Vec<f64> = all_kmers.iter()
-        .map(|kmer| *freqs_a.get(*kmer).unwrap_or(&0) as f64)
-        .collect();
-    let y: Vec<f64> = all_kmers.iter()
-        .map(|kmer| *freqs_b.get(*kmer).unwrap_or(&0) as f64)
-        .collect();
+    let x: Vec<f64> = pairs.iter().map(|&(a, _)| a as f64).collect();
+    let y: Vec<f64> = pairs.iter().map(|&(_, b)| b as f64).collect();
+
+    hoeffding_test(&x, &y, permutations, seed)
+}
+
+#[cfg(test)]
+mod hoeffding_tests {
+    use super::*;
+
+    #[test]
+    fn test_hoeffdings_d_matches_reference_values() {
+        // Reference values from the defining U-statistic: 30 times the mean of
+        // φ = ¼·ψ(x₁,x₂,x₃)ψ(x₁,x₄,x₅)ψ(y₁,y₂,y₃)ψ(y₁,y₄,y₅) over all ordered
+        // 5-tuples of distinct observations (Hoeffding 1948), computed by
+        // brute force.
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let reversed: Vec<f64> = x.iter().rev().copied().collect();
+        let cases: [(&[f64], &[f64], f64); 5] = [
+            (&x, &x, 1.0),
+            (&x, &reversed, 1.0),
+            (&x, &[4.0, 7.0, 2.0, 8.0, 1.0, 6.0, 3.0, 5.0], 1.0 / 56.0),
+            (
+                &[0.5, 1.7, 2.2, 3.1, 4.9, 5.3, 6.6, 7.4],
+                &[3.3, 1.2, 0.8, 4.4, 2.9, 5.1, 0.1, 6.0],
+                0.0,
+            ),
+            (
+                &[-3.0, -2.0, -1.0, 0.0, 1.0, 2.0, 3.0],
+                &[9.0, 4.0, 1.0, 0.0, 1.5, 4.5, 9.5],
+                1.0 / 21.0,
+            ),
+        ];
+        for (a, b, expected) in cases {
+            let d = hoeffdings_d(a, b).d;
+            assert!((d - expected).abs() < 1e-12, "D = {}, expected {}", d, expected);
+        }
+        // With ties (fractional Q); pinned to match hoeffdingD in the TS
+        // comparison package.
+        let tied = hoeffdings_d(
+            &[1.0, 2.0, 2.0, 3.0, 4.0, 5.0, 5.0, 6.0],
+            &[2.0, 1.0, 3.0, 3.0, 5.0, 4.0, 6.0, 6.0],
+        );
+        assert!((tied.d - 0.373_325_892_857_142_85).abs() < 1e-12);
+    }
+
+    fn gaussian_pairs(n: usize, seed: u64, dependence: f64) -> (Vec<f64>, Vec<f64>) {
+        let mut rng = rng::SplitMix64::new(seed);
+        let x: Vec<f64> = (0..n).map(|_| rng.gaussian()).collect();
+        let y = x
+            .iter()
+            .map(|&v| dependence * v * v + rng.gaussian())
+            .collect();
+        (x, y)
+    }
+
+    #[test]
+    fn test_p_values_detect_nonmonotone_dependence() {
+        // y = x² + noise has no correlation but strong dependence.
+        let (x, y) = gaussian_pairs(60, 1, 2.0);
+        let result = hoeffdings_d_test(&x, &y, 199, 7);
+        assert!(result.p_value < 0.01, "asymptotic p {}", result.p_value);
+        assert_eq!(result.permutation_p_value, 1.0 / 200.0);
+        assert_eq!(result.permutations, 199);
+
+        let (x, y) = gaussian_pairs(60, 2, 0.0);
+        let result = hoeffdings_d_test(&x, &y, 499, 7);
+        assert!((result.p_value - result.permutation_p_value).abs() < 0.1);
+        assert!(hoeffdings_d(&x, &y).permutation_p_value.is_nan());
+        assert!(hoeffdings_d(&x[..4], &y[..4]).p_value.is_nan());
+    }
 
-    hoeffdings_d(&x, &y)
+    #[test]
+    fn test_asymptotic_p_value_is_calibrated() {
+        let rejected = (0..200)
+            .filter(|&seed| {
+                let (x, y) = gaussian_pairs(40, 100 + seed, 0.0);
+                hoeffdings_d(&x, &y).p_value < 0.05
+            })
+            .count();
+        assert!((2..=20).contains(&rejected), "{} of 200 rejected", rejected);
+    }
 }
 
 // ============================================================================
@@ -3908,6 +4510,10 @@ pub struct KLScanResult {
     window_count: usize,
     /// K-mer size used
     k: usize,
+    /// Window size in bases
+    window_size: usize,
+    /// Step between window starts
+    step_size: usize,
 }
 
 #[wasm_bindgen]
@@ -3935,6 +4541,18 @@ impl KLScanResult {
     pub fn k(&self) -> usize {
         self.k
     }
+
+    /// Get the window size used
+    #[wasm_bindgen(getter)]
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Get the step size used
+    #[wasm_bindgen(getter)]
+    pub fn step_size(&self) -> usize {
+        self.step_size
+    }
 }
 
 /// Scan a sequence for k-mer KL divergence anomalies.
@@ -3986,6 +4604,8 @@ pub fn scan_kl_windows(
             positions: Vec::new(),
             window_count: 0,
             k,
+            window_size,
+            step_size,
         };
     }
 
@@ -3995,6 +4615,8 @@ pub fn scan_kl_windows(
             positions: Vec::new(),
             window_count: 0,
             k,
+            window_size,
+            step_size,
         };
     }
 
@@ -4038,6 +4660,8 @@ pub fn scan_kl_windows(
             positions: Vec::new(),
             window_count: 0,
             k,
+            window_size,
+            step_size,
         };
     }
 
@@ -4111,6 +4735,8 @@ pub fn scan_kl_windows(
         positions,
         window_count,
         k,
+        window_size,
+        step_size,
     }
 }
 
@@ -4955,6 +5581,8 @@ impl SequenceHandle {
                 signature: vec![u32::MAX; num_hashes],
                 total_kmers: 0,
                 k,
+                canonical: true,
+                hash_seed: 0,
             };
         }
 
@@ -5087,6 +5715,35 @@ impl SequenceHandle {
         }
     }
 
+    /// Compute an A-vs-B dot plot against another handle (this sequence is A).
+    ///
+    /// Same semantics as `dotplot_pair_buffers` without re-encoding either
+    /// sequence.
+    ///
+    /// # Arguments
+    /// * `other` - Sequence B
+    /// * `bins_a` - Number of bins for this sequence (rows)
+    /// * `bins_b` - Number of bins for `other` (columns)
+    /// * `window` - Identity window in bases. If 0, derives a default.
+    /// * `word_size` - Seed k-mer size (1..=32), or 0 for windowed identity.
+    pub fn dotplot_pair(
+        &self,
+        other: &SequenceHandle,
+        bins_a: usize,
+        bins_b: usize,
+        window: usize,
+        word_size: usize,
+    ) -> DotPlotPairBuffers {
+        dotplot_pair_encoded(
+            &self.encoded,
+            &other.encoded,
+            bins_a,
+            bins_b,
+            window,
+            word_size,
+        )
+    }
+
     /// Get the encoded sequence as a Uint8Array.
     ///
     /// Values: A=0, C=1, G=2, T=3, N=4
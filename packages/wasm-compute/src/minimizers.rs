@@ -0,0 +1,479 @@
+//! Minimizer and syncmer sampling over encoded sequences.
+//!
+//! - (w,k)-minimizers: the smallest k-mer hash in every window of `w`
+//!   consecutive k-mers (density ≈ 2 / (w + 1), every window is covered).
+//! - Closed syncmers: k-mers whose smallest s-mer is the first or last one
+//!   (density ≈ 2 / (k - s + 1)).
+//! - Open syncmers: k-mers whose smallest s-mer sits at offset `t`
+//!   (density ≈ 1 / (k - s + 1)).
+//!
+//! In canonical mode k-mers and s-mers are hashed as min(forward, reverse
+//! complement), so the same k-mers are sampled from either strand; the
+//! reported strand says which orientation was the canonical one. Symmetric
+//! k-mers (equal to their own reverse complement) have no defined strand
+//! and are never sampled in canonical mode, but still count towards the
+//! minimizer window. Windows restart after ambiguous bases.
+
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::fracminhash::frac_hash;
+use crate::{SequenceHandle, SEQ_BASE_N};
+
+/// One hashed k-mer occurrence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct KmerHit {
+    pub(crate) hash: u64,
+    pub(crate) pos: u32,
+    /// 0 = forward is canonical (or non-canonical mode), 1 = reverse complement
+    pub(crate) strand: u8,
+}
+
+/// Call `f(start, forward, reverse_complement)` for every k-mer (k <= 32)
+/// without ambiguous bases, with 2-bit packed codes.
+pub(crate) fn for_each_kmer(encoded: &[u8], k: usize, mut f: impl FnMut(usize, u64, u64)) {
+    if k == 0 || k > 32 || encoded.len() < k {
+        return;
+    }
+    let mask: u64 = if k == 32 {
+        u64::MAX
+    } else {
+        (1u64 << (2 * k)) - 1
+    };
+    let rc_shift = 2 * (k as u64 - 1);
+    let mut fwd: u64 = 0;
+    let mut rc: u64 = 0;
+    let mut valid = 0usize;
+    for (i, &code) in encoded.iter().enumerate() {
+        if code >= SEQ_BASE_N {
+            valid = 0;
+            fwd = 0;
+            rc = 0;
+            continue;
+        }
+        fwd = ((fwd << 2) | code as u64) & mask;
+        rc = (rc >> 2) | ((3 - code as u64) << rc_shift);
+        valid += 1;
+        if valid >= k {
+            f(i + 1 - k, fwd, rc);
+        }
+    }
+}
+
+/// A k-mer position as seen by the samplers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kmer {
+    /// Spans an ambiguous base (or runs past the end).
+    Ambiguous,
+    /// Equal to its own reverse complement in canonical mode.
+    Symmetric,
+    Hit(KmerHit),
+}
+
+impl Kmer {
+    fn hit(self) -> Option<KmerHit> {
+        match self {
+            Kmer::Hit(hit) => Some(hit),
+            _ => None,
+        }
+    }
+}
+
+/// Hash every k-mer.
+fn hashed_kmers(encoded: &[u8], k: usize, canonical: bool, seed: u64) -> Vec<Kmer> {
+    let mut out = vec![Kmer::Ambiguous; (encoded.len() + 1).saturating_sub(k)];
+    for_each_kmer(encoded, k, |pos, fwd, rc| {
+        let (code, strand) = if !canonical || fwd < rc {
+            (fwd, 0)
+        } else if rc < fwd {
+            (rc, 1)
+        } else {
+            out[pos] = Kmer::Symmetric;
+            return;
+        };
+        out[pos] = Kmer::Hit(KmerHit {
+            hash: frac_hash(code, seed),
+            pos: pos as u32,
+            strand,
+        });
+    });
+    out
+}
+
+/// Sliding-window minimum over runs of defined values.
+///
+/// Calls `f(window_start, argmin)` for every window of `w` consecutive
+/// defined entries; ties go to the leftmost entry.
+fn sliding_min(values: &[Option<u64>], w: usize, mut f: impl FnMut(usize, usize)) {
+    let mut deque: VecDeque<usize> = VecDeque::new();
+    let mut run = 0usize;
+    for (i, value) in values.iter().enumerate() {
+        let Some(v) = *value else {
+            deque.clear();
+            run = 0;
+            continue;
+        };
+        while deque
+            .back()
+            .is_some_and(|&j| values[j].unwrap_or(u64::MAX) > v)
+        {
+            deque.pop_back();
+        }
+        deque.push_back(i);
+        run += 1;
+        if run >= w {
+            let start = i + 1 - w;
+            while deque.front().is_some_and(|&j| j < start) {
+                deque.pop_front();
+            }
+            f(start, deque[0]);
+        }
+    }
+}
+
+/// (w,k)-minimizers in position order, each reported once.
+pub(crate) fn minimizer_hits(
+    encoded: &[u8],
+    w: usize,
+    k: usize,
+    canonical: bool,
+    seed: u64,
+) -> Vec<KmerHit> {
+    let kmers = hashed_kmers(encoded, k, canonical, seed);
+    // Symmetric k-mers rank last, so they only win windows made of nothing
+    // else; such windows are left unsampled rather than restarted.
+    let hashes: Vec<Option<u64>> = kmers
+        .iter()
+        .map(|kmer| match kmer {
+            Kmer::Ambiguous => None,
+            Kmer::Symmetric => Some(u64::MAX),
+            Kmer::Hit(hit) => Some(hit.hash),
+        })
+        .collect();
+    let mut out: Vec<KmerHit> = Vec::new();
+    sliding_min(&hashes, w.max(1), |_, argmin| {
+        if out.last().is_none_or(|h| h.pos as usize != argmin) {
+            if let Some(hit) = kmers[argmin].hit() {
+                out.push(hit);
+            }
+        }
+    });
+    out
+}
+
+/// Syncmers in position order. `offset = None` selects closed syncmers,
+/// `Some(t)` open syncmers with the minimal s-mer at offset `t`.
+pub(crate) fn syncmer_hits(
+    encoded: &[u8],
+    k: usize,
+    s: usize,
+    offset: Option<usize>,
+    canonical: bool,
+    seed: u64,
+) -> Vec<KmerHit> {
+    if s == 0 || s >= k {
+        return Vec::new();
+    }
+    let kmers = hashed_kmers(encoded, k, canonical, seed);
+    // s-mer hashes; symmetric s-mers still get a hash (strand is irrelevant).
+    let mut smers: Vec<Option<u64>> = vec![None; (encoded.len() + 1).saturating_sub(s)];
+    for_each_kmer(encoded, s, |pos, fwd, rc| {
+        let code = if canonical { fwd.min(rc) } else { fwd };
+        smers[pos] = Some(frac_hash(code, seed ^ 0x5bd1_e995));
+    });
+
+    let span = k - s + 1;
+    let mut out = Vec::new();
+    // Compare by value so tied s-mers select the same k-mers on both strands.
+    sliding_min(&smers, span, |start, argmin| {
+        let min = smers[argmin];
+        let selected = match offset {
+            None => smers[start] == min || smers[start + span - 1] == min,
+            Some(t) => smers[start + t] == min,
+        };
+        if selected {
+            if let Some(hit) = kmers.get(start).and_then(|kmer| kmer.hit()) {
+                out.push(hit);
+            }
+        }
+    });
+    out
+}
+
+/// Sampled k-mers as parallel (hash, position, strand) arrays.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct KmerSample {
+    hashes: Vec<u64>,
+    positions: Vec<u32>,
+    strands: Vec<u8>,
+    k: usize,
+    total_kmers: usize,
+    error: Option<String>,
+}
+
+impl KmerSample {
+    fn from_hits(hits: Vec<KmerHit>, k: usize, total_kmers: usize) -> KmerSample {
+        KmerSample {
+            hashes: hits.iter().map(|h| h.hash).collect(),
+            positions: hits.iter().map(|h| h.pos).collect(),
+            strands: hits.iter().map(|h| h.strand).collect(),
+            k,
+            total_kmers,
+            error: None,
+        }
+    }
+
+    fn failed(message: String, k: usize) -> KmerSample {
+        KmerSample {
+            hashes: Vec::new(),
+            positions: Vec::new(),
+            strands: Vec::new(),
+            k,
+            total_kmers: 0,
+            error: Some(message),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl KmerSample {
+    /// K-mer hashes as BigUint64Array.
+    #[wasm_bindgen(getter)]
+    pub fn hashes(&self) -> js_sys::BigUint64Array {
+        let arr = js_sys::BigUint64Array::new_with_length(self.hashes.len() as u32);
+        arr.copy_from(&self.hashes);
+        arr
+    }
+
+    /// K-mer start positions (ascending) as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.positions.len() as u32);
+        arr.copy_from(&self.positions);
+        arr
+    }
+
+    /// Canonical strand per k-mer (0 = forward, 1 = reverse complement) as Uint8Array.
+    #[wasm_bindgen(getter)]
+    pub fn strands(&self) -> js_sys::Uint8Array {
+        let arr = js_sys::Uint8Array::new_with_length(self.strands.len() as u32);
+        arr.copy_from(&self.strands);
+        arr
+    }
+
+    /// Number of sampled k-mers.
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> usize {
+        self.hashes.len()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Sampled k-mers divided by valid k-mer positions.
+    #[wasm_bindgen(getter)]
+    pub fn density(&self) -> f64 {
+        if self.total_kmers == 0 {
+            0.0
+        } else {
+            self.hashes.len() as f64 / self.total_kmers as f64
+        }
+    }
+
+    /// Error message if the parameters were invalid.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+fn valid_kmer_count(encoded: &[u8], k: usize) -> usize {
+    let mut count = 0;
+    for_each_kmer(encoded, k, |_, _, _| count += 1);
+    count
+}
+
+#[wasm_bindgen]
+impl SequenceHandle {
+    /// (w,k)-minimizers of the pre-encoded sequence.
+    ///
+    /// # Arguments
+    /// * `w` - Window size in k-mers (>= 1)
+    /// * `k` - K-mer size (1..=32)
+    /// * `canonical` - Hash min(forward, reverse complement)
+    /// * `seed` - Hash seed
+    pub fn minimizers(&self, w: usize, k: usize, canonical: bool, seed: u64) -> KmerSample {
+        if k == 0 || k > 32 || w == 0 {
+            return KmerSample::failed(format!("Invalid parameters: w={}, k={}", w, k), k);
+        }
+        let hits = minimizer_hits(&self.encoded, w, k, canonical, seed);
+        KmerSample::from_hits(hits, k, valid_kmer_count(&self.encoded, k))
+    }
+
+    /// Closed syncmers: k-mers whose smallest s-mer is at either end.
+    ///
+    /// # Arguments
+    /// * `k` - K-mer size (1..=32)
+    /// * `s` - S-mer size (1..k)
+    /// * `canonical` - Hash min(forward, reverse complement)
+    /// * `seed` - Hash seed
+    pub fn closed_syncmers(&self, k: usize, s: usize, canonical: bool, seed: u64) -> KmerSample {
+        if k == 0 || k > 32 || s == 0 || s >= k {
+            return KmerSample::failed(format!("Invalid parameters: k={}, s={}", k, s), k);
+        }
+        let hits = syncmer_hits(&self.encoded, k, s, None, canonical, seed);
+        KmerSample::from_hits(hits, k, valid_kmer_count(&self.encoded, k))
+    }
+
+    /// Open syncmers: k-mers whose smallest s-mer is at offset `t`.
+    ///
+    /// # Arguments
+    /// * `k` - K-mer size (1..=32)
+    /// * `s` - S-mer size (1..k)
+    /// * `t` - Offset of the minimal s-mer (0..=k-s)
+    /// * `canonical` - Hash min(forward, reverse complement)
+    /// * `seed` - Hash seed
+    pub fn open_syncmers(
+        &self,
+        k: usize,
+        s: usize,
+        t: usize,
+        canonical: bool,
+        seed: u64,
+    ) -> KmerSample {
+        if k == 0 || k > 32 || s == 0 || s >= k || t > k - s {
+            return KmerSample::failed(format!("Invalid parameters: k={}, s={}, t={}", k, s, t), k);
+        }
+        let hits = syncmer_hits(&self.encoded, k, s, Some(t), canonical, seed);
+        KmerSample::from_hits(hits, k, valid_kmer_count(&self.encoded, k))
+    }
+}
+
+#[cfg(test)]
+mod minimizers_tests {
+    use super::*;
+    use crate::encode_base;
+    use crate::rng::random_dna;
+
+    /// Random sequence as 2-bit codes.
+    fn random_codes(len: usize, seed: u64) -> Vec<u8> {
+        random_dna(len, seed)
+            .iter()
+            .map(|&b| encode_base(b))
+            .collect()
+    }
+
+    fn reverse_complement(seq: &[u8]) -> Vec<u8> {
+        seq.iter()
+            .rev()
+            .map(|&b| if b <= 3 { 3 - b } else { b })
+            .collect()
+    }
+
+    #[test]
+    fn test_minimizer_density_and_window_guarantee() {
+        let seq = random_codes(20_000, 1);
+        let (w, k) = (10, 15);
+        let hits = minimizer_hits(&seq, w, k, true, 42);
+        let density = hits.len() as f64 / (seq.len() - k + 1) as f64;
+        let expected = 2.0 / (w as f64 + 1.0);
+        assert!((density - expected).abs() < 0.03, "density {}", density);
+
+        // Consecutive minimizers are never more than w k-mers apart.
+        for pair in hits.windows(2) {
+            assert!(pair[1].pos > pair[0].pos);
+            assert!(pair[1].pos - pair[0].pos <= w as u32);
+        }
+    }
+
+    #[test]
+    fn test_canonical_minimizers_are_strand_independent() {
+        let seq = random_codes(5_000, 2);
+        let rc = reverse_complement(&seq);
+        let k = 21;
+        let fwd = minimizer_hits(&seq, 8, k, true, 7);
+        let rev = minimizer_hits(&rc, 8, k, true, 7);
+        assert_eq!(fwd.len(), rev.len());
+        for (a, b) in fwd.iter().zip(rev.iter().rev()) {
+            assert_eq!(a.hash, b.hash);
+            assert_eq!(a.pos as usize, seq.len() - k - b.pos as usize);
+            assert_eq!(a.strand, 1 - b.strand);
+        }
+    }
+
+    #[test]
+    fn test_symmetric_kmers_do_not_restart_windows() {
+        // ACGCGT is its own reverse complement.
+        let seq: Vec<u8> = b"TTGACAACGCGTCCATTG"
+            .iter()
+            .map(|&b| encode_base(b))
+            .collect();
+        let (w, k) = (4, 6);
+        let kmers = hashed_kmers(&seq, k, true, 0);
+        assert_eq!(kmers[6], Kmer::Symmetric);
+        let hits = minimizer_hits(&seq, w, k, true, 0);
+        assert!(hits.iter().all(|h| h.pos != 6));
+        for pair in hits.windows(2) {
+            assert!(pair[1].pos - pair[0].pos <= w as u32);
+        }
+        // Forward mode samples the palindrome like any other k-mer.
+        assert_ne!(hashed_kmers(&seq, k, false, 0)[6], Kmer::Symmetric);
+
+        // Short k-mers are often symmetric; the window guarantee still holds.
+        let seq = random_codes(5_000, 6);
+        let hits = minimizer_hits(&seq, w, k, true, 9);
+        for pair in hits.windows(2) {
+            assert!(pair[1].pos - pair[0].pos <= w as u32);
+        }
+    }
+
+    #[test]
+    fn test_syncmer_densities() {
+        let seq = random_codes(50_000, 3);
+        let (k, s) = (15, 7);
+        let total = (seq.len() - k + 1) as f64;
+        let closed = syncmer_hits(&seq, k, s, None, true, 1).len() as f64 / total;
+        let open = syncmer_hits(&seq, k, s, Some(0), true, 1).len() as f64 / total;
+        let span = (k - s + 1) as f64;
+        assert!((closed - 2.0 / span).abs() < 0.02, "closed {}", closed);
+        assert!((open - 1.0 / span).abs() < 0.02, "open {}", open);
+    }
+
+    #[test]
+    fn test_closed_syncmers_are_strand_independent() {
+        let seq = random_codes(5_000, 4);
+        let rc = reverse_complement(&seq);
+        let mut fwd: Vec<u64> = syncmer_hits(&seq, 16, 5, None, true, 3)
+            .iter()
+            .map(|h| h.hash)
+            .collect();
+        let mut rev: Vec<u64> = syncmer_hits(&rc, 16, 5, None, true, 3)
+            .iter()
+            .map(|h| h.hash)
+            .collect();
+        fwd.sort_unstable();
+        rev.sort_unstable();
+        assert_eq!(fwd, rev);
+    }
+
+    #[test]
+    fn test_ambiguous_bases_break_windows() {
+        let mut seq = random_codes(200, 5);
+        seq[100] = SEQ_BASE_N;
+        let k = 11;
+        for hit in minimizer_hits(&seq, 5, k, false, 0)
+            .iter()
+            .chain(syncmer_hits(&seq, k, 4, None, false, 0).iter())
+        {
+            let p = hit.pos as usize;
+            assert!(p + k <= 100 || p > 100);
+            assert_eq!(hit.strand, 0);
+        }
+    }
+}
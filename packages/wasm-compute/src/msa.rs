@@ -0,0 +1,837 @@
+//! Progressive multiple sequence alignment for gene families.
+//!
+//! Pipeline:
+//! 1. Pairwise k-mer distances (MinHash for DNA, exact 3-mer Jaccard for protein)
+//! 2. UPGMA guide tree over those distances
+//! 3. Post-order profile–profile alignment (Gotoh affine gaps, sum-of-pairs
+//!    column scores with a simple DNA matrix or BLOSUM62)
+//!
+//! Output is aligned FASTA or Clustal text plus a per-column conservation
+//! track for rendering. Everything is deterministic: ties in the guide tree
+//! and in traceback are broken by index/state order.
+
+use std::collections::HashSet;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    encode_base, json_string_array, minhash_jaccard_from_signatures, minhash_signature,
+    shannon_entropy_from_counts,
+};
+
+/// Maximum number of input sequences.
+pub(crate) const MSA_MAX_SEQUENCES: usize = 500;
+
+/// Maximum DP cells (one traceback byte each) for a single profile alignment.
+pub(crate) const MSA_MAX_CELLS: usize = 50_000_000;
+
+const MSA_DNA_KMER: usize = 11;
+const MSA_PROTEIN_KMER: usize = 3;
+const MSA_MINHASH_SIZE: usize = 128;
+const MSA_LINE_WIDTH: usize = 60;
+
+const DNA_MATCH: f32 = 5.0;
+const DNA_MISMATCH: f32 = -4.0;
+const UNKNOWN_SCORE: f32 = -1.0;
+
+pub(crate) const PROTEIN_ALPHABET: &[u8; 20] = b"ARNDCQEGHILKMFPSTWYV";
+
+/// BLOSUM62 in `PROTEIN_ALPHABET` order.
+#[rustfmt::skip]
+const BLOSUM62: [[i8; 20]; 20] = [
+    [ 4, -1, -2, -2,  0, -1, -1,  0, -2, -1, -1, -1, -1, -2, -1,  1,  0, -3, -2,  0],
+    [-1,  5,  0, -2, -3,  1,  0, -2,  0, -3, -2,  2, -1, -3, -2, -1, -1, -3, -2, -3],
+    [-2,  0,  6,  1, -3,  0,  0,  0,  1, -3, -3,  0, -2, -3, -2,  1,  0, -4, -2, -3],
+    [-2, -2,  1,  6, -3,  0,  2, -1, -1, -3, -4, -1, -3, -3, -1,  0, -1, -4, -3, -3],
+    [ 0, -3, -3, -3,  9, -3, -4, -3, -3, -1, -1, -3, -1, -2, -3, -1, -1, -2, -2, -1],
+    [-1,  1,  0,  0, -3,  5,  2, -2,  0, -3, -2,  1,  0, -3, -1,  0, -1, -2, -1, -2],
+    [-1,  0,  0,  2, -4,  2,  5, -2,  0, -3, -3,  1, -2, -3, -1,  0, -1, -3, -2, -2],
+    [ 0, -2,  0, -1, -3, -2, -2,  6, -2, -4, -4, -2, -3, -3, -2,  0, -2, -2, -3, -3],
+    [-2,  0,  1, -1, -3,  0,  0, -2,  8, -3, -3, -1, -2, -1, -2, -1, -2, -2,  2, -3],
+    [-1, -3, -3, -3, -1, -3, -3, -4, -3,  4,  2, -3,  1,  0, -3, -2, -1, -3, -1,  3],
+    [-1, -2, -3, -4, -1, -2, -3, -4, -3,  2,  4, -2,  2,  0, -3, -2, -1, -2, -1,  1],
+    [-1,  2,  0, -1, -3,  1,  1, -2, -1, -3, -2,  5, -1, -3, -1,  0, -1, -3, -2, -2],
+    [-1, -1, -2, -3, -1,  0, -2, -3, -2,  1,  2, -1,  5,  0, -2, -1, -1, -1, -1,  1],
+    [-2, -3, -3, -3, -2, -3, -3, -3, -1,  0,  0, -3,  0,  6, -4, -2, -2,  1,  3, -1],
+    [-1, -2, -2, -1, -3, -1, -1, -2, -2, -3, -3, -1, -2, -4,  7, -1, -1, -4, -3, -2],
+    [ 1, -1,  1,  0, -1,  0,  0,  0, -1, -2, -2,  0, -1, -2, -1,  4,  1, -3, -2, -2],
+    [ 0, -1,  0, -1, -1, -1, -1, -2, -2, -1, -1, -1, -1, -2, -1,  1,  5, -2, -2,  0],
+    [-3, -3, -4, -4, -2, -2, -3, -2, -2, -3, -2, -3, -1,  1, -4, -3, -2, 11,  2, -3],
+    [-2, -2, -2, -3, -2, -1, -2, -3,  2, -1, -1, -2, -1,  3, -3, -2, -2,  2,  7, -1],
+    [ 0, -3, -3, -3, -1, -2, -2, -3, -3,  3,  1, -2,  1, -1, -2, -2,  0, -3, -1,  4],
+];
+
+/// Clustal "strong" residue groups (':' in the conservation line).
+const CLUSTAL_STRONG_GROUPS: [&[u8]; 9] = [
+    b"STA", b"NEQK", b"NHQK", b"NDEQ", b"QHRK", b"MILV", b"MILF", b"HY", b"FYW",
+];
+
+/// Clustal "weak" residue groups ('.' in the conservation line).
+const CLUSTAL_WEAK_GROUPS: [&[u8]; 11] = [
+    b"CSA", b"ATV", b"SAG", b"STNK", b"STPA", b"SGND", b"SNDEQK", b"NDEQHK", b"NEQHRK", b"FVLIM",
+    b"HFY",
+];
+
+/// Residue alphabet used for scoring and conservation.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MsaAlphabet {
+    /// A, C, G, T (+ N as the unknown symbol)
+    Dna,
+    /// 20 amino acids (+ X as the unknown symbol)
+    Protein,
+}
+
+impl MsaAlphabet {
+    /// Number of symbols including the trailing unknown symbol.
+    pub(crate) fn size(self) -> usize {
+        match self {
+            MsaAlphabet::Dna => 5,
+            MsaAlphabet::Protein => 21,
+        }
+    }
+
+    /// Index of the unknown symbol (N or X).
+    pub(crate) fn unknown(self) -> usize {
+        self.size() - 1
+    }
+
+    /// Map an uppercase residue to a symbol index.
+    pub(crate) fn symbol(self, residue: u8) -> usize {
+        match self {
+            MsaAlphabet::Dna => encode_base(residue) as usize,
+            MsaAlphabet::Protein => PROTEIN_ALPHABET
+                .iter()
+                .position(|&aa| aa == residue.to_ascii_uppercase())
+                .unwrap_or(20),
+        }
+    }
+
+    fn score(self, a: usize, b: usize) -> f32 {
+        let unknown = self.unknown();
+        if a == unknown || b == unknown {
+            return UNKNOWN_SCORE;
+        }
+        match self {
+            MsaAlphabet::Dna => {
+                if a == b {
+                    DNA_MATCH
+                } else {
+                    DNA_MISMATCH
+                }
+            }
+            MsaAlphabet::Protein => BLOSUM62[a][b] as f32,
+        }
+    }
+}
+
+/// One FASTA record.
+pub(crate) struct FastaRecord {
+    pub(crate) name: String,
+    pub(crate) residues: Vec<u8>,
+}
+
+/// Parse FASTA text into records.
+///
+/// The name is the first whitespace-delimited token of the header. Residues
+/// are uppercased letters; `-` and `.` are kept as `-` when `keep_gaps` is
+/// set and dropped otherwise. Digits, whitespace and other characters are
+/// ignored, as is anything before the first header.
+pub(crate) fn parse_fasta(text: &str, keep_gaps: bool) -> Vec<FastaRecord> {
+    let mut records: Vec<FastaRecord> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end();
+        if let Some(header) = line.strip_prefix('>') {
+            let name = header.split_whitespace().next().unwrap_or("");
+            let name = if name.is_empty() {
+                format!("seq{}", records.len() + 1)
+            } else {
+                name.to_string()
+            };
+            records.push(FastaRecord {
+                name,
+                residues: Vec::new(),
+            });
+        } else if let Some(record) = records.last_mut() {
+            for &b in line.as_bytes() {
+                if b.is_ascii_alphabetic() {
+                    record.residues.push(b.to_ascii_uppercase());
+                } else if keep_gaps && (b == b'-' || b == b'.') {
+                    record.residues.push(b'-');
+                }
+            }
+        }
+    }
+    records
+}
+
+/// Pairwise k-mer distance matrix (row-major, n × n, values in [0, 1]).
+fn kmer_distance_matrix(records: &[FastaRecord], alphabet: MsaAlphabet) -> Vec<f64> {
+    let n = records.len();
+    let mut dist = vec![0.0f64; n * n];
+
+    match alphabet {
+        MsaAlphabet::Dna => {
+            let sigs: Vec<_> = records
+                .iter()
+                .map(|r| minhash_signature(&r.residues, MSA_DNA_KMER, MSA_MINHASH_SIZE))
+                .collect();
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let d = if sigs[i].total_kmers == 0 || sigs[j].total_kmers == 0 {
+                        1.0
+                    } else {
+                        1.0 - minhash_jaccard_from_signatures(
+                            &sigs[i].signature,
+                            &sigs[j].signature,
+                        )
+                    };
+                    dist[i * n + j] = d;
+                    dist[j * n + i] = d;
+                }
+            }
+        }
+        MsaAlphabet::Protein => {
+            let sets: Vec<HashSet<&[u8]>> = records
+                .iter()
+                .map(|r| r.residues.windows(MSA_PROTEIN_KMER).collect())
+                .collect();
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let inter = sets[i].intersection(&sets[j]).count();
+                    let union = sets[i].len() + sets[j].len() - inter;
+                    let d = if union == 0 {
+                        1.0
+                    } else {
+                        1.0 - inter as f64 / union as f64
+                    };
+                    dist[i * n + j] = d;
+                    dist[j * n + i] = d;
+                }
+            }
+        }
+    }
+
+    dist
+}
+
+/// Guide tree node. Leaves are `0..n`; internal nodes follow in merge order,
+/// so children always precede their parent.
+struct GuideNode {
+    children: Option<(usize, usize)>,
+    height: f64,
+}
+
+/// Build a UPGMA guide tree. Ties pick the lowest (i, j) pair.
+fn upgma_guide_tree(dist: &[f64], n: usize) -> Vec<GuideNode> {
+    let mut nodes: Vec<GuideNode> = (0..n)
+        .map(|_| GuideNode {
+            children: None,
+            height: 0.0,
+        })
+        .collect();
+    if n < 2 {
+        return nodes;
+    }
+
+    let mut d = dist.to_vec();
+    let mut active: Vec<bool> = vec![true; n];
+    let mut sizes: Vec<usize> = vec![1; n];
+    // Node id currently represented by each matrix slot.
+    let mut slot_node: Vec<usize> = (0..n).collect();
+
+    for _ in 1..n {
+        let mut best = (usize::MAX, usize::MAX, f64::INFINITY);
+        for i in 0..n {
+            if !active[i] {
+                continue;
+            }
+            for j in (i + 1)..n {
+                if active[j] && d[i * n + j] < best.2 {
+                    best = (i, j, d[i * n + j]);
+                }
+            }
+        }
+        let (i, j, dij) = best;
+
+        nodes.push(GuideNode {
+            children: Some((slot_node[i], slot_node[j])),
+            height: dij / 2.0,
+        });
+
+        // Slot i becomes the merged cluster; slot j is retired.
+        let (si, sj) = (sizes[i] as f64, sizes[j] as f64);
+        for k in 0..n {
+            if active[k] && k != i && k != j {
+                let merged = (d[i * n + k] * si + d[j * n + k] * sj) / (si + sj);
+                d[i * n + k] = merged;
+                d[k * n + i] = merged;
+            }
+        }
+        active[j] = false;
+        sizes[i] += sizes[j];
+        slot_node[i] = nodes.len() - 1;
+    }
+
+    nodes
+}
+
+pub(crate) fn newick_label(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '(' | ')' | ',' | ':' | ';' | '[' | ']' | '\'' => '_',
+            c if c.is_whitespace() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+fn guide_tree_newick(nodes: &[GuideNode], names: &[String]) -> String {
+    fn write(nodes: &[GuideNode], names: &[String], id: usize, parent_h: f64, out: &mut String) {
+        match nodes[id].children {
+            Some((l, r)) => {
+                out.push('(');
+                write(nodes, names, l, nodes[id].height, out);
+                out.push(',');
+                write(nodes, names, r, nodes[id].height, out);
+                out.push(')');
+            }
+            None => out.push_str(&newick_label(&names[id])),
+        }
+        if parent_h.is_finite() {
+            out.push_str(&format!(":{:.6}", (parent_h - nodes[id].height).max(0.0)));
+        }
+    }
+
+    let mut out = String::new();
+    if let Some(root) = nodes.len().checked_sub(1) {
+        write(nodes, names, root, f64::NAN, &mut out);
+    }
+    out.push(';');
+    out
+}
+
+/// A partially built alignment: input indices and their gapped rows.
+struct AlignedGroup {
+    members: Vec<usize>,
+    rows: Vec<Vec<u8>>,
+}
+
+impl AlignedGroup {
+    fn columns(&self) -> usize {
+        self.rows.first().map_or(0, |r| r.len())
+    }
+
+    /// Residue frequencies per column (`columns × alphabet.size()`); gaps
+    /// contribute nothing, so gappy columns score proportionally less.
+    fn profile(&self, alphabet: MsaAlphabet) -> Vec<f32> {
+        let size = alphabet.size();
+        let cols = self.columns();
+        let weight = 1.0 / self.rows.len() as f32;
+        let mut freqs = vec![0.0f32; cols * size];
+        for row in &self.rows {
+            for (c, &residue) in row.iter().enumerate() {
+                if residue != b'-' {
+                    freqs[c * size + alphabet.symbol(residue)] += weight;
+                }
+            }
+        }
+        freqs
+    }
+}
+
+// Traceback states.
+const ST_MATCH: u8 = 0;
+const ST_GAP_B: u8 = 1; // column of A against gaps in B
+const ST_GAP_A: u8 = 2; // gaps in A against a column of B
+
+/// Align two groups with Gotoh affine gaps; returns the merged group.
+///
+/// A gap of length L costs `gap_open + (L - 1) * gap_extend`.
+fn align_groups(
+    a: AlignedGroup,
+    b: AlignedGroup,
+    alphabet: MsaAlphabet,
+    gap_open: f32,
+    gap_extend: f32,
+) -> Result<AlignedGroup, String> {
+    let n = a.columns();
+    let m = b.columns();
+    let cells = (n + 1) * (m + 1);
+    if cells > MSA_MAX_CELLS {
+        return Err(format!(
+            "Profile alignment too large: {} x {} columns (max {} cells)",
+            n, m, MSA_MAX_CELLS
+        ));
+    }
+
+    let size = alphabet.size();
+    let freq_a = a.profile(alphabet);
+    let freq_b = b.profile(alphabet);
+
+    // Pre-multiply B's frequencies by the substitution matrix so each cell
+    // score is a single dot product over the alphabet.
+    let mut sub = vec![0.0f32; size * size];
+    for x in 0..size {
+        for y in 0..size {
+            sub[x * size + y] = alphabet.score(x, y);
+        }
+    }
+    let mut score_b = vec![0.0f32; m * size];
+    for j in 0..m {
+        for x in 0..size {
+            score_b[j * size + x] = (0..size)
+                .map(|y| freq_b[j * size + y] * sub[x * size + y])
+                .sum();
+        }
+    }
+
+    let neg = f32::NEG_INFINITY;
+    let width = m + 1;
+    // trace byte: bits 0-1 = predecessor of M, 2-3 = of X (gap B), 4-5 = of Y (gap A)
+    let mut trace = vec![0u8; cells];
+    let mut prev_m = vec![neg; width];
+    let mut prev_x = vec![neg; width];
+    let mut prev_y = vec![neg; width];
+    let mut cur_m = vec![neg; width];
+    let mut cur_x = vec![neg; width];
+    let mut cur_y = vec![neg; width];
+
+    prev_m[0] = 0.0;
+    for j in 1..width {
+        prev_y[j] = -gap_open - (j - 1) as f32 * gap_extend;
+        let from = if j == 1 { ST_MATCH } else { ST_GAP_A };
+        trace[j] = from << 4;
+    }
+
+    for i in 1..=n {
+        cur_m[0] = neg;
+        cur_y[0] = neg;
+        cur_x[0] = -gap_open - (i - 1) as f32 * gap_extend;
+        let from = if i == 1 { ST_MATCH } else { ST_GAP_B };
+        trace[i * width] = from << 2;
+
+        let fa = &freq_a[(i - 1) * size..i * size];
+        for j in 1..width {
+            let sb = &score_b[(j - 1) * size..j * size];
+            let s: f32 = fa.iter().zip(sb).map(|(p, q)| p * q).sum();
+
+            let (m_from, m_best) = best_of(prev_m[j - 1], prev_x[j - 1], prev_y[j - 1]);
+            cur_m[j] = m_best + s;
+
+            let (x_from, x_best) = best_of(
+                prev_m[j] - gap_open,
+                prev_x[j] - gap_extend,
+                prev_y[j] - gap_open,
+            );
+            cur_x[j] = x_best;
+
+            let (y_from, y_best) = best_of(
+                cur_m[j - 1] - gap_open,
+                cur_x[j - 1] - gap_open,
+                cur_y[j - 1] - gap_extend,
+            );
+            cur_y[j] = y_best;
+
+            trace[i * width + j] = m_from | (x_from << 2) | (y_from << 4);
+        }
+
+        std::mem::swap(&mut prev_m, &mut cur_m);
+        std::mem::swap(&mut prev_x, &mut cur_x);
+        std::mem::swap(&mut prev_y, &mut cur_y);
+    }
+
+    // Traceback from the best final state.
+    let (mut state, _) = best_of(prev_m[m], prev_x[m], prev_y[m]);
+    let (mut i, mut j) = (n, m);
+    let mut ops: Vec<u8> = Vec::with_capacity(n + m);
+    while i > 0 || j > 0 {
+        let t = trace[i * width + j];
+        ops.push(state);
+        let pred = (t >> (2 * state)) & 0b11;
+        match state {
+            ST_MATCH => {
+                i -= 1;
+                j -= 1;
+            }
+            ST_GAP_B => i -= 1,
+            _ => j -= 1,
+        }
+        state = pred;
+    }
+    ops.reverse();
+
+    let mut rows: Vec<Vec<u8>> = (0..a.rows.len() + b.rows.len())
+        .map(|_| Vec::with_capacity(ops.len()))
+        .collect();
+    let (mut ci, mut cj) = (0usize, 0usize);
+    for &op in &ops {
+        let take_a = op != ST_GAP_A;
+        let take_b = op != ST_GAP_B;
+        for (r, row) in a.rows.iter().enumerate() {
+            rows[r].push(if take_a { row[ci] } else { b'-' });
+        }
+        for (r, row) in b.rows.iter().enumerate() {
+            rows[a.rows.len() + r].push(if take_b { row[cj] } else { b'-' });
+        }
+        ci += usize::from(take_a);
+        cj += usize::from(take_b);
+    }
+
+    let mut members = a.members;
+    members.extend(b.members);
+    Ok(AlignedGroup { members, rows })
+}
+
+/// Pick the best of (M, X, Y) scores; ties prefer M, then X, then Y.
+#[inline(always)]
+fn best_of(m: f32, x: f32, y: f32) -> (u8, f32) {
+    if m >= x && m >= y {
+        (ST_MATCH, m)
+    } else if x >= y {
+        (ST_GAP_B, x)
+    } else {
+        (ST_GAP_A, y)
+    }
+}
+
+/// Per-column conservation in [0, 1]:
+/// `(1 - H / log2(alphabet)) * (1 - gap_fraction)`, ignoring unknown residues.
+pub(crate) fn column_conservation(rows: &[Vec<u8>], alphabet: MsaAlphabet) -> Vec<f32> {
+    let cols = rows.first().map_or(0, |r| r.len());
+    let size = alphabet.size();
+    let unknown = alphabet.unknown();
+    let h_max = (unknown as f64).log2();
+    let mut counts = vec![0.0f64; unknown];
+
+    (0..cols)
+        .map(|c| {
+            counts.iter_mut().for_each(|x| *x = 0.0);
+            let mut gaps = 0usize;
+            for row in rows {
+                let residue = row[c];
+                if residue == b'-' {
+                    gaps += 1;
+                    continue;
+                }
+                let sym = alphabet.symbol(residue);
+                if sym < size - 1 {
+                    counts[sym] += 1.0;
+                }
+            }
+            let observed: f64 = counts.iter().sum();
+            if observed == 0.0 {
+                return 0.0;
+            }
+            let h = shannon_entropy_from_counts(&counts);
+            let gap_fraction = gaps as f64 / rows.len() as f64;
+            ((1.0 - h / h_max) * (1.0 - gap_fraction)).clamp(0.0, 1.0) as f32
+        })
+        .collect()
+}
+
+/// Result of a progressive multiple sequence alignment.
+///
+/// Rows are returned in input order.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct MsaResult {
+    names: Vec<String>,
+    rows: Vec<Vec<u8>>,
+    conservation: Vec<f32>,
+    guide_tree: String,
+    protein: bool,
+    error: Option<String>,
+}
+
+impl MsaResult {
+    fn failed(message: String, protein: bool) -> MsaResult {
+        MsaResult {
+            names: Vec::new(),
+            rows: Vec::new(),
+            conservation: Vec::new(),
+            guide_tree: String::new(),
+            protein,
+            error: Some(message),
+        }
+    }
+
+    fn name_width(&self) -> usize {
+        self.names.iter().map(|n| n.len()).max().unwrap_or(0) + 4
+    }
+
+    /// Clustal conservation symbol for one column.
+    fn clustal_symbol(&self, col: usize) -> char {
+        let first = self.rows[0][col];
+        if self.rows.iter().any(|r| r[col] == b'-') {
+            return ' ';
+        }
+        if self.rows.iter().all(|r| r[col] == first) {
+            return '*';
+        }
+        if !self.protein {
+            return ' ';
+        }
+        let in_group = |group: &[u8]| self.rows.iter().all(|r| group.contains(&r[col]));
+        if CLUSTAL_STRONG_GROUPS.iter().any(|g| in_group(g)) {
+            ':'
+        } else if CLUSTAL_WEAK_GROUPS.iter().any(|g| in_group(g)) {
+            '.'
+        } else {
+            ' '
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl MsaResult {
+    /// Number of aligned sequences.
+    #[wasm_bindgen(getter)]
+    pub fn num_sequences(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Number of alignment columns.
+    #[wasm_bindgen(getter)]
+    pub fn alignment_length(&self) -> usize {
+        self.rows.first().map_or(0, |r| r.len())
+    }
+
+    /// Per-column conservation in [0, 1] as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn conservation(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.conservation.len() as u32);
+        arr.copy_from(&self.conservation);
+        arr
+    }
+
+    /// Sequence names in input order as a JSON array.
+    #[wasm_bindgen(getter)]
+    pub fn names_json(&self) -> String {
+        json_string_array(&self.names)
+    }
+
+    /// Aligned row `index` (with `-` gaps), or an empty string if out of range.
+    pub fn row(&self, index: usize) -> String {
+        self.rows
+            .get(index)
+            .map(|r| String::from_utf8_lossy(r).into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Alignment as aligned FASTA (60 columns per line).
+    #[wasm_bindgen(getter)]
+    pub fn aligned_fasta(&self) -> String {
+        let mut out = String::new();
+        for (name, row) in self.names.iter().zip(&self.rows) {
+            out.push('>');
+            out.push_str(name);
+            out.push('\n');
+            for chunk in row.chunks(MSA_LINE_WIDTH) {
+                out.push_str(&String::from_utf8_lossy(chunk));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Alignment in Clustal format with a conservation line per block.
+    #[wasm_bindgen(getter)]
+    pub fn clustal(&self) -> String {
+        let mut out = String::from("CLUSTAL W multiple sequence alignment\n\n");
+        let width = self.name_width();
+        let cols = self.alignment_length();
+        let mut start = 0;
+        while start < cols {
+            let end = (start + MSA_LINE_WIDTH).min(cols);
+            out.push('\n');
+            for (name, row) in self.names.iter().zip(&self.rows) {
+                out.push_str(&format!("{:<width$}", name, width = width));
+                out.push_str(&String::from_utf8_lossy(&row[start..end]));
+                out.push('\n');
+            }
+            out.push_str(&" ".repeat(width));
+            out.extend((start..end).map(|c| self.clustal_symbol(c)));
+            out.push('\n');
+            start = end;
+        }
+        out
+    }
+
+    /// UPGMA guide tree in Newick format.
+    #[wasm_bindgen(getter)]
+    pub fn guide_tree(&self) -> String {
+        self.guide_tree.clone()
+    }
+
+    /// Error message if the alignment failed.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Progressive multiple sequence alignment of FASTA records.
+///
+/// # Arguments
+/// * `fasta` - Unaligned sequences in FASTA format (gaps are stripped)
+/// * `protein` - true for amino acids (BLOSUM62), false for nucleotides (+5/-4)
+/// * `gap_open` - Penalty for the first gap position (e.g. 10.0)
+/// * `gap_extend` - Penalty for each further gap position (e.g. 0.5 DNA, 1.0 protein)
+///
+/// # Returns
+/// `MsaResult` with rows in input order. On invalid input or when a profile
+/// alignment exceeds `MSA_MAX_CELLS`, `error` is set and the result is empty.
+#[wasm_bindgen]
+pub fn progressive_msa(fasta: &str, protein: bool, gap_open: f32, gap_extend: f32) -> MsaResult {
+    let alphabet = if protein {
+        MsaAlphabet::Protein
+    } else {
+        MsaAlphabet::Dna
+    };
+    let records: Vec<FastaRecord> = parse_fasta(fasta, false)
+        .into_iter()
+        .filter(|r| !r.residues.is_empty())
+        .collect();
+
+    if records.is_empty() {
+        return MsaResult::failed("No sequences found in FASTA input".to_string(), protein);
+    }
+    if records.len() > MSA_MAX_SEQUENCES {
+        return MsaResult::failed(
+            format!(
+                "Too many sequences: {} (max {})",
+                records.len(),
+                MSA_MAX_SEQUENCES
+            ),
+            protein,
+        );
+    }
+
+    let gap_open = gap_open.max(0.0);
+    let gap_extend = gap_extend.max(0.0);
+    let n = records.len();
+    let dist = kmer_distance_matrix(&records, alphabet);
+    let nodes = upgma_guide_tree(&dist, n);
+    let names: Vec<String> = records.iter().map(|r| r.name.clone()).collect();
+    let guide_tree = guide_tree_newick(&nodes, &names);
+
+    let mut groups: Vec<Option<AlignedGroup>> = records
+        .into_iter()
+        .enumerate()
+        .map(|(i, r)| {
+            Some(AlignedGroup {
+                members: vec![i],
+                rows: vec![r.residues],
+            })
+        })
+        .collect();
+
+    for node in nodes.iter().skip(n) {
+        let (l, r) = node.children.expect("internal node");
+        let (a, b) = (groups[l].take(), groups[r].take());
+        let (Some(a), Some(b)) = (a, b) else {
+            return MsaResult::failed("Guide tree is inconsistent".to_string(), protein);
+        };
+        match align_groups(a, b, alphabet, gap_open, gap_extend) {
+            Ok(merged) => groups.push(Some(merged)),
+            Err(message) => return MsaResult::failed(message, protein),
+        }
+    }
+
+    let root = groups.pop().flatten().expect("root group");
+    let mut rows: Vec<Vec<u8>> = vec![Vec::new(); n];
+    for (member, row) in root.members.into_iter().zip(root.rows) {
+        rows[member] = row;
+    }
+    let conservation = column_conservation(&rows, alphabet);
+
+    MsaResult {
+        names,
+        rows,
+        conservation,
+        guide_tree,
+        protein,
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod msa_tests {
+    use super::*;
+
+    fn ungapped(row: &[u8]) -> Vec<u8> {
+        row.iter().copied().filter(|&b| b != b'-').collect()
+    }
+
+    #[test]
+    fn test_parse_fasta() {
+        let records = parse_fasta(">a desc\nAC-gt\n\n>b\nTT.A\n", true);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].name, "a");
+        assert_eq!(records[0].residues, b"AC-GT");
+        assert_eq!(records[1].residues, b"TT-A");
+        let stripped = parse_fasta(">a\nAC-GT\n", false);
+        assert_eq!(stripped[0].residues, b"ACGT");
+    }
+
+    #[test]
+    fn test_dna_msa_preserves_sequences() {
+        let fasta = ">s1\nATGGCTAGCTAGGATCCGATCGATCGTAGCTAGCTAA\n\
+                     >s2\nATGGCTAGCTAGGATCGATCGATCGTAGCTAGCTAA\n\
+                     >s3\nATGGCTAGCTTGGATCCGATCGATCGTAGCAAGCTAGCTAA\n";
+        let result = progressive_msa(fasta, false, 10.0, 0.5);
+        assert!(result.error.is_none());
+        assert_eq!(result.num_sequences(), 3);
+        let len = result.alignment_length();
+        let inputs = parse_fasta(fasta, false);
+        for (row, input) in result.rows.iter().zip(&inputs) {
+            assert_eq!(row.len(), len);
+            assert_eq!(ungapped(row), input.residues);
+        }
+        assert_eq!(result.conservation.len(), len);
+        // Shared start codon is fully conserved.
+        assert!(result.conservation[..3]
+            .iter()
+            .all(|&c| (c - 1.0).abs() < 1e-6));
+        assert!(result.guide_tree.ends_with(';'));
+    }
+
+    #[test]
+    fn test_identical_sequences_align_without_gaps() {
+        let fasta = ">a\nMKVLAAGIW\n>b\nMKVLAAGIW\n";
+        let result = progressive_msa(fasta, true, 10.0, 1.0);
+        assert_eq!(result.row(0), "MKVLAAGIW");
+        assert_eq!(result.row(1), "MKVLAAGIW");
+    }
+
+    #[test]
+    fn test_protein_indel_placed_as_single_gap() {
+        let fasta = ">a\nMKTAYIAKQRQISFVKSHFSRQ\n>b\nMKTAYIAKQISFVKSHFSRQ\n";
+        let result = progressive_msa(fasta, true, 10.0, 1.0);
+        let row_b = result.row(1);
+        assert_eq!(row_b.len(), 22);
+        // One contiguous two-residue gap.
+        assert_eq!(row_b.matches("--").count(), 1);
+        assert_eq!(row_b.chars().filter(|&c| c == '-').count(), 2);
+    }
+
+    #[test]
+    fn test_output_formats() {
+        let fasta = ">x\nACGTACGT\n>y\nACGAACGT\n";
+        let result = progressive_msa(fasta, false, 10.0, 0.5);
+        assert_eq!(result.aligned_fasta(), ">x\nACGTACGT\n>y\nACGAACGT\n");
+        let clustal = result.clustal();
+        assert!(clustal.starts_with("CLUSTAL"));
+        assert!(clustal.contains("x    ACGTACGT\n"));
+        assert!(clustal.contains("     *** ****\n"));
+    }
+
+    #[test]
+    fn test_empty_input_reports_error() {
+        let result = progressive_msa("", false, 10.0, 0.5);
+        assert!(result.error.is_some());
+        assert_eq!(result.num_sequences(), 0);
+    }
+}
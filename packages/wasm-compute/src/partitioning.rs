@@ -0,0 +1,733 @@
+//! Flat partitions of feature matrices or distance matrices.
+//!
+//! - k-means with k-means++ seeding (Lloyd iterations, best of `n_init`)
+//!   on feature matrices, and k-medoids (Voronoi iteration, same seeding)
+//!   on precomputed distances
+//! - Silhouette coefficients for any labelling, plus a scan over k for
+//!   choosing the number of clusters
+//!
+//! Every flat clustering (including DBSCAN and HDBSCAN in `density`)
+//! returns a `ClusterAssignment`: labels (-1 = noise), outlier flags,
+//! membership probabilities, and centroids (features) or medoids
+//! (distances).
+
+use wasm_bindgen::prelude::*;
+
+use crate::embedding::PointSet;
+use crate::rng::SplitMix64;
+
+/// Labels and summaries of a flat clustering.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct ClusterAssignment {
+    pub(crate) labels: Vec<i32>,
+    pub(crate) probabilities: Vec<f32>,
+    pub(crate) centroids: Vec<f32>,
+    pub(crate) medoids: Vec<u32>,
+    pub(crate) cluster_count: usize,
+    pub(crate) inertia: f64,
+    pub(crate) iterations: usize,
+    pub(crate) error: Option<String>,
+}
+
+impl ClusterAssignment {
+    pub(crate) fn failed(error: String) -> ClusterAssignment {
+        ClusterAssignment {
+            labels: Vec::new(),
+            probabilities: Vec::new(),
+            centroids: Vec::new(),
+            medoids: Vec::new(),
+            cluster_count: 0,
+            inertia: f64::NAN,
+            iterations: 0,
+            error: Some(error),
+        }
+    }
+
+    /// Assignment from labels (clusters numbered 0..count, -1 = noise);
+    /// fills in centroids for feature input or medoids for distance input.
+    pub(crate) fn from_labels(
+        points: &PointSet,
+        labels: Vec<i32>,
+        probabilities: Vec<f32>,
+        inertia: f64,
+        iterations: usize,
+    ) -> ClusterAssignment {
+        let cluster_count = labels.iter().map(|&l| l + 1).max().unwrap_or(0).max(0) as usize;
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); cluster_count];
+        for (i, &l) in labels.iter().enumerate() {
+            if l >= 0 {
+                members[l as usize].push(i);
+            }
+        }
+        let (centroids, medoids) = match points.features() {
+            Some((data, dim)) => {
+                let mut centroids = vec![0.0f32; cluster_count * dim];
+                for (c, list) in members.iter().enumerate() {
+                    let centroid = &mut centroids[c * dim..(c + 1) * dim];
+                    for &i in list {
+                        for (v, &x) in centroid.iter_mut().zip(&data[i * dim..(i + 1) * dim]) {
+                            *v += x / list.len() as f32;
+                        }
+                    }
+                }
+                (centroids, Vec::new())
+            }
+            None => {
+                let medoids = members
+                    .iter()
+                    .map(|list| medoid(points, list) as u32)
+                    .collect();
+                (Vec::new(), medoids)
+            }
+        };
+        ClusterAssignment {
+            labels,
+            probabilities,
+            centroids,
+            medoids,
+            cluster_count,
+            inertia,
+            iterations,
+            error: None,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ClusterAssignment {
+    /// Cluster label per point (-1 = noise/outlier).
+    #[wasm_bindgen(getter)]
+    pub fn labels(&self) -> js_sys::Int32Array {
+        let arr = js_sys::Int32Array::new_with_length(self.labels.len() as u32);
+        arr.copy_from(&self.labels);
+        arr
+    }
+
+    /// 1 for noise points, 0 otherwise.
+    #[wasm_bindgen(getter)]
+    pub fn outliers(&self) -> js_sys::Uint8Array {
+        let flags: Vec<u8> = self.labels.iter().map(|&l| u8::from(l < 0)).collect();
+        let arr = js_sys::Uint8Array::new_with_length(flags.len() as u32);
+        arr.copy_from(&flags);
+        arr
+    }
+
+    /// Membership strength in [0, 1] (1 for k-means and DBSCAN members).
+    #[wasm_bindgen(getter)]
+    pub fn probabilities(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.probabilities.len() as u32);
+        arr.copy_from(&self.probabilities);
+        arr
+    }
+
+    /// Cluster means (cluster_count × n_features); empty for distance input.
+    #[wasm_bindgen(getter)]
+    pub fn centroids(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.centroids.len() as u32);
+        arr.copy_from(&self.centroids);
+        arr
+    }
+
+    /// Medoid point index per cluster; empty for feature input.
+    #[wasm_bindgen(getter)]
+    pub fn medoids(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.medoids.len() as u32);
+        arr.copy_from(&self.medoids);
+        arr
+    }
+
+    /// Points per cluster.
+    #[wasm_bindgen(getter)]
+    pub fn cluster_sizes(&self) -> js_sys::Uint32Array {
+        let mut sizes = vec![0u32; self.cluster_count];
+        for &l in &self.labels {
+            if l >= 0 {
+                sizes[l as usize] += 1;
+            }
+        }
+        let arr = js_sys::Uint32Array::new_with_length(sizes.len() as u32);
+        arr.copy_from(&sizes);
+        arr
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn cluster_count(&self) -> usize {
+        self.cluster_count
+    }
+
+    /// Number of noise points.
+    #[wasm_bindgen(getter)]
+    pub fn noise_count(&self) -> usize {
+        self.labels.iter().filter(|&&l| l < 0).count()
+    }
+
+    /// k-means: within-cluster sum of squared distances; k-medoids: sum of
+    /// distances to medoids; NaN for density clusterings.
+    #[wasm_bindgen(getter)]
+    pub fn inertia(&self) -> f64 {
+        self.inertia
+    }
+
+    /// Iterations of the best run (0 for density clusterings).
+    #[wasm_bindgen(getter)]
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Error message for invalid input.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Member with the smallest total distance to the others.
+fn medoid(points: &PointSet, members: &[usize]) -> usize {
+    members
+        .iter()
+        .copied()
+        .min_by(|&a, &b| {
+            let sa: f64 = members.iter().map(|&j| points.distance(a, j)).sum();
+            let sb: f64 = members.iter().map(|&j| points.distance(b, j)).sum();
+            sa.total_cmp(&sb).then(a.cmp(&b))
+        })
+        .unwrap_or(0)
+}
+
+/// k-means++ seeding: indices of `k` points, sampled ∝ squared distance to
+/// the nearest seed so far.
+fn plus_plus_seeds(points: &PointSet, k: usize, rng: &mut SplitMix64) -> Vec<usize> {
+    let n = points.len();
+    let mut seeds = vec![rng.below(n)];
+    let mut d2: Vec<f64> = (0..n)
+        .map(|i| points.distance(i, seeds[0]).powi(2))
+        .collect();
+    while seeds.len() < k {
+        let total: f64 = d2.iter().sum();
+        let next = if total > 0.0 {
+            let mut target = rng.next_f64() * total;
+            d2.iter()
+                .position(|&w| {
+                    target -= w;
+                    target < 0.0 && w > 0.0
+                })
+                .unwrap_or_else(|| d2.iter().rposition(|&w| w > 0.0).unwrap_or(0))
+        } else {
+            rng.below(n)
+        };
+        seeds.push(next);
+        for (i, v) in d2.iter_mut().enumerate() {
+            *v = v.min(points.distance(i, next).powi(2));
+        }
+    }
+    seeds
+}
+
+fn squared_distance(a: &[f32], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(&x, &c)| (x as f64 - c).powi(2))
+        .sum()
+}
+
+/// One Lloyd run from k-means++ seeds: (labels, inertia, iterations).
+fn lloyd(
+    points: &PointSet,
+    data: &[f32],
+    dim: usize,
+    k: usize,
+    max_iterations: usize,
+    rng: &mut SplitMix64,
+) -> (Vec<i32>, f64, usize) {
+    let n = points.len();
+    let mut centroids: Vec<Vec<f64>> = plus_plus_seeds(points, k, rng)
+        .iter()
+        .map(|&i| {
+            data[i * dim..(i + 1) * dim]
+                .iter()
+                .map(|&v| v as f64)
+                .collect()
+        })
+        .collect();
+    let mut labels = vec![-1i32; n];
+    let mut dist2 = vec![0.0f64; n];
+    let mut iterations = 0;
+    for iteration in 1..=max_iterations.max(1) {
+        iterations = iteration;
+        let mut changed = false;
+        for i in 0..n {
+            let row = &data[i * dim..(i + 1) * dim];
+            let (best, d) = centroids
+                .iter()
+                .enumerate()
+                .map(|(c, centroid)| (c, squared_distance(row, centroid)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            dist2[i] = d;
+            if labels[i] != best as i32 {
+                labels[i] = best as i32;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+        let mut sums = vec![vec![0.0f64; dim]; k];
+        let mut counts = vec![0usize; k];
+        for (i, &l) in labels.iter().enumerate() {
+            counts[l as usize] += 1;
+            for (s, &x) in sums[l as usize]
+                .iter_mut()
+                .zip(&data[i * dim..(i + 1) * dim])
+            {
+                *s += x as f64;
+            }
+        }
+        for c in 0..k {
+            if counts[c] > 0 {
+                centroids[c] = sums[c].iter().map(|s| s / counts[c] as f64).collect();
+            } else {
+                // Empty cluster: restart it at the worst-fitted point.
+                let far = (0..n)
+                    .max_by(|&a, &b| dist2[a].total_cmp(&dist2[b]))
+                    .unwrap();
+                centroids[c] = data[far * dim..(far + 1) * dim]
+                    .iter()
+                    .map(|&v| v as f64)
+                    .collect();
+                dist2[far] = 0.0;
+            }
+        }
+    }
+    (labels, dist2.iter().sum(), iterations)
+}
+
+/// One k-medoids (Voronoi iteration) run: (labels, total distance, iterations).
+fn voronoi_medoids(
+    points: &PointSet,
+    k: usize,
+    max_iterations: usize,
+    rng: &mut SplitMix64,
+) -> (Vec<i32>, f64, usize) {
+    let n = points.len();
+    let mut medoids = plus_plus_seeds(points, k, rng);
+    let mut labels = vec![0i32; n];
+    let mut iterations = 0;
+    loop {
+        iterations += 1;
+        for (i, label) in labels.iter_mut().enumerate() {
+            let best = (0..k)
+                .min_by(|&a, &b| {
+                    points
+                        .distance(i, medoids[a])
+                        .total_cmp(&points.distance(i, medoids[b]))
+                })
+                .unwrap();
+            *label = best as i32;
+        }
+        if iterations >= max_iterations.max(1) {
+            break;
+        }
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); k];
+        for (i, &l) in labels.iter().enumerate() {
+            members[l as usize].push(i);
+        }
+        let updated: Vec<usize> = members
+            .iter()
+            .zip(medoids.iter())
+            .map(|(list, &m)| {
+                if list.is_empty() {
+                    m
+                } else {
+                    medoid(points, list)
+                }
+            })
+            .collect();
+        if updated == medoids {
+            break;
+        }
+        medoids = updated;
+    }
+    let cost = labels
+        .iter()
+        .enumerate()
+        .map(|(i, &l)| points.distance(i, medoids[l as usize]))
+        .sum();
+    (labels, cost, iterations)
+}
+
+fn partition(
+    points: Result<PointSet, String>,
+    k: usize,
+    max_iterations: usize,
+    n_init: usize,
+    seed: u64,
+) -> ClusterAssignment {
+    let points = match points {
+        Ok(points) => points,
+        Err(error) => return ClusterAssignment::failed(error),
+    };
+    if k == 0 || k > points.len() {
+        return ClusterAssignment::failed(format!("k must be 1..={} (got {})", points.len(), k));
+    }
+    let mut rng = SplitMix64::new(seed);
+    let mut best: Option<(Vec<i32>, f64, usize)> = None;
+    for _ in 0..n_init.max(1) {
+        let run = match points.features() {
+            Some((data, dim)) => lloyd(&points, data, dim, k, max_iterations, &mut rng),
+            None => voronoi_medoids(&points, k, max_iterations, &mut rng),
+        };
+        if best.as_ref().is_none_or(|b| run.1 < b.1) {
+            best = Some(run);
+        }
+    }
+    let (mut labels, inertia, iterations) = best.unwrap();
+    // Clusters left empty (e.g. fewer distinct points than k) are dropped so
+    // cluster_count matches the centroid and medoid arrays.
+    let mut used = vec![false; k];
+    for &l in &labels {
+        used[l as usize] = true;
+    }
+    let remap: Vec<i32> = used
+        .iter()
+        .scan(0, |next, &u| {
+            let label = *next;
+            *next += i32::from(u);
+            Some(label)
+        })
+        .collect();
+    for l in labels.iter_mut() {
+        *l = remap[*l as usize];
+    }
+    let probabilities = vec![1.0; labels.len()];
+    ClusterAssignment::from_labels(&points, labels, probabilities, inertia, iterations)
+}
+
+/// Silhouette coefficient per point; NaN for noise points (label < 0).
+/// Points in singleton clusters score 0. Labels need not be contiguous;
+/// they are remapped to dense cluster ids first.
+pub(crate) fn silhouette_samples(points: &PointSet, labels: &[i32]) -> Vec<f64> {
+    let mut ids: Vec<i32> = labels.iter().copied().filter(|&l| l >= 0).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let dense: Vec<Option<usize>> = labels.iter().map(|l| ids.binary_search(l).ok()).collect();
+    let count = ids.len();
+    let mut sizes = vec![0usize; count];
+    for &c in dense.iter().flatten() {
+        sizes[c] += 1;
+    }
+    let mut sums = vec![0.0f64; count];
+    (0..labels.len())
+        .map(|i| {
+            let Some(own) = dense[i] else {
+                return f64::NAN;
+            };
+            if sizes[own] <= 1 {
+                return 0.0;
+            }
+            sums.iter_mut().for_each(|s| *s = 0.0);
+            for (j, &c) in dense.iter().enumerate() {
+                if let Some(c) = c.filter(|_| j != i) {
+                    sums[c] += points.distance(i, j);
+                }
+            }
+            let a = sums[own] / (sizes[own] - 1) as f64;
+            let b = (0..count)
+                .filter(|&c| c != own)
+                .map(|c| sums[c] / sizes[c] as f64)
+                .fold(f64::INFINITY, f64::min);
+            if !b.is_finite() {
+                return 0.0;
+            }
+            let denom = a.max(b);
+            if denom > 0.0 {
+                (b - a) / denom
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Mean silhouette over non-noise points (NaN if none).
+pub(crate) fn mean_silhouette(samples: &[f64]) -> f64 {
+    let valid: Vec<f64> = samples.iter().copied().filter(|s| !s.is_nan()).collect();
+    if valid.is_empty() {
+        f64::NAN
+    } else {
+        valid.iter().sum::<f64>() / valid.len() as f64
+    }
+}
+
+fn silhouette_array(points: Result<PointSet, String>, labels: &[i32]) -> js_sys::Float64Array {
+    let samples = match points {
+        Ok(points) if points.len() == labels.len() => silhouette_samples(&points, labels),
+        _ => Vec::new(),
+    };
+    let arr = js_sys::Float64Array::new_with_length(samples.len() as u32);
+    arr.copy_from(&samples);
+    arr
+}
+
+fn scan(
+    points: Result<PointSet, String>,
+    k_min: usize,
+    k_max: usize,
+    n_init: usize,
+    seed: u64,
+) -> Vec<f64> {
+    let Ok(points) = points else {
+        return Vec::new();
+    };
+    let k_min = k_min.max(2);
+    let k_max = k_max.min(points.len() - 1);
+    (k_min..=k_max)
+        .map(|k| {
+            let mut rng = SplitMix64::new(seed);
+            let mut best: Option<(Vec<i32>, f64, usize)> = None;
+            for _ in 0..n_init.max(1) {
+                let run = match points.features() {
+                    Some((data, dim)) => lloyd(&points, data, dim, k, 300, &mut rng),
+                    None => voronoi_medoids(&points, k, 300, &mut rng),
+                };
+                if best.as_ref().is_none_or(|b| run.1 < b.1) {
+                    best = Some(run);
+                }
+            }
+            mean_silhouette(&silhouette_samples(&points, &best.unwrap().0))
+        })
+        .collect()
+}
+
+/// k-means with k-means++ seeding on a feature matrix.
+///
+/// # Arguments
+/// * `data` - Row-major matrix (n_samples × n_features)
+/// * `k` - Number of clusters
+/// * `max_iterations` - Lloyd iterations per run (300 typical)
+/// * `n_init` - Independent seedings; the lowest-inertia run is kept
+/// * `seed` - Seed for k-means++ sampling
+#[wasm_bindgen]
+pub fn kmeans(
+    data: &[f32],
+    n_samples: usize,
+    n_features: usize,
+    k: usize,
+    max_iterations: usize,
+    n_init: usize,
+    seed: u64,
+) -> ClusterAssignment {
+    partition(
+        PointSet::from_features(data, n_samples, n_features),
+        k,
+        max_iterations,
+        n_init,
+        seed,
+    )
+}
+
+/// k-medoids on a condensed distance matrix (k-means++ seeding, Voronoi
+/// iteration). Arguments as for `kmeans`.
+#[wasm_bindgen]
+pub fn kmedoids_from_distances(
+    condensed: &[f32],
+    k: usize,
+    max_iterations: usize,
+    n_init: usize,
+    seed: u64,
+) -> ClusterAssignment {
+    partition(
+        PointSet::from_condensed(condensed),
+        k,
+        max_iterations,
+        n_init,
+        seed,
+    )
+}
+
+/// Silhouette coefficient per sample (Euclidean), NaN for noise labels.
+/// Empty if the sizes do not match.
+#[wasm_bindgen]
+pub fn silhouette_scores(
+    data: &[f32],
+    n_samples: usize,
+    n_features: usize,
+    labels: &[i32],
+) -> js_sys::Float64Array {
+    silhouette_array(PointSet::from_features(data, n_samples, n_features), labels)
+}
+
+/// Silhouette coefficient per sample from a condensed distance matrix.
+#[wasm_bindgen]
+pub fn silhouette_scores_from_distances(condensed: &[f32], labels: &[i32]) -> js_sys::Float64Array {
+    silhouette_array(PointSet::from_condensed(condensed), labels)
+}
+
+/// Mean silhouette of the best k-means run for each k in `k_min..=k_max`
+/// (k >= 2), for choosing k: entry i is for k = max(k_min, 2) + i.
+#[wasm_bindgen]
+pub fn kmeans_silhouette_scan(
+    data: &[f32],
+    n_samples: usize,
+    n_features: usize,
+    k_min: usize,
+    k_max: usize,
+    n_init: usize,
+    seed: u64,
+) -> js_sys::Float64Array {
+    let scores = scan(
+        PointSet::from_features(data, n_samples, n_features),
+        k_min,
+        k_max,
+        n_init,
+        seed,
+    );
+    let arr = js_sys::Float64Array::new_with_length(scores.len() as u32);
+    arr.copy_from(&scores);
+    arr
+}
+
+/// `kmeans_silhouette_scan` with k-medoids on a condensed distance matrix.
+#[wasm_bindgen]
+pub fn kmedoids_silhouette_scan(
+    condensed: &[f32],
+    k_min: usize,
+    k_max: usize,
+    n_init: usize,
+    seed: u64,
+) -> js_sys::Float64Array {
+    let scores = scan(
+        PointSet::from_condensed(condensed),
+        k_min,
+        k_max,
+        n_init,
+        seed,
+    );
+    let arr = js_sys::Float64Array::new_with_length(scores.len() as u32);
+    arr.copy_from(&scores);
+    arr
+}
+
+#[cfg(test)]
+pub(crate) mod partitioning_tests {
+    use super::*;
+
+    /// `clusters` Gaussian blobs in 4-D, `per` points each, centers 10 apart.
+    pub(crate) fn blobs(clusters: usize, per: usize, seed: u64) -> Vec<f32> {
+        let mut rng = SplitMix64::new(seed);
+        let mut data = Vec::new();
+        for c in 0..clusters {
+            for _ in 0..per {
+                for d in 0..4 {
+                    let center = if d == c % 4 {
+                        10.0 * (1 + c / 4) as f64
+                    } else {
+                        0.0
+                    };
+                    data.push((center + rng.gaussian()) as f32);
+                }
+            }
+        }
+        data
+    }
+
+    /// Same partition up to relabelling.
+    pub(crate) fn same_partition(labels: &[i32], per: usize) -> bool {
+        labels
+            .chunks(per)
+            .all(|chunk| chunk.iter().all(|&l| l == chunk[0]))
+            && labels
+                .chunks(per)
+                .enumerate()
+                .all(|(i, a)| labels.chunks(per).skip(i + 1).all(|b| a[0] != b[0]))
+    }
+
+    #[test]
+    fn test_kmeans_recovers_blobs() {
+        let data = blobs(3, 30, 1);
+        let result = kmeans(&data, 90, 4, 3, 100, 4, 9);
+        assert!(result.error.is_none());
+        assert!(same_partition(&result.labels, 30));
+        assert_eq!(result.centroids.len(), 12);
+        // Centroid of the first blob sits near (10, 0, 0, 0).
+        let c = result.labels[0] as usize * 4;
+        assert!((result.centroids[c] - 10.0).abs() < 1.0);
+        assert!(kmeans(&data, 90, 4, 0, 100, 1, 0).error.is_some());
+    }
+
+    #[test]
+    fn test_empty_clusters_are_dropped() {
+        // Two distinct points cannot fill three clusters.
+        let data = [0.0, 0.0, 0.0, 0.0, 5.0, 5.0];
+        let result = kmeans(&data, 3, 2, 3, 10, 1, 0);
+        assert!(result.error.is_none());
+        assert_eq!(result.cluster_count, 2);
+        assert_eq!(result.centroids.len(), result.cluster_count * 2);
+        assert!(result.labels.iter().all(|&l| (0..2).contains(&l)));
+        assert_eq!(result.labels[0], result.labels[1]);
+
+        let result = kmedoids_from_distances(&[0.0, 5.0, 5.0], 3, 10, 1, 0);
+        assert_eq!(result.cluster_count, 2);
+        assert_eq!(result.medoids.len(), 2);
+        for (c, &m) in result.medoids.iter().enumerate() {
+            assert_eq!(result.labels[m as usize], c as i32);
+        }
+    }
+
+    #[test]
+    fn test_kmedoids_and_silhouette_scan() {
+        let data = blobs(4, 20, 2);
+        let points = PointSet::from_features(&data, 80, 4).unwrap();
+        let mut condensed = Vec::new();
+        for i in 0..80 {
+            for j in (i + 1)..80 {
+                condensed.push(points.distance(i, j) as f32);
+            }
+        }
+        let result = kmedoids_from_distances(&condensed, 4, 50, 3, 5);
+        assert!(same_partition(&result.labels, 20));
+        assert_eq!(result.medoids.len(), 4);
+        for (c, &m) in result.medoids.iter().enumerate() {
+            assert_eq!(result.labels[m as usize], c as i32);
+        }
+
+        let scores = scan(PointSet::from_condensed(&condensed), 2, 6, 3, 5);
+        let best = scores
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap()
+            .0;
+        assert_eq!(best + 2, 4, "scores {:?}", scores);
+    }
+
+    #[test]
+    fn test_silhouette_values() {
+        // Two tight pairs far apart: silhouettes close to 1.
+        let data = [0.0f32, 0.0, 0.0, 1.0, 100.0, 0.0, 100.0, 1.0];
+        let points = PointSet::from_features(&data, 4, 2).unwrap();
+        let s = silhouette_samples(&points, &[0, 0, 1, 1]);
+        for v in &s {
+            assert!(*v > 0.98);
+        }
+        // Point 0 alone is a singleton (0); noise is NaN and skipped in the mean.
+        let s = silhouette_samples(&points, &[0, -1, 1, 1]);
+        assert_eq!(s[0], 0.0);
+        assert!(s[1].is_nan());
+        assert!((mean_silhouette(&s) - (s[2] + s[3]) / 3.0).abs() < 1e-12);
+
+        // Sparse and huge labels behave like their dense equivalents.
+        let dense = silhouette_samples(&points, &[0, 0, 1, 1]);
+        assert_eq!(
+            silhouette_samples(&points, &[7, 7, i32::MAX, i32::MAX]),
+            dense
+        );
+        assert_eq!(
+            silhouette_samples(&points, &[1_000_000_000, 1_000_000_000, 3, 3]),
+            dense
+        );
+    }
+}
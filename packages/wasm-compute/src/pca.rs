@@ -0,0 +1,785 @@
+//! PCA by randomized SVD, with full projection outputs.
+//!
+//! `pca_power_iteration` deflates one component at a time, which gets slow
+//! for 4^6-feature k-mer matrices and many components. `pca_randomized`
+//! follows Halko, Martinsson & Tropp (2011): sketch the range of the
+//! centered matrix with `k + oversamples` Gaussian test vectors, sharpen it
+//! with a few power iterations (re-orthonormalized each pass), then take an
+//! exact SVD of the small projected matrix.
+//!
+//! `IncrementalPca` fits the same model from chunks of samples (Ross et al.
+//! 2008, as in scikit-learn's IncrementalPCA): each chunk is stacked under
+//! the current `S·Vᵀ` plus a mean-shift row and re-decomposed, so only
+//! `n_components × n_features` values are kept between chunks.
+//!
+//! Optional transforms: per-feature standardization, or the centered
+//! log-ratio (CLR) for compositional rows such as k-mer frequencies.
+
+use wasm_bindgen::prelude::*;
+
+use crate::rng::SplitMix64;
+
+/// Preprocessing applied before centering.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PcaTransform {
+    /// Center only
+    None = 0,
+    /// Center and scale each feature to unit sample variance
+    Standardize = 1,
+    /// Centered log-ratio per sample (zeros replaced by half the smallest
+    /// positive value in the row), then center
+    Clr = 2,
+}
+
+/// Fitted PCA model.
+///
+/// Components are canonicalized so their largest-magnitude entry is
+/// positive. Eigenvalues are on the sample-covariance scale (divided by
+/// n_samples - 1), as in `pca_power_iteration`.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct PcaModel {
+    components: Vec<f64>,
+    singular_values: Vec<f64>,
+    eigenvalues: Vec<f64>,
+    total_variance: f64,
+    mean: Vec<f64>,
+    scale: Vec<f64>,
+    scores: Vec<f64>,
+    transform: PcaTransform,
+    n_samples: usize,
+    n_features: usize,
+    error: Option<String>,
+}
+
+impl PcaModel {
+    fn failed(n_features: usize, transform: PcaTransform, error: String) -> PcaModel {
+        PcaModel {
+            components: Vec::new(),
+            singular_values: Vec::new(),
+            eigenvalues: Vec::new(),
+            total_variance: 0.0,
+            mean: Vec::new(),
+            scale: Vec::new(),
+            scores: Vec::new(),
+            transform,
+            n_samples: 0,
+            n_features,
+            error: Some(error),
+        }
+    }
+
+    /// Scores (n × n_components, row-major) of raw samples under this model.
+    pub(crate) fn project_rows(&self, data: &[f32], n_samples: usize) -> Vec<f64> {
+        let d = self.n_features;
+        let k = self.n_components();
+        if self.error.is_some() || n_samples.checked_mul(d) != Some(data.len()) {
+            return Vec::new();
+        }
+        let mut out = Vec::with_capacity(n_samples * k);
+        let mut row = vec![0.0f64; d];
+        for sample in data.chunks_exact(d) {
+            for (r, &x) in row.iter_mut().zip(sample.iter()) {
+                *r = x as f64;
+            }
+            if self.transform == PcaTransform::Clr {
+                clr_row(&mut row);
+            }
+            for ((r, &m), &s) in row.iter_mut().zip(self.mean.iter()).zip(self.scale.iter()) {
+                *r = (*r - m) / s;
+            }
+            for c in 0..k {
+                out.push(dot(&row, &self.components[c * d..(c + 1) * d]));
+            }
+        }
+        out
+    }
+}
+
+#[wasm_bindgen]
+impl PcaModel {
+    /// Principal axes, row-major (n_components × n_features).
+    #[wasm_bindgen(getter)]
+    pub fn components(&self) -> js_sys::Float32Array {
+        to_f32_array(&self.components)
+    }
+
+    /// Explained variance per component (sample-covariance eigenvalues).
+    #[wasm_bindgen(getter)]
+    pub fn eigenvalues(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.eigenvalues.len() as u32);
+        arr.copy_from(&self.eigenvalues);
+        arr
+    }
+
+    /// Fraction of total variance explained by each component.
+    #[wasm_bindgen(getter)]
+    pub fn explained_variance_ratio(&self) -> js_sys::Float64Array {
+        let ratios: Vec<f64> = self
+            .eigenvalues
+            .iter()
+            .map(|&e| {
+                if self.total_variance > 0.0 {
+                    e / self.total_variance
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let arr = js_sys::Float64Array::new_with_length(ratios.len() as u32);
+        arr.copy_from(&ratios);
+        arr
+    }
+
+    /// Singular values of the (transformed, centered) data matrix.
+    #[wasm_bindgen(getter)]
+    pub fn singular_values(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.singular_values.len() as u32);
+        arr.copy_from(&self.singular_values);
+        arr
+    }
+
+    /// Loadings: components scaled by √eigenvalue (n_components × n_features).
+    #[wasm_bindgen(getter)]
+    pub fn loadings(&self) -> js_sys::Float32Array {
+        let d = self.n_features;
+        let loadings: Vec<f64> = self
+            .components
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| v * self.eigenvalues[i / d].max(0.0).sqrt())
+            .collect();
+        to_f32_array(&loadings)
+    }
+
+    /// Projected scores of the fitted samples (n_samples × n_components).
+    /// Empty for `IncrementalPca` models; use `project` instead.
+    #[wasm_bindgen(getter)]
+    pub fn scores(&self) -> js_sys::Float32Array {
+        to_f32_array(&self.scores)
+    }
+
+    /// Per-feature mean subtracted before projection (after CLR).
+    #[wasm_bindgen(getter)]
+    pub fn mean(&self) -> js_sys::Float32Array {
+        to_f32_array(&self.mean)
+    }
+
+    /// Per-feature divisor (standard deviations, or 1 unless standardized).
+    #[wasm_bindgen(getter)]
+    pub fn scale(&self) -> js_sys::Float32Array {
+        to_f32_array(&self.scale)
+    }
+
+    /// Total variance of the transformed data (sample-covariance scale).
+    #[wasm_bindgen(getter)]
+    pub fn total_variance(&self) -> f64 {
+        self.total_variance
+    }
+
+    /// Project new samples (row-major, n_samples × n_features).
+    pub fn project(&self, data: &[f32], n_samples: usize) -> js_sys::Float32Array {
+        to_f32_array(&self.project_rows(data, n_samples))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn transform(&self) -> PcaTransform {
+        self.transform
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n_samples(&self) -> usize {
+        self.n_samples
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n_features(&self) -> usize {
+        self.n_features
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n_components(&self) -> usize {
+        self.singular_values.len()
+    }
+
+    /// Error message for invalid input.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+fn to_f32_array(values: &[f64]) -> js_sys::Float32Array {
+    let values: Vec<f32> = values.iter().map(|&v| v as f32).collect();
+    let arr = js_sys::Float32Array::new_with_length(values.len() as u32);
+    arr.copy_from(&values);
+    arr
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+/// Centered log-ratio of one composition, in place.
+fn clr_row(row: &mut [f64]) {
+    let min_positive = row
+        .iter()
+        .copied()
+        .filter(|&v| v > 0.0)
+        .fold(f64::INFINITY, f64::min);
+    if !min_positive.is_finite() {
+        row.iter_mut().for_each(|v| *v = 0.0);
+        return;
+    }
+    let pseudo = 0.5 * min_positive;
+    for v in row.iter_mut() {
+        *v = if *v > 0.0 { v.ln() } else { pseudo.ln() };
+    }
+    let mean = row.iter().sum::<f64>() / row.len() as f64;
+    row.iter_mut().for_each(|v| *v -= mean);
+}
+
+/// Transform, center and (optionally) scale; returns (matrix, mean, scale).
+fn preprocess(
+    data: &[f32],
+    n: usize,
+    d: usize,
+    transform: PcaTransform,
+) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let mut x: Vec<f64> = data.iter().map(|&v| v as f64).collect();
+    if transform == PcaTransform::Clr {
+        x.chunks_exact_mut(d).for_each(clr_row);
+    }
+    let mut mean = vec![0.0; d];
+    for row in x.chunks_exact(d) {
+        for (m, &v) in mean.iter_mut().zip(row.iter()) {
+            *m += v;
+        }
+    }
+    mean.iter_mut().for_each(|m| *m /= n as f64);
+    for row in x.chunks_exact_mut(d) {
+        for (v, &m) in row.iter_mut().zip(mean.iter()) {
+            *v -= m;
+        }
+    }
+    let mut scale = vec![1.0; d];
+    if transform == PcaTransform::Standardize {
+        let mut sum_sq = vec![0.0; d];
+        for row in x.chunks_exact(d) {
+            for (s, &v) in sum_sq.iter_mut().zip(row.iter()) {
+                *s += v * v;
+            }
+        }
+        for (s, &ss) in scale.iter_mut().zip(sum_sq.iter()) {
+            let sd = (ss / n.saturating_sub(1).max(1) as f64).sqrt();
+            if sd > 0.0 {
+                *s = sd;
+            }
+        }
+        for row in x.chunks_exact_mut(d) {
+            for (v, &s) in row.iter_mut().zip(scale.iter()) {
+                *v /= s;
+            }
+        }
+    }
+    (x, mean, scale)
+}
+
+/// X·c for each column vector c (each of length d); returns columns of length n.
+fn x_times(x: &[f64], d: usize, cols: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = x.len() / d;
+    let mut out = vec![vec![0.0; n]; cols.len()];
+    for (i, row) in x.chunks_exact(d).enumerate() {
+        for (o, c) in out.iter_mut().zip(cols.iter()) {
+            o[i] = dot(row, c);
+        }
+    }
+    out
+}
+
+/// Xᵀ·c for each column vector c (each of length n); returns columns of length d.
+fn xt_times(x: &[f64], d: usize, cols: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let mut out = vec![vec![0.0; d]; cols.len()];
+    for (i, row) in x.chunks_exact(d).enumerate() {
+        for (o, c) in out.iter_mut().zip(cols.iter()) {
+            let w = c[i];
+            if w != 0.0 {
+                for (ov, &xv) in o.iter_mut().zip(row.iter()) {
+                    *ov += w * xv;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Modified Gram–Schmidt with one re-orthogonalization pass; drops columns
+/// that are numerically dependent on earlier ones.
+fn orthonormalize(cols: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(cols.len());
+    for mut c in cols {
+        let norm0 = dot(&c, &c).sqrt();
+        for _ in 0..2 {
+            for q in &basis {
+                let p = dot(&c, q);
+                c.iter_mut().zip(q.iter()).for_each(|(v, &qv)| *v -= p * qv);
+            }
+        }
+        let norm = dot(&c, &c).sqrt();
+        if norm > 1e-10 * norm0.max(f64::MIN_POSITIVE) {
+            c.iter_mut().for_each(|v| *v /= norm);
+            basis.push(c);
+        }
+    }
+    basis
+}
+
+/// Eigen-decomposition of a symmetric m × m matrix (row-major) by cyclic
+/// Jacobi rotations. Returns eigenvalues in descending order with their
+/// unit eigenvectors.
+pub(crate) fn symmetric_eigen(mut a: Vec<f64>, m: usize) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let mut v = vec![0.0; m * m];
+    for i in 0..m {
+        v[i * m + i] = 1.0;
+    }
+    let scale: f64 = a.iter().map(|x| x * x).sum::<f64>().max(f64::MIN_POSITIVE);
+    for _sweep in 0..64 {
+        let mut off = 0.0;
+        for p in 0..m {
+            for q in (p + 1)..m {
+                off += a[p * m + q] * a[p * m + q];
+            }
+        }
+        if off <= 1e-30 * scale {
+            break;
+        }
+        for p in 0..m {
+            for q in (p + 1)..m {
+                let apq = a[p * m + q];
+                if apq == 0.0 {
+                    continue;
+                }
+                let theta = (a[q * m + q] - a[p * m + p]) / (2.0 * apq);
+                let t = if theta == 0.0 {
+                    1.0
+                } else {
+                    theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt())
+                };
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..m {
+                    let (akp, akq) = (a[k * m + p], a[k * m + q]);
+                    a[k * m + p] = c * akp - s * akq;
+                    a[k * m + q] = s * akp + c * akq;
+                }
+                for k in 0..m {
+                    let (apk, aqk) = (a[p * m + k], a[q * m + k]);
+                    a[p * m + k] = c * apk - s * aqk;
+                    a[q * m + k] = s * apk + c * aqk;
+                }
+                for k in 0..m {
+                    let (vkp, vkq) = (v[k * m + p], v[k * m + q]);
+                    v[k * m + p] = c * vkp - s * vkq;
+                    v[k * m + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&i, &j| a[j * m + j].total_cmp(&a[i * m + i]));
+    let values = order.iter().map(|&i| a[i * m + i]).collect();
+    let vectors = order
+        .iter()
+        .map(|&i| (0..m).map(|k| v[k * m + i]).collect())
+        .collect();
+    (values, vectors)
+}
+
+/// Thin SVD of a short, wide matrix given as rows: returns up to `keep`
+/// (singular value, right singular vector) pairs, largest first.
+fn wide_svd(rows: &[Vec<f64>], keep: usize) -> Vec<(f64, Vec<f64>)> {
+    let m = rows.len();
+    let mut gram = vec![0.0; m * m];
+    for i in 0..m {
+        for j in i..m {
+            let g = dot(&rows[i], &rows[j]);
+            gram[i * m + j] = g;
+            gram[j * m + i] = g;
+        }
+    }
+    let (values, vectors) = symmetric_eigen(gram, m);
+    let d = rows.first().map_or(0, |r| r.len());
+    let tiny = values.first().copied().unwrap_or(0.0).max(0.0) * 1e-24;
+    let mut out = Vec::with_capacity(keep);
+    for (lambda, u) in values.into_iter().zip(vectors) {
+        if out.len() == keep || lambda <= tiny {
+            break;
+        }
+        let sigma = lambda.sqrt();
+        let mut v = vec![0.0; d];
+        for (row, &w) in rows.iter().zip(u.iter()) {
+            v.iter_mut()
+                .zip(row.iter())
+                .for_each(|(a, &b)| *a += w * b / sigma);
+        }
+        canonicalize_sign(&mut v);
+        out.push((sigma, v));
+    }
+    out
+}
+
+/// Largest-magnitude entry positive (deterministic component signs).
+fn canonicalize_sign(v: &mut [f64]) {
+    let max = v.iter().copied().fold(
+        0.0f64,
+        |best, x| if x.abs() > best.abs() { x } else { best },
+    );
+    if max < 0.0 {
+        v.iter_mut().for_each(|x| *x = -*x);
+    }
+}
+
+/// PCA by randomized SVD.
+///
+/// # Arguments
+/// * `data` - Row-major matrix (n_samples × n_features)
+/// * `n_components` - Components to keep
+/// * `transform` - Preprocessing (None, Standardize, Clr)
+/// * `oversamples` - Extra sketch vectors (10 is typical)
+/// * `power_iterations` - Subspace iterations (2–4 for slowly decaying spectra)
+/// * `seed` - Seed for the Gaussian test matrix
+///
+/// # Returns
+/// `PcaModel` with components, eigenvalues, explained-variance ratios,
+/// loadings and scores; `error` is set for mismatched dimensions.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn pca_randomized(
+    data: &[f32],
+    n_samples: usize,
+    n_features: usize,
+    n_components: usize,
+    transform: PcaTransform,
+    oversamples: usize,
+    power_iterations: usize,
+    seed: u64,
+) -> PcaModel {
+    if n_samples == 0 || n_features == 0 || n_samples.checked_mul(n_features) != Some(data.len()) {
+        return PcaModel::failed(
+            n_features,
+            transform,
+            format!(
+                "data length {} does not match {} × {}",
+                data.len(),
+                n_samples,
+                n_features
+            ),
+        );
+    }
+    let (x, mean, scale) = preprocess(data, n_samples, n_features, transform);
+    let denom = n_samples.saturating_sub(1).max(1) as f64;
+    let total_variance = x.iter().map(|v| v * v).sum::<f64>() / denom;
+
+    let k = n_components.min(n_samples).min(n_features);
+    let l = (k + oversamples).min(n_samples).min(n_features);
+    let mut rng = SplitMix64::new(seed);
+    let omega: Vec<Vec<f64>> = (0..l)
+        .map(|_| (0..n_features).map(|_| rng.gaussian()).collect())
+        .collect();
+    let mut q = orthonormalize(x_times(&x, n_features, &omega));
+    for _ in 0..power_iterations {
+        let z = orthonormalize(xt_times(&x, n_features, &q));
+        q = orthonormalize(x_times(&x, n_features, &z));
+    }
+    // B = QᵀX, stored as rows of length n_features.
+    let b = xt_times(&x, n_features, &q);
+    let pairs = wide_svd(&b, k);
+
+    let components: Vec<f64> = pairs.iter().flat_map(|(_, v)| v.iter().copied()).collect();
+    let comp_cols: Vec<Vec<f64>> = pairs.iter().map(|(_, v)| v.clone()).collect();
+    let score_cols = x_times(&x, n_features, &comp_cols);
+    let mut scores = Vec::with_capacity(n_samples * pairs.len());
+    for i in 0..n_samples {
+        scores.extend(score_cols.iter().map(|col| col[i]));
+    }
+    PcaModel {
+        components,
+        singular_values: pairs.iter().map(|&(s, _)| s).collect(),
+        eigenvalues: pairs.iter().map(|&(s, _)| s * s / denom).collect(),
+        total_variance,
+        mean,
+        scale,
+        scores,
+        transform,
+        n_samples,
+        n_features,
+        error: None,
+    }
+}
+
+/// PCA fitted from chunks of samples.
+///
+/// Standardization needs global feature variances and is not supported;
+/// CLR is applied per sample. Chunks should hold at least `n_components`
+/// samples; the per-chunk cost grows with the cube of the chunk size.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct IncrementalPca {
+    n_features: usize,
+    n_components: usize,
+    transform: PcaTransform,
+    n_seen: usize,
+    mean: Vec<f64>,
+    /// Sum of squared deviations from the running mean, over all features.
+    sum_sq: f64,
+    components: Vec<(f64, Vec<f64>)>,
+    error: Option<String>,
+}
+
+#[wasm_bindgen]
+impl IncrementalPca {
+    #[wasm_bindgen(constructor)]
+    pub fn new(n_features: usize, n_components: usize, transform: PcaTransform) -> IncrementalPca {
+        let error = if transform == PcaTransform::Standardize {
+            Some("standardization is not supported for incremental PCA".to_string())
+        } else if n_features == 0 || n_components == 0 {
+            Some("n_features and n_components must be positive".to_string())
+        } else {
+            None
+        };
+        IncrementalPca {
+            n_features,
+            n_components: n_components.min(n_features),
+            transform,
+            n_seen: 0,
+            mean: vec![0.0; n_features],
+            sum_sq: 0.0,
+            components: Vec::new(),
+            error,
+        }
+    }
+
+    /// Fold a chunk (row-major, n_samples × n_features) into the model.
+    ///
+    /// Returns false if the model is in an error state or the chunk size
+    /// does not match.
+    pub fn partial_fit(&mut self, chunk: &[f32], n_samples: usize) -> bool {
+        let d = self.n_features;
+        if self.error.is_some() || n_samples == 0 || n_samples.checked_mul(d) != Some(chunk.len()) {
+            return false;
+        }
+        let mut x: Vec<f64> = chunk.iter().map(|&v| v as f64).collect();
+        if self.transform == PcaTransform::Clr {
+            x.chunks_exact_mut(d).for_each(clr_row);
+        }
+        let mut batch_mean = vec![0.0; d];
+        for row in x.chunks_exact(d) {
+            for (m, &v) in batch_mean.iter_mut().zip(row.iter()) {
+                *m += v;
+            }
+        }
+        batch_mean.iter_mut().for_each(|m| *m /= n_samples as f64);
+
+        let n_old = self.n_seen as f64;
+        let n_new = n_old + n_samples as f64;
+        let mut rows: Vec<Vec<f64>> = self
+            .components
+            .iter()
+            .map(|(s, v)| v.iter().map(|&c| s * c).collect())
+            .collect();
+        let mut batch_sum_sq = 0.0;
+        for row in x.chunks_exact(d) {
+            let centered: Vec<f64> = row.iter().zip(&batch_mean).map(|(v, m)| v - m).collect();
+            batch_sum_sq += dot(&centered, &centered);
+            rows.push(centered);
+        }
+        if self.n_seen > 0 {
+            let w = (n_old * n_samples as f64 / n_new).sqrt();
+            let shift: Vec<f64> = self
+                .mean
+                .iter()
+                .zip(&batch_mean)
+                .map(|(a, b)| w * (a - b))
+                .collect();
+            batch_sum_sq += dot(&shift, &shift);
+            rows.push(shift);
+        }
+        self.components = wide_svd(&rows, self.n_components);
+        self.sum_sq += batch_sum_sq;
+        for (m, &b) in self.mean.iter_mut().zip(&batch_mean) {
+            *m = (n_old * *m + n_samples as f64 * b) / n_new;
+        }
+        self.n_seen += n_samples;
+        true
+    }
+
+    /// Samples folded in so far.
+    #[wasm_bindgen(getter)]
+    pub fn n_samples_seen(&self) -> usize {
+        self.n_seen
+    }
+
+    /// Snapshot of the current model (without scores; use `project`).
+    pub fn model(&self) -> PcaModel {
+        if let Some(error) = &self.error {
+            return PcaModel::failed(self.n_features, self.transform, error.clone());
+        }
+        let denom = self.n_seen.saturating_sub(1).max(1) as f64;
+        PcaModel {
+            components: self
+                .components
+                .iter()
+                .flat_map(|(_, v)| v.iter().copied())
+                .collect(),
+            singular_values: self.components.iter().map(|&(s, _)| s).collect(),
+            eigenvalues: self
+                .components
+                .iter()
+                .map(|&(s, _)| s * s / denom)
+                .collect(),
+            total_variance: self.sum_sq / denom,
+            mean: self.mean.clone(),
+            scale: vec![1.0; self.n_features],
+            scores: Vec::new(),
+            transform: self.transform,
+            n_samples: self.n_seen,
+            n_features: self.n_features,
+            error: None,
+        }
+    }
+
+    /// Error message for an invalid configuration.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+#[cfg(test)]
+mod pca_tests {
+    use super::*;
+
+    /// n × d samples from a rank-3 signal plus small noise.
+    fn low_rank_data(n: usize, d: usize, seed: u64) -> Vec<f32> {
+        let mut rng = SplitMix64::new(seed);
+        // Per-feature loadings on the three latent axes.
+        let axes: Vec<[f64; 3]> = (0..d)
+            .map(|_| [rng.gaussian(), rng.gaussian(), rng.gaussian()])
+            .collect();
+        let weights = [10.0, 5.0, 2.0];
+        let mut data = Vec::with_capacity(n * d);
+        for _ in 0..n {
+            let coefs: Vec<f64> = weights.iter().map(|w| w * rng.gaussian()).collect();
+            for axis in &axes {
+                let signal: f64 = coefs.iter().zip(axis.iter()).map(|(c, a)| c * a).sum();
+                data.push((signal + 0.01 * rng.gaussian() + 3.0) as f32);
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_matches_power_iteration() {
+        let (n, d) = (80, 40);
+        let data = low_rank_data(n, d, 1);
+        let model = pca_randomized(&data, n, d, 3, PcaTransform::None, 10, 2, 7);
+        let reference = crate::pca_power_iteration(
+            &data.iter().map(|&v| v as f64).collect::<Vec<_>>(),
+            n,
+            d,
+            3,
+            500,
+            1e-12,
+        );
+        for c in 0..3 {
+            let rel =
+                (model.eigenvalues[c] - reference.eigenvalues[c]).abs() / reference.eigenvalues[c];
+            assert!(rel < 1e-6, "component {} rel {}", c, rel);
+            let cos = dot(
+                &model.components[c * d..(c + 1) * d],
+                &reference.eigenvectors[c * d..(c + 1) * d],
+            );
+            assert!(cos.abs() > 1.0 - 1e-6, "component {} cos {}", c, cos);
+        }
+        let explained: f64 = model.eigenvalues.iter().sum::<f64>() / model.total_variance;
+        assert!(explained > 0.999 && explained <= 1.0 + 1e-9);
+    }
+
+    #[test]
+    fn test_scores_match_projection() {
+        let (n, d) = (50, 20);
+        let data = low_rank_data(n, d, 2);
+        let model = pca_randomized(&data, n, d, 2, PcaTransform::Standardize, 5, 3, 1);
+        let projected = model.project_rows(&data, n);
+        assert_eq!(projected.len(), model.scores.len());
+        for (a, b) in projected.iter().zip(model.scores.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        // Score variance equals the eigenvalue; standardized total variance is d.
+        let var0: f64 = (0..n).map(|i| model.scores[i * 2].powi(2)).sum::<f64>() / (n - 1) as f64;
+        assert!((var0 - model.eigenvalues[0]).abs() < 1e-9 * var0);
+        assert!((model.total_variance - d as f64).abs() < 1e-9);
+        assert!(
+            pca_randomized(&data, n, d + 1, 2, PcaTransform::None, 5, 1, 1)
+                .error
+                .is_some()
+        );
+        // n_samples * d wraps around to data.len().
+        let wrapping = n + (1usize << 62);
+        assert!(
+            pca_randomized(&data, wrapping, d, 2, PcaTransform::None, 5, 1, 1)
+                .error
+                .is_some()
+        );
+        assert!(model.project_rows(&data, wrapping).is_empty());
+        let mut inc = IncrementalPca::new(d, 2, PcaTransform::None);
+        assert!(!inc.partial_fit(&data, wrapping));
+    }
+
+    #[test]
+    fn test_clr_ignores_row_scale() {
+        let (n, d) = (30, 16);
+        let data: Vec<f32> = low_rank_data(n, d, 3).iter().map(|v| v.abs()).collect();
+        let scaled: Vec<f32> = data
+            .chunks_exact(d)
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().map(move |&v| v * (i + 1) as f32))
+            .collect();
+        let a = pca_randomized(&data, n, d, 2, PcaTransform::Clr, 10, 2, 5);
+        let b = pca_randomized(&scaled, n, d, 2, PcaTransform::Clr, 10, 2, 5);
+        for (x, y) in a.eigenvalues.iter().zip(b.eigenvalues.iter()) {
+            assert!((x - y).abs() < 1e-4 * x, "{} vs {}", x, y);
+        }
+    }
+
+    #[test]
+    fn test_incremental_matches_batch() {
+        let (n, d) = (120, 24);
+        let data = low_rank_data(n, d, 4);
+        let batch = pca_randomized(&data, n, d, 3, PcaTransform::None, 10, 3, 2);
+        let mut inc = IncrementalPca::new(d, 3, PcaTransform::None);
+        for chunk in data.chunks(40 * d) {
+            assert!(inc.partial_fit(chunk, chunk.len() / d));
+        }
+        let model = inc.model();
+        assert_eq!(model.n_samples, n);
+        assert!((model.total_variance - batch.total_variance).abs() < 1e-6 * batch.total_variance);
+        for c in 0..3 {
+            let rel = (model.eigenvalues[c] - batch.eigenvalues[c]).abs() / batch.eigenvalues[c];
+            assert!(rel < 1e-3, "component {} rel {}", c, rel);
+            let cos = dot(
+                &model.components[c * d..(c + 1) * d],
+                &batch.components[c * d..(c + 1) * d],
+            );
+            assert!(cos > 0.999, "component {} cos {}", c, cos);
+        }
+        assert!(IncrementalPca::new(d, 3, PcaTransform::Standardize)
+            .error
+            .is_some());
+    }
+}
@@ -0,0 +1,722 @@
+//! Distance-based phylogenetic trees.
+//!
+//! Builds trees from a condensed distance matrix (`CondensedDistanceMatrix`
+//! values or any SciPy-style condensed array):
+//! - UPGMA / WPGMA (ultrametric, rooted)
+//! - Neighbor joining, plus a RapidNJ-style search that keeps each row
+//!   sorted and prunes with the bound `(m-2)·d(i,j) - r_i - r_max`
+//!
+//! `PhyloTree` exports Newick with branch lengths, can be rerooted at the
+//! midpoint of its longest leaf-to-leaf path, and produces phylogram
+//! coordinates for the web and TUI renderers.
+
+use wasm_bindgen::prelude::*;
+
+use crate::distance_matrix::condensed_index;
+use crate::msa::newick_label;
+
+/// Maximum number of taxa (NJ keeps a full n × n f64 matrix).
+pub(crate) const TREE_MAX_TAXA: usize = 3_000;
+
+/// Tree construction method.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeMethod {
+    /// Unweighted pair group method with arithmetic mean
+    Upgma = 0,
+    /// Weighted pair group method with arithmetic mean
+    Wpgma = 1,
+    /// Saitou & Nei neighbor joining (exhaustive Q search)
+    NeighborJoining = 2,
+    /// Neighbor joining with RapidNJ-style bounded search (same tree up to ties)
+    RapidNj = 3,
+}
+
+#[derive(Clone)]
+struct TreeNode {
+    name: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// Length of the branch to the parent (0 for the root).
+    length: f64,
+}
+
+/// A rooted phylogenetic tree.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct PhyloTree {
+    nodes: Vec<TreeNode>,
+    root: usize,
+    error: Option<String>,
+}
+
+impl PhyloTree {
+    fn failed(message: String) -> PhyloTree {
+        PhyloTree {
+            nodes: Vec::new(),
+            root: 0,
+            error: Some(message),
+        }
+    }
+
+    fn leaf(&mut self, name: &str) -> usize {
+        self.nodes.push(TreeNode {
+            name: name.to_string(),
+            parent: None,
+            children: Vec::new(),
+            length: 0.0,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn join(&mut self, children: &[(usize, f64)]) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(TreeNode {
+            name: String::new(),
+            parent: None,
+            children: children.iter().map(|&(c, _)| c).collect(),
+            length: 0.0,
+        });
+        for &(child, length) in children {
+            self.nodes[child].parent = Some(id);
+            self.nodes[child].length = length.max(0.0);
+        }
+        id
+    }
+
+    /// Nodes in pre-order from the root.
+    fn preorder(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(self.nodes.len());
+        if self.nodes.is_empty() {
+            return order;
+        }
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            order.push(id);
+            stack.extend(self.nodes[id].children.iter().rev());
+        }
+        order
+    }
+
+    /// Undirected adjacency (neighbor, edge length) over all nodes.
+    fn adjacency(&self) -> Vec<Vec<(usize, f64)>> {
+        let mut adj: Vec<Vec<(usize, f64)>> = vec![Vec::new(); self.nodes.len()];
+        for (id, node) in self.nodes.iter().enumerate() {
+            if let Some(parent) = node.parent {
+                adj[id].push((parent, node.length));
+                adj[parent].push((id, node.length));
+            }
+        }
+        adj
+    }
+
+    /// Farthest node from `start` (by path length), with predecessor links.
+    fn farthest(adj: &[Vec<(usize, f64)>], start: usize) -> (usize, Vec<f64>, Vec<usize>) {
+        let mut dist = vec![f64::NAN; adj.len()];
+        let mut prev = vec![usize::MAX; adj.len()];
+        dist[start] = 0.0;
+        let mut stack = vec![start];
+        let mut best = start;
+        while let Some(u) = stack.pop() {
+            if dist[u] > dist[best] {
+                best = u;
+            }
+            for &(v, w) in &adj[u] {
+                if dist[v].is_nan() {
+                    dist[v] = dist[u] + w;
+                    prev[v] = u;
+                    stack.push(v);
+                }
+            }
+        }
+        (best, dist, prev)
+    }
+
+    /// Rebuild `nodes` as a tree rooted at `root` over an undirected graph.
+    fn rebuild_from(&mut self, adj: &[Vec<(usize, f64)>], names: &[String], root: usize) {
+        let mut nodes: Vec<TreeNode> = Vec::with_capacity(adj.len());
+        // (old id, old parent, branch length, new parent)
+        let mut stack: Vec<(usize, usize, f64, Option<usize>)> =
+            vec![(root, usize::MAX, 0.0, None)];
+        while let Some((old, from, length, parent)) = stack.pop() {
+            let id = nodes.len();
+            nodes.push(TreeNode {
+                name: names[old].clone(),
+                parent,
+                children: Vec::new(),
+                length,
+            });
+            if let Some(p) = parent {
+                nodes[p].children.push(id);
+            }
+            for &(next, w) in adj[old].iter().rev() {
+                if next != from {
+                    stack.push((next, old, w, Some(id)));
+                }
+            }
+        }
+        // Children were pushed in stack order; restore adjacency order.
+        for node in &mut nodes {
+            node.children.reverse();
+        }
+        self.nodes = nodes;
+        self.root = 0;
+    }
+
+    fn write_newick(&self, id: usize, out: &mut String) {
+        let node = &self.nodes[id];
+        if !node.children.is_empty() {
+            out.push('(');
+            for (i, &child) in node.children.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                self.write_newick(child, out);
+            }
+            out.push(')');
+        }
+        out.push_str(&newick_label(&node.name));
+        if node.parent.is_some() {
+            out.push_str(&format!(":{:.6}", node.length));
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl PhyloTree {
+    /// Newick string with branch lengths (6 decimals).
+    #[wasm_bindgen(getter)]
+    pub fn newick(&self) -> String {
+        if self.nodes.is_empty() {
+            return String::from(";");
+        }
+        let mut out = String::new();
+        self.write_newick(self.root, &mut out);
+        out.push(';');
+        out
+    }
+
+    /// Total number of nodes (leaves and internal).
+    #[wasm_bindgen(getter)]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Number of leaves.
+    #[wasm_bindgen(getter)]
+    pub fn leaf_count(&self) -> usize {
+        self.nodes.iter().filter(|n| n.children.is_empty()).count()
+    }
+
+    /// Name of node `index` (empty for internal nodes or out of range).
+    pub fn node_name(&self, index: usize) -> String {
+        self.nodes
+            .get(index)
+            .map(|n| n.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Reroot at the midpoint of the longest leaf-to-leaf path.
+    ///
+    /// A binary root left over from a previous rooting is dissolved first,
+    /// so repeated calls keep the same root position.
+    pub fn midpoint_root(&mut self) {
+        if self.nodes.len() < 3 {
+            return;
+        }
+        let mut adj = self.adjacency();
+        let mut names: Vec<String> = self.nodes.iter().map(|n| n.name.clone()).collect();
+
+        // Dissolve a degree-2 root by joining its two neighbors directly.
+        if adj[self.root].len() == 2 {
+            let (a, wa) = adj[self.root][0];
+            let (b, wb) = adj[self.root][1];
+            for (x, y) in [(a, b), (b, a)] {
+                adj[x].retain(|&(v, _)| v != self.root);
+                adj[x].push((y, wa + wb));
+            }
+            adj[self.root].clear();
+        }
+
+        let start = (0..adj.len()).find(|&i| adj[i].len() == 1).unwrap_or(0);
+        let (end_a, _, _) = PhyloTree::farthest(&adj, start);
+        let (end_b, dist, prev) = PhyloTree::farthest(&adj, end_a);
+        let half = dist[end_b] / 2.0;
+
+        // Walk from end_b back toward end_a until the midpoint is passed.
+        let mut v = end_b;
+        let mut u = prev[v];
+        while u != usize::MAX && dist[u] > half {
+            v = u;
+            u = prev[v];
+        }
+        if u == usize::MAX {
+            return;
+        }
+
+        // Midpoint lies on edge u—v at distance (half - dist[u]) from u.
+        let from_u = half - dist[u];
+        let edge = dist[v] - dist[u];
+        let root = if from_u <= 1e-12 {
+            u
+        } else if edge - from_u <= 1e-12 {
+            v
+        } else {
+            let mid = adj.len();
+            adj.push(vec![(u, from_u), (v, edge - from_u)]);
+            names.push(String::new());
+            adj[u].retain(|&(x, _)| x != v);
+            adj[u].push((mid, from_u));
+            adj[v].retain(|&(x, _)| x != u);
+            adj[v].push((mid, edge - from_u));
+            mid
+        };
+
+        self.rebuild_from(&adj, &names, root);
+    }
+
+    /// Phylogram coordinates for rendering.
+    pub fn layout(&self) -> TreeLayout {
+        let n = self.nodes.len();
+        let mut x = vec![0.0f32; n];
+        let mut y = vec![0.0f32; n];
+        let order = self.preorder();
+
+        let mut depth = vec![0.0f64; n];
+        let mut next_leaf = 0.0f32;
+        for &id in &order {
+            if let Some(p) = self.nodes[id].parent {
+                depth[id] = depth[p] + self.nodes[id].length;
+            }
+            x[id] = depth[id] as f32;
+            if self.nodes[id].children.is_empty() {
+                y[id] = next_leaf;
+                next_leaf += 1.0;
+            }
+        }
+        // Internal nodes sit midway between their first and last child.
+        for &id in order.iter().rev() {
+            let children = &self.nodes[id].children;
+            if let (Some(&first), Some(&last)) = (children.first(), children.last()) {
+                y[id] = (y[first] + y[last]) / 2.0;
+            }
+        }
+
+        TreeLayout {
+            x,
+            y,
+            parents: self
+                .nodes
+                .iter()
+                .map(|n| n.parent.map_or(-1, |p| p as i32))
+                .collect(),
+            branch_lengths: self.nodes.iter().map(|n| n.length as f32).collect(),
+            is_leaf: self
+                .nodes
+                .iter()
+                .map(|n| u8::from(n.children.is_empty()))
+                .collect(),
+        }
+    }
+
+    /// Error message if the tree could not be built.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Per-node phylogram coordinates, indexed by node id.
+///
+/// `x` is the distance from the root; leaves get `y = 0, 1, 2, ...` in
+/// pre-order and internal nodes sit midway between their first and last
+/// child. Draw each node as a horizontal segment from `x[parent]` to `x[i]`
+/// at `y[i]`, and each internal node as a vertical segment at `x[i]`.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct TreeLayout {
+    x: Vec<f32>,
+    y: Vec<f32>,
+    parents: Vec<i32>,
+    branch_lengths: Vec<f32>,
+    is_leaf: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl TreeLayout {
+    /// Distance from the root per node as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn x(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.x.len() as u32);
+        arr.copy_from(&self.x);
+        arr
+    }
+
+    /// Vertical position per node as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn y(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.y.len() as u32);
+        arr.copy_from(&self.y);
+        arr
+    }
+
+    /// Parent node id per node (-1 for the root) as Int32Array.
+    #[wasm_bindgen(getter)]
+    pub fn parents(&self) -> js_sys::Int32Array {
+        let arr = js_sys::Int32Array::new_with_length(self.parents.len() as u32);
+        arr.copy_from(&self.parents);
+        arr
+    }
+
+    /// Branch length to the parent per node as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn branch_lengths(&self) -> js_sys::Float32Array {
+        let arr = js_sys::Float32Array::new_with_length(self.branch_lengths.len() as u32);
+        arr.copy_from(&self.branch_lengths);
+        arr
+    }
+
+    /// 1 for leaves, 0 for internal nodes, as Uint8Array.
+    #[wasm_bindgen(getter)]
+    pub fn is_leaf(&self) -> js_sys::Uint8Array {
+        let arr = js_sys::Uint8Array::new_with_length(self.is_leaf.len() as u32);
+        arr.copy_from(&self.is_leaf);
+        arr
+    }
+}
+
+/// Number of taxa for a condensed matrix of `len` entries, if valid.
+pub(crate) fn taxa_from_condensed(len: usize) -> Option<usize> {
+    let n = ((1.0 + (1.0 + 8.0 * len as f64).sqrt()) / 2.0).round() as usize;
+    (n * n.saturating_sub(1) / 2 == len).then_some(n)
+}
+
+/// Expand a condensed matrix to a full symmetric n × n matrix.
+fn full_matrix(condensed: &[f32], n: usize) -> Vec<f64> {
+    let mut d = vec![0.0f64; n * n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let v = condensed[condensed_index(n, i, j)] as f64;
+            d[i * n + j] = v;
+            d[j * n + i] = v;
+        }
+    }
+    d
+}
+
+fn build_pgma(tree: &mut PhyloTree, mut d: Vec<f64>, n: usize, weighted: bool) {
+    let mut active = vec![true; n];
+    let mut sizes = vec![1usize; n];
+    let mut slot_node: Vec<usize> = (0..n).collect();
+    let mut heights = vec![0.0f64; 2 * n];
+
+    for _ in 1..n {
+        let mut best = (0, 0, f64::INFINITY);
+        for i in 0..n {
+            if !active[i] {
+                continue;
+            }
+            for j in (i + 1)..n {
+                if active[j] && d[i * n + j] < best.2 {
+                    best = (i, j, d[i * n + j]);
+                }
+            }
+        }
+        let (i, j, dij) = best;
+        let h = dij / 2.0;
+        let (ni, nj) = (slot_node[i], slot_node[j]);
+        let u = tree.join(&[(ni, h - heights[ni]), (nj, h - heights[nj])]);
+        heights[u] = h;
+
+        let (si, sj) = (sizes[i] as f64, sizes[j] as f64);
+        for k in 0..n {
+            if active[k] && k != i && k != j {
+                let merged = if weighted {
+                    (d[i * n + k] + d[j * n + k]) / 2.0
+                } else {
+                    (d[i * n + k] * si + d[j * n + k] * sj) / (si + sj)
+                };
+                d[i * n + k] = merged;
+                d[k * n + i] = merged;
+            }
+        }
+        active[j] = false;
+        sizes[i] += sizes[j];
+        slot_node[i] = u;
+    }
+    tree.root = tree.nodes.len() - 1;
+}
+
+fn build_nj(tree: &mut PhyloTree, mut d: Vec<f64>, n: usize, rapid: bool) {
+    let mut active = vec![true; n];
+    let mut slot_node: Vec<usize> = (0..n).collect();
+    let mut r: Vec<f64> = (0..n).map(|i| d[i * n..(i + 1) * n].iter().sum()).collect();
+
+    // RapidNJ state: per-slot rows sorted by distance, tagged with the
+    // generation of the column slot so stale entries can be skipped.
+    let mut generation = vec![0u32; n];
+    let mut rows: Vec<Vec<(f64, usize, u32)>> = Vec::new();
+    if rapid {
+        rows = (0..n)
+            .map(|i| {
+                let mut row: Vec<(f64, usize, u32)> = (0..n)
+                    .filter(|&j| j != i)
+                    .map(|j| (d[i * n + j], j, 0))
+                    .collect();
+                row.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+                row
+            })
+            .collect();
+    }
+
+    let mut m = n;
+    while m > 2 {
+        let scale = (m - 2) as f64;
+        let mut best = (0, 0, f64::INFINITY);
+
+        if rapid {
+            let r_max = (0..n)
+                .filter(|&k| active[k])
+                .map(|k| r[k])
+                .fold(f64::NEG_INFINITY, f64::max);
+            for i in (0..n).filter(|&i| active[i]) {
+                for &(dij, j, g) in &rows[i] {
+                    if !active[j] || generation[j] != g {
+                        continue;
+                    }
+                    if scale * dij - r[i] - r_max > best.2 {
+                        break;
+                    }
+                    let q = scale * dij - r[i] - r[j];
+                    if q < best.2 {
+                        best = (i.min(j), i.max(j), q);
+                    }
+                }
+            }
+        } else {
+            for i in 0..n {
+                if !active[i] {
+                    continue;
+                }
+                for j in (i + 1)..n {
+                    if active[j] {
+                        let q = scale * d[i * n + j] - r[i] - r[j];
+                        if q < best.2 {
+                            best = (i, j, q);
+                        }
+                    }
+                }
+            }
+        }
+
+        let (i, j, _) = best;
+        let dij = d[i * n + j];
+        let li = 0.5 * dij + (r[i] - r[j]) / (2.0 * scale);
+        let lj = dij - li;
+        let u = tree.join(&[(slot_node[i], li), (slot_node[j], lj)]);
+
+        active[j] = false;
+        r[i] = 0.0;
+        for k in 0..n {
+            if active[k] && k != i {
+                let duk = (d[i * n + k] + d[j * n + k] - dij) / 2.0;
+                r[k] += duk - d[k * n + i] - d[k * n + j];
+                d[i * n + k] = duk;
+                d[k * n + i] = duk;
+                r[i] += duk;
+            }
+        }
+        slot_node[i] = u;
+        m -= 1;
+
+        if rapid {
+            generation[i] += 1;
+            let mut row: Vec<(f64, usize, u32)> = (0..n)
+                .filter(|&k| active[k] && k != i)
+                .map(|k| (d[i * n + k], k, generation[k]))
+                .collect();
+            row.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            rows[i] = row;
+            rows[j] = Vec::new();
+        }
+    }
+
+    // Join the last two clusters. Attach to the internal one so the root is
+    // the usual unrooted trifurcation.
+    let remaining: Vec<usize> = (0..n).filter(|&k| active[k]).collect();
+    let (a, b) = (remaining[0], remaining[1]);
+    let dab = d[a * n + b];
+    let (na, nb) = (slot_node[a], slot_node[b]);
+    if n == 2 {
+        tree.root = tree.join(&[(na, dab / 2.0), (nb, dab / 2.0)]);
+    } else {
+        let (host, other) = if tree.nodes[na].children.is_empty() {
+            (nb, na)
+        } else {
+            (na, nb)
+        };
+        tree.nodes[host].children.push(other);
+        tree.nodes[other].parent = Some(host);
+        tree.nodes[other].length = dab.max(0.0);
+        tree.root = host;
+    }
+}
+
+/// Build a tree from a condensed distance matrix.
+///
+/// # Arguments
+/// * `condensed` - Upper-triangle distances, row-major (n*(n-1)/2 values)
+/// * `names` - Taxon names (missing names become `t0`, `t1`, ...)
+/// * `method` - Construction method
+///
+/// # Returns
+/// `PhyloTree`. UPGMA/WPGMA trees are rooted at the last merge; NJ trees
+/// are unrooted (trifurcating root) until `midpoint_root` is called.
+/// Negative NJ branch lengths are clamped to 0.
+#[wasm_bindgen]
+pub fn build_tree(condensed: &[f32], names: Vec<String>, method: TreeMethod) -> PhyloTree {
+    let Some(n) = taxa_from_condensed(condensed.len()) else {
+        return PhyloTree::failed(format!(
+            "Condensed matrix length {} is not n*(n-1)/2",
+            condensed.len()
+        ));
+    };
+    if n == 0 {
+        return PhyloTree::failed("No taxa".to_string());
+    }
+    if n > TREE_MAX_TAXA {
+        return PhyloTree::failed(format!("Too many taxa: {} (max {})", n, TREE_MAX_TAXA));
+    }
+    if condensed.iter().any(|v| !v.is_finite()) {
+        return PhyloTree::failed("Distance matrix contains non-finite values".to_string());
+    }
+
+    let mut tree = PhyloTree {
+        nodes: Vec::with_capacity(2 * n),
+        root: 0,
+        error: None,
+    };
+    for i in 0..n {
+        let name = names.get(i).cloned().unwrap_or_else(|| format!("t{}", i));
+        tree.leaf(&name);
+    }
+    if n == 1 {
+        return tree;
+    }
+
+    let d = full_matrix(condensed, n);
+    match method {
+        TreeMethod::Upgma => build_pgma(&mut tree, d, n, false),
+        TreeMethod::Wpgma => build_pgma(&mut tree, d, n, true),
+        TreeMethod::NeighborJoining => build_nj(&mut tree, d, n, false),
+        TreeMethod::RapidNj => build_nj(&mut tree, d, n, true),
+    }
+    tree
+}
+
+#[cfg(test)]
+mod phylo_tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Classic additive 5-taxon example (a..e).
+    fn additive_matrix() -> Vec<f32> {
+        // ab ac ad ae bc bd be cd ce de
+        vec![5.0, 9.0, 9.0, 8.0, 10.0, 10.0, 9.0, 8.0, 7.0, 3.0]
+    }
+
+    fn leaf_length(tree: &PhyloTree, name: &str) -> f64 {
+        tree.nodes.iter().find(|n| n.name == name).unwrap().length
+    }
+
+    #[test]
+    fn test_upgma_ultrametric() {
+        // a-b 2, c-d 4, across 8
+        let d = vec![2.0, 8.0, 8.0, 8.0, 8.0, 4.0];
+        let tree = build_tree(&d, names(&["a", "b", "c", "d"]), TreeMethod::Upgma);
+        assert!(tree.error.is_none());
+        assert_eq!(
+            tree.newick(),
+            "((a:1.000000,b:1.000000):3.000000,(c:2.000000,d:2.000000):2.000000);"
+        );
+    }
+
+    #[test]
+    fn test_nj_recovers_additive_tree() {
+        let labels = names(&["a", "b", "c", "d", "e"]);
+        for method in [TreeMethod::NeighborJoining, TreeMethod::RapidNj] {
+            let tree = build_tree(&additive_matrix(), labels.clone(), method);
+            assert_eq!(tree.leaf_count(), 5);
+            for (name, expected) in [("a", 2.0), ("b", 3.0), ("c", 4.0), ("d", 2.0), ("e", 1.0)] {
+                assert!(
+                    (leaf_length(&tree, name) - expected).abs() < 1e-9,
+                    "{:?} {}",
+                    method,
+                    name
+                );
+            }
+            let total: f64 = tree.nodes.iter().map(|n| n.length).sum();
+            assert!((total - 17.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_rapid_nj_matches_exhaustive() {
+        // Deterministic pseudo-random tree-like matrix.
+        let n = 12;
+        let mut condensed = Vec::new();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let x = ((i * 37 + j * 101) % 17) as f32 / 10.0;
+                condensed.push((j - i) as f32 + x);
+            }
+        }
+        let exhaustive = build_tree(&condensed, Vec::new(), TreeMethod::NeighborJoining);
+        let rapid = build_tree(&condensed, Vec::new(), TreeMethod::RapidNj);
+        assert_eq!(exhaustive.newick(), rapid.newick());
+    }
+
+    #[test]
+    fn test_midpoint_root_and_layout() {
+        let mut tree = build_tree(
+            &additive_matrix(),
+            names(&["a", "b", "c", "d", "e"]),
+            TreeMethod::NeighborJoining,
+        );
+        tree.midpoint_root();
+        let layout = tree.layout();
+        let max_depth = layout.x.iter().cloned().fold(0.0f32, f32::max);
+        // Longest leaf-to-leaf path is 10 (b to c or d), so depth is 5.
+        assert!((max_depth - 5.0).abs() < 1e-5);
+        let b = tree.nodes.iter().position(|n| n.name == "b").unwrap();
+        assert!((layout.x[b] - 5.0).abs() < 1e-5);
+        assert_eq!(layout.parents[tree.root], -1);
+        assert_eq!(layout.x[tree.root], 0.0);
+
+        let mut leaf_ys: Vec<f32> = (0..tree.nodes.len())
+            .filter(|&i| layout.is_leaf[i] == 1)
+            .map(|i| layout.y[i])
+            .collect();
+        leaf_ys.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(leaf_ys, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        // Rerooting again keeps the same root depth.
+        tree.midpoint_root();
+        let again = tree.layout();
+        let max_again = again.x.iter().cloned().fold(0.0f32, f32::max);
+        assert!((max_again - 5.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_invalid_condensed_length() {
+        let tree = build_tree(&[1.0, 2.0], Vec::new(), TreeMethod::Upgma);
+        assert!(tree.error.is_some());
+    }
+}
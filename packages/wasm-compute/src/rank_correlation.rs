@@ -0,0 +1,534 @@
+//! Dependence measures beyond Hoeffding's D.
+//!
+//! - Spearman's ρ: Pearson correlation of average ranks (ties handled),
+//!   with a Student's t p-value
+//! - Kendall's τ-b in O(n log n) (Knight's merge-sort algorithm), with a
+//!   tie-corrected normal p-value
+//! - Székely's distance correlation: zero only under independence, O(n²)
+//!   time and O(n) memory
+//! - Maximal information coefficient via ApproxMaxMI (Reshef et al. 2011)
+//!   with the MINE defaults B(n) = n^0.6 and c = 15
+//!
+//! Every measure is available on paired `Float64Array`s and on the k-mer
+//! count vectors of two sequences (all k-mers present in either, as for
+//! `kmer_hoeffdings_d`).
+
+use wasm_bindgen::prelude::*;
+
+use crate::stats::{normal_sf, student_t_two_sided};
+use crate::{average_rank, paired_kmer_counts};
+
+/// Grid size exponent: grids with nx·ny <= n^0.6 are searched.
+const MIC_ALPHA: f64 = 0.6;
+/// Superclumps per column allowed in the x-axis optimization.
+const MIC_CLUMP_FACTOR: usize = 15;
+
+/// A correlation coefficient with its significance.
+#[wasm_bindgen]
+pub struct CorrelationResult {
+    /// The coefficient (0 when undefined, e.g. constant input)
+    pub coefficient: f64,
+    /// Two-sided p-value under independence; NaN where no null
+    /// distribution is available (distance correlation, MIC) or n is too
+    /// small
+    pub p_value: f64,
+    /// Number of observations used
+    pub n: usize,
+}
+
+impl CorrelationResult {
+    fn undefined(n: usize) -> CorrelationResult {
+        CorrelationResult {
+            coefficient: 0.0,
+            p_value: f64::NAN,
+            n,
+        }
+    }
+}
+
+/// Pearson correlation; None if either side is constant.
+fn pearson(x: &[f64], y: &[f64]) -> Option<f64> {
+    let n = x.len() as f64;
+    let mx = x.iter().sum::<f64>() / n;
+    let my = y.iter().sum::<f64>() / n;
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (&a, &b) in x.iter().zip(y) {
+        sxy += (a - mx) * (b - my);
+        sxx += (a - mx) * (a - mx);
+        syy += (b - my) * (b - my);
+    }
+    (sxx > 0.0 && syy > 0.0).then(|| (sxy / (sxx * syy).sqrt()).clamp(-1.0, 1.0))
+}
+
+fn spearman(x: &[f64], y: &[f64]) -> CorrelationResult {
+    let n = x.len();
+    if n != y.len() || n < 3 {
+        return CorrelationResult::undefined(n);
+    }
+    let Some(rho) = pearson(&average_rank(x), &average_rank(y)) else {
+        return CorrelationResult::undefined(n);
+    };
+    let df = (n - 2) as f64;
+    let p_value = if rho.abs() >= 1.0 {
+        0.0
+    } else {
+        student_t_two_sided(rho * (df / (1.0 - rho * rho)).sqrt(), df)
+    };
+    CorrelationResult {
+        coefficient: rho,
+        p_value,
+        n,
+    }
+}
+
+/// Sizes of runs of equal adjacent values.
+fn tie_runs<T: PartialEq>(sorted: &[T]) -> Vec<f64> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for i in 1..=sorted.len() {
+        if i == sorted.len() || sorted[i] != sorted[start] {
+            if i - start > 1 {
+                runs.push((i - start) as f64);
+            }
+            start = i;
+        }
+    }
+    runs
+}
+
+/// Sort `values` in place, returning the number of inversions (pairs
+/// i < j with values[i] > values[j]).
+fn merge_sort_swaps(values: &mut [f64], buffer: &mut [f64]) -> u64 {
+    let n = values.len();
+    if n < 2 {
+        return 0;
+    }
+    let mid = n / 2;
+    let mut swaps = merge_sort_swaps(&mut values[..mid], &mut buffer[..mid]);
+    swaps += merge_sort_swaps(&mut values[mid..], &mut buffer[mid..]);
+    let (mut i, mut j) = (0, mid);
+    for slot in buffer[..n].iter_mut() {
+        if j >= n || (i < mid && values[i] <= values[j]) {
+            *slot = values[i];
+            i += 1;
+        } else {
+            *slot = values[j];
+            swaps += (mid - i) as u64;
+            j += 1;
+        }
+    }
+    values.copy_from_slice(&buffer[..n]);
+    swaps
+}
+
+fn kendall(x: &[f64], y: &[f64]) -> CorrelationResult {
+    let n = x.len();
+    if n != y.len() || n < 3 {
+        return CorrelationResult::undefined(n);
+    }
+    let mut order: Vec<(f64, f64)> = x.iter().copied().zip(y.iter().copied()).collect();
+    order.sort_unstable_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    let xs: Vec<f64> = order.iter().map(|p| p.0).collect();
+    let x_ties = tie_runs(&xs);
+    let joint_ties = tie_runs(&order);
+    let mut ys: Vec<f64> = order.iter().map(|p| p.1).collect();
+    let mut buffer = vec![0.0; n];
+    let swaps = merge_sort_swaps(&mut ys, &mut buffer) as f64;
+    let y_ties = tie_runs(&ys);
+
+    let pairs = |runs: &[f64]| runs.iter().map(|t| t * (t - 1.0) / 2.0).sum::<f64>();
+    let nf = n as f64;
+    let n0 = nf * (nf - 1.0) / 2.0;
+    let (n1, n2, n3) = (pairs(&x_ties), pairs(&y_ties), pairs(&joint_ties));
+    // Concordant minus discordant pairs.
+    let s = n0 - n1 - n2 + n3 - 2.0 * swaps;
+    let denom = ((n0 - n1) * (n0 - n2)).sqrt();
+    if denom <= 0.0 {
+        return CorrelationResult::undefined(n);
+    }
+
+    // Variance of S under independence with ties in both variables.
+    let sum = |runs: &[f64], f: &dyn Fn(f64) -> f64| runs.iter().map(|&t| f(t)).sum::<f64>();
+    let v0 = nf * (nf - 1.0) * (2.0 * nf + 5.0);
+    let vt = sum(&x_ties, &|t| t * (t - 1.0) * (2.0 * t + 5.0));
+    let vu = sum(&y_ties, &|t| t * (t - 1.0) * (2.0 * t + 5.0));
+    let v1 = sum(&x_ties, &|t| t * (t - 1.0)) * sum(&y_ties, &|t| t * (t - 1.0));
+    let v2 =
+        sum(&x_ties, &|t| t * (t - 1.0) * (t - 2.0)) * sum(&y_ties, &|t| t * (t - 1.0) * (t - 2.0));
+    let variance = (v0 - vt - vu) / 18.0
+        + v1 / (2.0 * nf * (nf - 1.0))
+        + v2 / (9.0 * nf * (nf - 1.0) * (nf - 2.0));
+    CorrelationResult {
+        coefficient: (s / denom).clamp(-1.0, 1.0),
+        p_value: (2.0 * normal_sf(s.abs() / variance.sqrt())).min(1.0),
+        n,
+    }
+}
+
+/// Row means and grand mean of the pairwise |a_i - a_j| matrix.
+fn distance_means(values: &[f64]) -> (Vec<f64>, f64) {
+    let n = values.len() as f64;
+    let rows: Vec<f64> = values
+        .iter()
+        .map(|&a| values.iter().map(|&b| (a - b).abs()).sum::<f64>() / n)
+        .collect();
+    let grand = rows.iter().sum::<f64>() / n;
+    (rows, grand)
+}
+
+fn dcor(x: &[f64], y: &[f64]) -> CorrelationResult {
+    let n = x.len();
+    if n != y.len() || n < 2 {
+        return CorrelationResult::undefined(n);
+    }
+    let (ax, gx) = distance_means(x);
+    let (ay, gy) = distance_means(y);
+    // Double-centered distances, recomputed on the fly to keep O(n) memory.
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for i in 0..n {
+        for j in 0..n {
+            let a = (x[i] - x[j]).abs() - ax[i] - ax[j] + gx;
+            let b = (y[i] - y[j]).abs() - ay[i] - ay[j] + gy;
+            cov += a * b;
+            var_x += a * a;
+            var_y += b * b;
+        }
+    }
+    let denom = (var_x * var_y).sqrt();
+    if denom <= 0.0 {
+        return CorrelationResult::undefined(n);
+    }
+    CorrelationResult {
+        coefficient: (cov.max(0.0) / denom).sqrt(),
+        p_value: f64::NAN,
+        n,
+    }
+}
+
+/// Equipartition of `values` into at most `bins` rows by rank, keeping
+/// equal values together. Returns (row per point, rows used).
+fn equipartition(values: &[f64], bins: usize) -> (Vec<usize>, usize) {
+    let n = values.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_unstable_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut rows = vec![0; n];
+    let mut row = 0;
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        while end < n && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        // Move to the next row once this one holds its share.
+        if start > 0 && start * bins >= (row + 1) * n {
+            row = (row + 1).min(bins - 1);
+        }
+        for &i in &order[start..end] {
+            rows[i] = row;
+        }
+        start = end;
+    }
+    (rows, row + 1)
+}
+
+/// Best mutual information (nats) for every column count 2..=max_cols,
+/// with rows fixed and columns placed along `x` (ApproxMaxMI's
+/// OptimizeXAxis on superclumps).
+fn optimize_x_axis(x: &[f64], rows: &[usize], n_rows: usize, max_cols: usize) -> Vec<f64> {
+    let n = x.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_unstable_by(|&a, &b| x[a].total_cmp(&x[b]).then(rows[a].cmp(&rows[b])));
+
+    // Clumps: maximal runs in x order that share a row. Equal x values
+    // can never be split, so a tie group spanning rows is a clump of its
+    // own.
+    const MIXED: usize = usize::MAX;
+    let mut groups: Vec<(usize, usize)> = Vec::new();
+    let mut start = 0;
+    for i in 1..=n {
+        if i == n || x[order[i]] != x[order[start]] {
+            let row = rows[order[start]];
+            let pure = order[start..i].iter().all(|&j| rows[j] == row);
+            groups.push((i, if pure { row } else { MIXED }));
+            start = i;
+        }
+    }
+    let mut clump_ends: Vec<usize> = Vec::new();
+    for (g, &(end, label)) in groups.iter().enumerate() {
+        match groups.get(g + 1) {
+            Some(&(_, next)) if label != MIXED && next == label => {}
+            _ => clump_ends.push(end),
+        }
+    }
+    // Superclumps: merge clumps into at most c·max_cols groups of about
+    // equal size.
+    let limit = MIC_CLUMP_FACTOR * max_cols;
+    let ends: Vec<usize> = if clump_ends.len() <= limit {
+        clump_ends
+    } else {
+        let mut merged = Vec::with_capacity(limit);
+        for &end in &clump_ends {
+            let target = (merged.len() + 1) * n / limit;
+            if end >= target || end == n {
+                merged.push(end);
+            }
+        }
+        if merged.last() != Some(&n) {
+            merged.push(n);
+        }
+        merged
+    };
+    let k = ends.len();
+    if k < 2 {
+        return vec![0.0; max_cols.saturating_sub(1)];
+    }
+
+    // Prefix counts per row over superclump boundaries.
+    let mut prefix = vec![vec![0usize; n_rows]; k + 1];
+    let mut start = 0;
+    for (c, &end) in ends.iter().enumerate() {
+        let mut counts = prefix[c].clone();
+        for &i in &order[start..end] {
+            counts[rows[i]] += 1;
+        }
+        prefix[c + 1] = counts;
+        start = end;
+    }
+    let total = n as f64;
+    // Column score Σ_r p(r, col) ln(p(r, col) / p(col)) = -p(col)·H(rows | col).
+    let score = |s: usize, t: usize| -> f64 {
+        let size: usize = prefix[t].iter().zip(&prefix[s]).map(|(a, b)| a - b).sum();
+        let size = size as f64;
+        prefix[t]
+            .iter()
+            .zip(&prefix[s])
+            .map(|(a, b)| (a - b) as f64)
+            .filter(|&c| c > 0.0)
+            .map(|c| c / total * (c / size).ln())
+            .sum()
+    };
+    let h_rows: f64 = -prefix[k]
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / total;
+            p * p.ln()
+        })
+        .sum::<f64>();
+
+    // best[t] = best score for the first t superclumps in `cols` columns.
+    let mut best: Vec<f64> = (0..=k).map(|t| score(0, t)).collect();
+    let mut out = Vec::with_capacity(max_cols.saturating_sub(1));
+    for cols in 2..=max_cols.min(k) {
+        let mut next = vec![f64::NEG_INFINITY; k + 1];
+        for (t, slot) in next.iter_mut().enumerate().skip(cols) {
+            *slot = (cols - 1..t)
+                .map(|s| best[s] + score(s, t))
+                .fold(f64::NEG_INFINITY, f64::max);
+        }
+        best = next;
+        out.push((h_rows + best[k]).max(0.0));
+    }
+    // More columns than superclumps cannot add information.
+    while out.len() + 1 < max_cols {
+        out.push(out.last().copied().unwrap_or(0.0));
+    }
+    out
+}
+
+fn mic(x: &[f64], y: &[f64]) -> CorrelationResult {
+    let n = x.len();
+    if n != y.len() || n < 4 {
+        return CorrelationResult::undefined(n);
+    }
+    let budget = ((n as f64).powf(MIC_ALPHA) as usize).max(4);
+    let mut best: f64 = 0.0;
+    for (a, b) in [(x, y), (y, x)] {
+        for ny in 2..=budget / 2 {
+            let (rows, used) = equipartition(b, ny);
+            if used < 2 {
+                continue;
+            }
+            let max_cols = budget / used;
+            if max_cols < 2 {
+                continue;
+            }
+            for (i, &mi) in optimize_x_axis(a, &rows, used, max_cols).iter().enumerate() {
+                let cols = i + 2;
+                best = best.max(mi / (cols.min(used) as f64).ln());
+            }
+        }
+    }
+    CorrelationResult {
+        coefficient: best.min(1.0),
+        p_value: f64::NAN,
+        n,
+    }
+}
+
+/// Paired k-mer counts of two sequences as float vectors.
+fn kmer_vectors(sequence_a: &str, sequence_b: &str, k: usize) -> (Vec<f64>, Vec<f64>) {
+    if k == 0 {
+        return (Vec::new(), Vec::new());
+    }
+    let (_, _, pairs) = paired_kmer_counts(sequence_a, sequence_b, k);
+    pairs.iter().map(|&(a, b)| (a as f64, b as f64)).unzip()
+}
+
+/// Spearman's rank correlation ρ with ties given average ranks.
+///
+/// # Returns
+/// ρ in [-1, 1] and the two-sided p-value from t = ρ·√((n-2)/(1-ρ²)) with
+/// n - 2 degrees of freedom (n >= 3).
+#[wasm_bindgen]
+pub fn spearman_rho(x: &[f64], y: &[f64]) -> CorrelationResult {
+    spearman(x, y)
+}
+
+/// Kendall's τ-b (tie-adjusted) in O(n log n).
+///
+/// # Returns
+/// τ-b in [-1, 1] and the two-sided normal p-value with the tie-corrected
+/// variance of the concordance count (n >= 3).
+#[wasm_bindgen]
+pub fn kendall_tau_b(x: &[f64], y: &[f64]) -> CorrelationResult {
+    kendall(x, y)
+}
+
+/// Székely's distance correlation in [0, 1]; 0 only under independence,
+/// so it also detects non-monotone dependence. O(n²) time.
+#[wasm_bindgen]
+pub fn distance_correlation(x: &[f64], y: &[f64]) -> CorrelationResult {
+    dcor(x, y)
+}
+
+/// Maximal information coefficient in [0, 1] (ApproxMaxMI, grids up to
+/// n^0.6 cells, c = 15). Near 1 for noiseless functional relationships of
+/// any shape. Roughly O(n^1.8) time.
+#[wasm_bindgen]
+pub fn maximal_information_coefficient(x: &[f64], y: &[f64]) -> CorrelationResult {
+    mic(x, y)
+}
+
+/// Spearman's ρ between the k-mer count vectors of two sequences.
+#[wasm_bindgen]
+pub fn kmer_spearman_rho(sequence_a: &str, sequence_b: &str, k: usize) -> CorrelationResult {
+    let (x, y) = kmer_vectors(sequence_a, sequence_b, k);
+    spearman(&x, &y)
+}
+
+/// Kendall's τ-b between the k-mer count vectors of two sequences.
+#[wasm_bindgen]
+pub fn kmer_kendall_tau_b(sequence_a: &str, sequence_b: &str, k: usize) -> CorrelationResult {
+    let (x, y) = kmer_vectors(sequence_a, sequence_b, k);
+    kendall(&x, &y)
+}
+
+/// Distance correlation between the k-mer count vectors of two sequences.
+#[wasm_bindgen]
+pub fn kmer_distance_correlation(
+    sequence_a: &str,
+    sequence_b: &str,
+    k: usize,
+) -> CorrelationResult {
+    let (x, y) = kmer_vectors(sequence_a, sequence_b, k);
+    dcor(&x, &y)
+}
+
+/// MIC between the k-mer count vectors of two sequences.
+#[wasm_bindgen]
+pub fn kmer_maximal_information_coefficient(
+    sequence_a: &str,
+    sequence_b: &str,
+    k: usize,
+) -> CorrelationResult {
+    let (x, y) = kmer_vectors(sequence_a, sequence_b, k);
+    mic(&x, &y)
+}
+
+#[cfg(test)]
+mod rank_correlation_tests {
+    use super::*;
+    use crate::rng::SplitMix64;
+
+    /// O(n²) τ-b straight from the definition.
+    fn kendall_brute(x: &[f64], y: &[f64]) -> f64 {
+        let (mut s, mut tx, mut ty, mut n0) = (0.0, 0.0, 0.0, 0.0);
+        for i in 0..x.len() {
+            for j in (i + 1)..x.len() {
+                let (dx, dy) = (x[i] - x[j], y[i] - y[j]);
+                n0 += 1.0;
+                s += (dx * dy).signum() * f64::from(u8::from(dx != 0.0 && dy != 0.0));
+                tx += f64::from(u8::from(dx == 0.0));
+                ty += f64::from(u8::from(dy == 0.0));
+            }
+        }
+        s / ((n0 - tx) * (n0 - ty)).sqrt()
+    }
+
+    #[test]
+    fn test_spearman_and_kendall_with_ties() {
+        let mut rng = SplitMix64::new(4);
+        let x: Vec<f64> = (0..300).map(|_| rng.below(12) as f64).collect();
+        let y: Vec<f64> = x.iter().map(|&v| v + rng.below(6) as f64).collect();
+        let tau = kendall_tau_b(&x, &y);
+        assert!((tau.coefficient - kendall_brute(&x, &y)).abs() < 1e-12);
+        assert!(tau.p_value < 1e-10);
+
+        let rho = spearman_rho(&x, &y);
+        let expected = pearson(&average_rank(&x), &average_rank(&y)).unwrap();
+        assert_eq!(rho.coefficient, expected);
+        assert!(rho.p_value < 1e-10);
+
+        // Reversed order: perfect negative association.
+        let z: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let w: Vec<f64> = z.iter().map(|v| -v * v).collect();
+        assert_eq!(kendall_tau_b(&z, &w).coefficient, -1.0);
+        assert_eq!(spearman_rho(&z, &w).coefficient, -1.0);
+        assert_eq!(spearman_rho(&z, &[1.0; 20]).coefficient, 0.0);
+    }
+
+    #[test]
+    fn test_kendall_p_value_under_independence() {
+        let mut rng = SplitMix64::new(9);
+        let rejected = (0..200)
+            .filter(|_| {
+                let x: Vec<f64> = (0..50).map(|_| rng.gaussian()).collect();
+                let y: Vec<f64> = (0..50).map(|_| rng.gaussian()).collect();
+                kendall_tau_b(&x, &y).p_value < 0.05
+            })
+            .count();
+        assert!((2..=20).contains(&rejected), "{} of 200 rejected", rejected);
+    }
+
+    #[test]
+    fn test_distance_correlation_and_mic_see_nonmonotone_dependence() {
+        let mut rng = SplitMix64::new(5);
+        let x: Vec<f64> = (0..300).map(|_| rng.next_f64() * 2.0 - 1.0).collect();
+        let parabola: Vec<f64> = x.iter().map(|v| v * v).collect();
+        let noise: Vec<f64> = (0..300).map(|_| rng.gaussian()).collect();
+
+        // ρ misses the parabola, dCor and MIC do not.
+        assert!(spearman_rho(&x, &parabola).coefficient.abs() < 0.2);
+        assert!(distance_correlation(&x, &parabola).coefficient > 0.4);
+        assert!(distance_correlation(&x, &noise).coefficient < 0.2);
+        assert!((distance_correlation(&x, &x).coefficient - 1.0).abs() < 1e-12);
+
+        let sine: Vec<f64> = x.iter().map(|v| (6.0 * v).sin()).collect();
+        assert!(maximal_information_coefficient(&x, &parabola).coefficient > 0.9);
+        assert!(maximal_information_coefficient(&x, &sine).coefficient > 0.9);
+        assert!(maximal_information_coefficient(&x, &noise).coefficient < 0.35);
+    }
+
+    #[test]
+    fn test_kmer_vectors_match_hoeffding_pairs() {
+        let a = "ATGCGTACGTTAGCCGATAGGCTTACGATCGATCGGATCCATTGACCAGTACGATCGATGCA";
+        let b = "ATGCGTACGTTAGCCGATTGGCTTACGATCGAACGGATCCATTGACCAGTACGTTCGATGCA";
+        let rho = kmer_spearman_rho(a, b, 3);
+        assert_eq!(rho.n, crate::kmer_hoeffdings_d(a, b, 3).n);
+        assert!(rho.coefficient > 0.5);
+        assert!(kmer_kendall_tau_b(a, b, 3).coefficient > 0.4);
+        assert!(kmer_distance_correlation(a, b, 3).coefficient > 0.5);
+        assert_eq!(kmer_maximal_information_coefficient(a, b, 0).n, 0);
+    }
+}
@@ -0,0 +1,716 @@
+//! Changepoint segmentation of windowed tracks and of raw composition.
+//!
+//! - PELT (Killick et al. 2012): exact optimum of the penalized
+//!   change-in-mean cost, O(n) on average thanks to pruning
+//! - Binary segmentation: greedy splits while the cost drop exceeds the
+//!   penalty, optionally capped at a number of changepoints
+//! - HMM segmentation of the bases themselves (Churchill 1989): hidden
+//!   composition classes with Baum–Welch trained emissions, decoded with
+//!   Viterbi, so no window size has to be chosen
+//!
+//! Track costs are squared deviations scaled by a robust noise estimate
+//! (MAD of first differences), so the penalty is on the -2·log likelihood
+//! scale of BIC and the default `2·ln(n)` works across GC skew, entropy,
+//! complexity and KL tracks. Overlapping windows make neighbouring values
+//! correlated, which this noise model ignores: segment tracks with
+//! `step_size >= window_size`, or raise the penalty.
+
+use wasm_bindgen::prelude::*;
+
+use crate::stats::median_and_mad;
+use crate::{encode_base, SEQ_BASE_C, SEQ_BASE_G, SEQ_BASE_T};
+
+/// Largest number of hidden states for HMM segmentation.
+pub(crate) const HMM_MAX_STATES: usize = 8;
+
+/// Cap on bases × states for the stored forward variables (256 MB).
+const HMM_MAX_CELLS: usize = 32_000_000;
+
+/// Baum–Welch stops once the log likelihood improves by less than this.
+const HMM_TOLERANCE: f64 = 1e-4;
+
+/// Segments of a track or sequence with per-segment statistics.
+///
+/// Coordinates are track indices for `segment_track_*` (window i starts at
+/// base i·step_size) and bases for `segment_composition_hmm`; `ends` are
+/// exclusive.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct Segmentation {
+    starts: Vec<u32>,
+    ends: Vec<u32>,
+    means: Vec<f64>,
+    std_devs: Vec<f64>,
+    states: Vec<u32>,
+    composition: Vec<f64>,
+    log_likelihood: f64,
+    error: Option<String>,
+}
+
+impl Segmentation {
+    fn failed(error: String) -> Segmentation {
+        Segmentation {
+            starts: Vec::new(),
+            ends: Vec::new(),
+            means: Vec::new(),
+            std_devs: Vec::new(),
+            states: Vec::new(),
+            composition: Vec::new(),
+            log_likelihood: f64::NAN,
+            error: Some(error),
+        }
+    }
+
+    /// Track segmentation from sorted, exclusive segment ends.
+    fn from_track(values: &[f64], ends: &[usize]) -> Segmentation {
+        let mut result = Segmentation::failed(String::new());
+        result.error = None;
+        let mut start = 0;
+        for &end in ends {
+            let segment = &values[start..end];
+            let len = segment.len() as f64;
+            let mean = segment.iter().sum::<f64>() / len;
+            let var = segment.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / len;
+            result.starts.push(start as u32);
+            result.ends.push(end as u32);
+            result.means.push(mean);
+            result.std_devs.push(var.sqrt());
+            start = end;
+        }
+        result
+    }
+}
+
+#[wasm_bindgen]
+impl Segmentation {
+    /// Segment start coordinates as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn starts(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.starts.len() as u32);
+        arr.copy_from(&self.starts);
+        arr
+    }
+
+    /// Segment end coordinates (exclusive) as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn ends(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.ends.len() as u32);
+        arr.copy_from(&self.ends);
+        arr
+    }
+
+    /// Changepoints (the starts of all segments but the first).
+    #[wasm_bindgen(getter)]
+    pub fn changepoints(&self) -> js_sys::Uint32Array {
+        let cps = self.starts.get(1..).unwrap_or(&[]);
+        let arr = js_sys::Uint32Array::new_with_length(cps.len() as u32);
+        arr.copy_from(cps);
+        arr
+    }
+
+    /// Per-segment mean as Float64Array (GC fraction for HMM segments).
+    #[wasm_bindgen(getter)]
+    pub fn means(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.means.len() as u32);
+        arr.copy_from(&self.means);
+        arr
+    }
+
+    /// Per-segment standard deviation as Float64Array (of the per-base GC
+    /// indicator for HMM segments).
+    #[wasm_bindgen(getter)]
+    pub fn std_devs(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.std_devs.len() as u32);
+        arr.copy_from(&self.std_devs);
+        arr
+    }
+
+    /// HMM state of each segment, ordered by GC content (0 = most AT-rich).
+    /// Empty for track segmentation.
+    #[wasm_bindgen(getter)]
+    pub fn states(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.states.len() as u32);
+        arr.copy_from(&self.states);
+        arr
+    }
+
+    /// A/C/G/T fractions per HMM segment, row-major (segments × 4). Empty
+    /// for track segmentation.
+    #[wasm_bindgen(getter)]
+    pub fn composition(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.composition.len() as u32);
+        arr.copy_from(&self.composition);
+        arr
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn segment_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Log likelihood (nats) of the trained HMM; NaN for tracks.
+    #[wasm_bindgen(getter)]
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
+    }
+
+    /// Error message for invalid input.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Change-in-mean cost of track slices in O(1) via prefix sums.
+struct MeanCost {
+    sum: Vec<f64>,
+    sum_sq: Vec<f64>,
+    inv_var: f64,
+}
+
+impl MeanCost {
+    fn new(values: &[f64]) -> MeanCost {
+        // Centre first so the prefix sums do not cancel catastrophically.
+        let center = values.iter().sum::<f64>() / values.len() as f64;
+        let mut sum = Vec::with_capacity(values.len() + 1);
+        let mut sum_sq = Vec::with_capacity(values.len() + 1);
+        sum.push(0.0);
+        sum_sq.push(0.0);
+        for &v in values {
+            let c = v - center;
+            sum.push(sum.last().unwrap() + c);
+            sum_sq.push(sum_sq.last().unwrap() + c * c);
+        }
+        let diffs: Vec<f64> = values.windows(2).map(|w| w[1] - w[0]).collect();
+        let mut sigma = median_and_mad(&diffs).1 / std::f64::consts::SQRT_2;
+        if sigma.is_nan() || sigma <= 0.0 {
+            // Piecewise-constant tracks: fall back to the overall spread.
+            sigma = (sum_sq[values.len()] / values.len() as f64).sqrt();
+        }
+        if sigma.is_nan() || sigma <= 0.0 {
+            sigma = 1.0;
+        }
+        MeanCost {
+            sum,
+            sum_sq,
+            inv_var: 1.0 / (sigma * sigma),
+        }
+    }
+
+    /// Scaled residual sum of squares of values[start..end].
+    fn cost(&self, start: usize, end: usize) -> f64 {
+        let s = self.sum[end] - self.sum[start];
+        let ss = self.sum_sq[end] - self.sum_sq[start];
+        (ss - s * s / (end - start) as f64).max(0.0) * self.inv_var
+    }
+}
+
+/// Shared validation; returns the penalty to use.
+fn check_track(values: &[f64], penalty: f64, min_size: usize) -> Result<f64, String> {
+    if values.is_empty() {
+        return Err("track is empty".to_string());
+    }
+    if let Some(i) = values.iter().position(|v| !v.is_finite()) {
+        return Err(format!("track value {} is not finite", i));
+    }
+    if min_size == 0 {
+        return Err("min_size must be at least 1".to_string());
+    }
+    if penalty.is_finite() && penalty > 0.0 {
+        Ok(penalty)
+    } else {
+        Ok(2.0 * (values.len().max(2) as f64).ln())
+    }
+}
+
+/// Segment a track by PELT with a change-in-mean cost.
+///
+/// # Arguments
+/// * `values` - Track values, e.g. from `compute_gc_skew` (no NaN)
+/// * `penalty` - Cost per changepoint; 0 or NaN for the default 2·ln(n)
+/// * `min_size` - Minimum segment length in track values (>= 1)
+///
+/// # Returns
+/// `Segmentation` with the optimal segments and their mean and standard
+/// deviation.
+#[wasm_bindgen]
+pub fn segment_track_pelt(values: &[f64], penalty: f64, min_size: usize) -> Segmentation {
+    let beta = match check_track(values, penalty, min_size) {
+        Ok(beta) => beta,
+        Err(error) => return Segmentation::failed(error),
+    };
+    let n = values.len();
+    if n < 2 * min_size {
+        return Segmentation::from_track(values, &[n]);
+    }
+    let costs = MeanCost::new(values);
+    let mut best = vec![f64::INFINITY; n + 1];
+    let mut previous = vec![0usize; n + 1];
+    best[0] = -beta;
+    let mut candidates: Vec<usize> = Vec::new();
+    let mut scored: Vec<f64> = Vec::new();
+    for t in min_size..=n {
+        let newest = t - min_size;
+        if best[newest].is_finite() {
+            candidates.push(newest);
+        }
+        scored.clear();
+        let (mut value, mut arg) = (f64::INFINITY, 0);
+        for &tau in &candidates {
+            let v = best[tau] + costs.cost(tau, t);
+            scored.push(v);
+            if v + beta < value {
+                value = v + beta;
+                arg = tau;
+            }
+        }
+        best[t] = value;
+        previous[t] = arg;
+        // Prune split points that can never be optimal again.
+        let mut kept = 0;
+        for i in 0..candidates.len() {
+            if scored[i] <= value {
+                candidates[kept] = candidates[i];
+                kept += 1;
+            }
+        }
+        candidates.truncate(kept);
+    }
+
+    let mut ends = Vec::new();
+    let mut t = n;
+    while t > 0 {
+        ends.push(t);
+        t = previous[t];
+    }
+    ends.reverse();
+    Segmentation::from_track(values, &ends)
+}
+
+/// Cost drop and position of the best split of a segment.
+type Split = Option<(f64, usize)>;
+
+/// Best split of values[start..end].
+fn best_split(costs: &MeanCost, start: usize, end: usize, min_size: usize) -> Split {
+    if end - start < 2 * min_size {
+        return None;
+    }
+    let whole = costs.cost(start, end);
+    ((start + min_size)..=(end - min_size))
+        .map(|split| {
+            let gain = whole - costs.cost(start, split) - costs.cost(split, end);
+            (gain, split)
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// Segment a track by binary segmentation with a change-in-mean cost.
+///
+/// Repeatedly splits the segment whose best split lowers the cost most,
+/// while that drop exceeds the penalty.
+///
+/// # Arguments
+/// * `values` - Track values (no NaN)
+/// * `penalty` - Minimum cost drop per split; 0 or NaN for 2·ln(n)
+/// * `min_size` - Minimum segment length in track values (>= 1)
+/// * `max_changepoints` - Stop after this many splits (0 = no limit)
+#[wasm_bindgen]
+pub fn segment_track_binary(
+    values: &[f64],
+    penalty: f64,
+    min_size: usize,
+    max_changepoints: usize,
+) -> Segmentation {
+    let beta = match check_track(values, penalty, min_size) {
+        Ok(beta) => beta,
+        Err(error) => return Segmentation::failed(error),
+    };
+    let n = values.len();
+    let costs = MeanCost::new(values);
+    let limit = if max_changepoints == 0 {
+        n
+    } else {
+        max_changepoints
+    };
+    // Open segments with their best split, if any.
+    let mut open: Vec<(usize, usize, Split)> = vec![(0, n, best_split(&costs, 0, n, min_size))];
+    let mut ends = Vec::new();
+    let mut splits = 0;
+    while splits < limit {
+        let pick = open
+            .iter()
+            .enumerate()
+            .filter_map(|(i, seg)| seg.2.map(|(gain, _)| (gain, i)))
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        let Some((gain, i)) = pick else { break };
+        if gain <= beta {
+            break;
+        }
+        let (start, end, split) = open.swap_remove(i);
+        let split = split.unwrap().1;
+        open.push((start, split, best_split(&costs, start, split, min_size)));
+        open.push((split, end, best_split(&costs, split, end, min_size)));
+        splits += 1;
+    }
+    ends.extend(open.iter().map(|seg| seg.1));
+    ends.sort_unstable();
+    Segmentation::from_track(values, &ends)
+}
+
+/// Fixed-switch transition model shared by the HMM passes.
+struct Switching {
+    stay: f64,
+    jump: f64,
+}
+
+impl Switching {
+    /// Mass flowing into state s from a distribution whose total is `total`.
+    fn into(&self, from: f64, total: f64) -> f64 {
+        self.stay * from + self.jump * (total - from)
+    }
+}
+
+/// Initial emissions from the composition of GC-ranked chunks.
+fn initial_emissions(codes: &[u8], states: usize) -> Vec<[f64; 4]> {
+    let chunk = (codes.len() / 200).max(50);
+    let mut chunks: Vec<(f64, [f64; 4])> = codes
+        .chunks(chunk)
+        .map(|c| {
+            let mut counts = [0.0; 4];
+            for &b in c {
+                if b <= SEQ_BASE_T {
+                    counts[b as usize] += 1.0;
+                }
+            }
+            let total: f64 = counts.iter().sum();
+            let gc = (counts[SEQ_BASE_C as usize] + counts[SEQ_BASE_G as usize]) / total.max(1.0);
+            (gc, counts)
+        })
+        .collect();
+    chunks.sort_by(|a, b| a.0.total_cmp(&b.0));
+    (0..states)
+        .map(|s| {
+            let lo = s * chunks.len() / states;
+            let hi = ((s + 1) * chunks.len() / states)
+                .max(lo + 1)
+                .min(chunks.len());
+            let mut counts = [1.0; 4];
+            for (_, c) in &chunks[lo.min(chunks.len() - 1)..hi] {
+                for b in 0..4 {
+                    counts[b] += c[b];
+                }
+            }
+            normalized(counts)
+        })
+        .collect()
+}
+
+fn normalized(counts: [f64; 4]) -> [f64; 4] {
+    let total: f64 = counts.iter().sum();
+    counts.map(|c| c / total)
+}
+
+fn emission(e: &[f64; 4], code: u8) -> f64 {
+    if code <= SEQ_BASE_T {
+        e[code as usize]
+    } else {
+        1.0
+    }
+}
+
+/// One Baum–Welch pass: returns the log likelihood and re-estimated
+/// emissions. `alpha` is scratch space of length codes.len() × states.
+fn baum_welch_step(
+    codes: &[u8],
+    emissions: &[[f64; 4]],
+    switching: &Switching,
+    alpha: &mut [f64],
+    scale: &mut [f64],
+) -> (f64, Vec<[f64; 4]>) {
+    let k = emissions.len();
+    let n = codes.len();
+    let mut log_likelihood = 0.0;
+    for t in 0..n {
+        let mut total = 0.0;
+        for s in 0..k {
+            let prior = if t == 0 {
+                1.0 / k as f64
+            } else {
+                switching.into(alpha[(t - 1) * k + s], 1.0)
+            };
+            let v = prior * emission(&emissions[s], codes[t]);
+            alpha[t * k + s] = v;
+            total += v;
+        }
+        for v in &mut alpha[t * k..(t + 1) * k] {
+            *v /= total;
+        }
+        scale[t] = total;
+        log_likelihood += total.ln();
+    }
+
+    let mut counts = vec![[1e-3; 4]; k];
+    let mut beta = vec![1.0; k];
+    let mut weighted = vec![0.0; k];
+    for t in (0..n).rev() {
+        if codes[t] <= SEQ_BASE_T {
+            for s in 0..k {
+                counts[s][codes[t] as usize] += alpha[t * k + s] * beta[s];
+            }
+        }
+        if t == 0 {
+            break;
+        }
+        let mut total = 0.0;
+        for s in 0..k {
+            weighted[s] = emission(&emissions[s], codes[t]) * beta[s];
+            total += weighted[s];
+        }
+        for s in 0..k {
+            beta[s] = switching.into(weighted[s], total) / scale[t];
+        }
+    }
+    (log_likelihood, counts.into_iter().map(normalized).collect())
+}
+
+/// Viterbi path in log space.
+fn viterbi(codes: &[u8], emissions: &[[f64; 4]], switching: &Switching) -> Vec<u8> {
+    let k = emissions.len();
+    let n = codes.len();
+    let (ln_stay, ln_jump) = (switching.stay.ln(), switching.jump.ln());
+    let ln_emit: Vec<[f64; 4]> = emissions.iter().map(|e| e.map(f64::ln)).collect();
+    let emit = |s: usize, code: u8| -> f64 {
+        if code <= SEQ_BASE_T {
+            ln_emit[s][code as usize]
+        } else {
+            0.0
+        }
+    };
+    let mut back = vec![0u8; n * k];
+    let mut delta: Vec<f64> = (0..k).map(|s| emit(s, codes[0])).collect();
+    let mut next = vec![0.0; k];
+    for t in 1..n {
+        // Best and second-best predecessor cover every "jump from another
+        // state" case in O(k).
+        let mut first = (f64::NEG_INFINITY, 0);
+        let mut second = (f64::NEG_INFINITY, 0);
+        for (s, &d) in delta.iter().enumerate() {
+            if d > first.0 {
+                second = first;
+                first = (d, s);
+            } else if d > second.0 {
+                second = (d, s);
+            }
+        }
+        for s in 0..k {
+            let other = if first.1 == s { second } else { first };
+            let (score, from) = if delta[s] + ln_stay >= other.0 + ln_jump {
+                (delta[s] + ln_stay, s)
+            } else {
+                (other.0 + ln_jump, other.1)
+            };
+            next[s] = score + emit(s, codes[t]);
+            back[t * k + s] = from as u8;
+        }
+        std::mem::swap(&mut delta, &mut next);
+    }
+    let mut path = vec![0u8; n];
+    let mut state = (0..k)
+        .max_by(|&a, &b| delta[a].total_cmp(&delta[b]))
+        .unwrap();
+    for t in (0..n).rev() {
+        path[t] = state as u8;
+        state = back[t * k + state] as usize;
+    }
+    path
+}
+
+/// Segment a nucleotide sequence into composition classes with an HMM.
+///
+/// Each hidden state emits A/C/G/T with its own frequencies (trained by
+/// Baum–Welch from GC-ranked starting values); the chain switches state
+/// with a fixed probability per base, which sets the segment granularity
+/// the way the penalty does for tracks. Segments are the runs of the
+/// Viterbi path. Non-ACGT bases carry no evidence and stay in the
+/// surrounding segment.
+///
+/// # Arguments
+/// * `seq` - Nucleotide sequence
+/// * `num_states` - Composition classes (2..=8)
+/// * `switch_probability` - Per-base probability of changing state, in
+///   (0, 1); around 1e-4 to 1e-6 for genomic islands
+/// * `max_iterations` - Baum–Welch iteration cap (0 keeps the initial
+///   emissions)
+///
+/// # Returns
+/// `Segmentation` in base coordinates with states, GC means and A/C/G/T
+/// composition per segment. Memory is about 8 × length × states bytes.
+#[wasm_bindgen]
+pub fn segment_composition_hmm(
+    seq: &[u8],
+    num_states: usize,
+    switch_probability: f64,
+    max_iterations: usize,
+) -> Segmentation {
+    if !(2..=HMM_MAX_STATES).contains(&num_states) {
+        return Segmentation::failed(format!(
+            "num_states must be 2..={} (got {})",
+            HMM_MAX_STATES, num_states
+        ));
+    }
+    if !(switch_probability > 0.0 && switch_probability < 1.0) {
+        return Segmentation::failed(format!(
+            "switch_probability must be in (0, 1) (got {})",
+            switch_probability
+        ));
+    }
+    let codes: Vec<u8> = seq.iter().map(|&b| encode_base(b)).collect();
+    if !codes.iter().any(|&c| c <= SEQ_BASE_T) {
+        return Segmentation::failed("sequence has no A/C/G/T bases".to_string());
+    }
+    if codes.len() * num_states > HMM_MAX_CELLS {
+        return Segmentation::failed(format!(
+            "sequence too long for {} states (at most {} bases)",
+            num_states,
+            HMM_MAX_CELLS / num_states
+        ));
+    }
+
+    let switching = Switching {
+        stay: 1.0 - switch_probability,
+        jump: switch_probability / (num_states - 1) as f64,
+    };
+    let mut emissions = initial_emissions(&codes, num_states);
+    let mut alpha = vec![0.0; codes.len() * num_states];
+    let mut scale = vec![0.0; codes.len()];
+    let mut log_likelihood = f64::NAN;
+    for _ in 0..max_iterations {
+        let (ll, updated) = baum_welch_step(&codes, &emissions, &switching, &mut alpha, &mut scale);
+        let converged = (ll - log_likelihood).abs() < HMM_TOLERANCE;
+        log_likelihood = ll;
+        emissions = updated;
+        if converged {
+            break;
+        }
+    }
+    if max_iterations == 0 || log_likelihood.is_nan() {
+        log_likelihood = baum_welch_step(&codes, &emissions, &switching, &mut alpha, &mut scale).0;
+    }
+    drop(alpha);
+
+    // Relabel states by GC content so labels are comparable across runs.
+    let gc = |e: &[f64; 4]| e[SEQ_BASE_C as usize] + e[SEQ_BASE_G as usize];
+    let mut order: Vec<usize> = (0..num_states).collect();
+    order.sort_by(|&a, &b| gc(&emissions[a]).total_cmp(&gc(&emissions[b])));
+    let mut rank = vec![0u32; num_states];
+    for (r, &s) in order.iter().enumerate() {
+        rank[s] = r as u32;
+    }
+
+    let path = viterbi(&codes, &emissions, &switching);
+    let mut result = Segmentation::failed(String::new());
+    result.error = None;
+    result.log_likelihood = log_likelihood;
+    let mut start = 0;
+    for end in 1..=path.len() {
+        if end < path.len() && path[end] == path[start] {
+            continue;
+        }
+        let mut counts = [0.0; 4];
+        for &c in &codes[start..end] {
+            if c <= SEQ_BASE_T {
+                counts[c as usize] += 1.0;
+            }
+        }
+        let total: f64 = counts.iter().sum();
+        let fractions = if total > 0.0 {
+            counts.map(|c| c / total)
+        } else {
+            [f64::NAN; 4]
+        };
+        let p = fractions[SEQ_BASE_C as usize] + fractions[SEQ_BASE_G as usize];
+        result.starts.push(start as u32);
+        result.ends.push(end as u32);
+        result.means.push(p);
+        result.std_devs.push((p * (1.0 - p)).sqrt());
+        result.states.push(rank[path[start] as usize]);
+        result.composition.extend_from_slice(&fractions);
+        start = end;
+    }
+    result
+}
+
+#[cfg(test)]
+mod segmentation_tests {
+    use super::*;
+    use crate::rng::SplitMix64;
+
+    fn steps(rng: &mut SplitMix64) -> Vec<f64> {
+        let mut values = Vec::new();
+        for (len, mean) in [(120, 0.0), (80, 1.5), (100, -0.5)] {
+            values.extend((0..len).map(|_| mean + 0.3 * rng.gaussian()));
+        }
+        values
+    }
+
+    #[test]
+    fn test_pelt_recovers_mean_shifts() {
+        let mut rng = SplitMix64::new(5);
+        let values = steps(&mut rng);
+        let seg = segment_track_pelt(&values, 0.0, 5);
+        assert!(seg.error.is_none());
+        assert_eq!(seg.starts.len(), 3);
+        assert!((seg.starts[1] as i64 - 120).abs() <= 2);
+        assert!((seg.starts[2] as i64 - 200).abs() <= 2);
+        assert!((seg.means[1] - 1.5).abs() < 0.1);
+        assert_eq!(*seg.ends.last().unwrap(), 300);
+
+        let flat: Vec<f64> = (0..500).map(|_| rng.gaussian()).collect();
+        assert_eq!(segment_track_pelt(&flat, 0.0, 1).starts, vec![0]);
+        assert!(segment_track_pelt(&[1.0, f64::NAN], 0.0, 1).error.is_some());
+    }
+
+    #[test]
+    fn test_binary_segmentation_matches_pelt_and_caps_splits() {
+        let mut rng = SplitMix64::new(6);
+        let values = steps(&mut rng);
+        let binary = segment_track_binary(&values, 0.0, 5, 0);
+        let pelt = segment_track_pelt(&values, 0.0, 5);
+        assert_eq!(binary.starts, pelt.starts);
+        // With a cap of one split, the single changepoint is a true boundary.
+        let capped = segment_track_binary(&values, 0.0, 5, 1);
+        assert_eq!(capped.starts.len(), 2);
+        assert!([120i64, 200]
+            .iter()
+            .any(|&b| (capped.starts[1] as i64 - b).abs() <= 2));
+    }
+
+    #[test]
+    fn test_hmm_finds_gc_island() {
+        let mut rng = SplitMix64::new(7);
+        let mut seq = Vec::new();
+        for (len, gc) in [(20_000, 0.35), (8_000, 0.65), (20_000, 0.35)] {
+            seq.extend((0..len).map(|_| {
+                let strong = rng.next_f64() < gc;
+                let pick = rng.below(2);
+                if strong {
+                    b"GC"[pick]
+                } else {
+                    b"AT"[pick]
+                }
+            }));
+        }
+        let seg = segment_composition_hmm(&seq, 2, 1e-5, 50);
+        assert!(seg.error.is_none());
+        assert_eq!(seg.states, vec![0, 1, 0]);
+        assert!((seg.starts[1] as i64 - 20_000).abs() < 200);
+        assert!((seg.ends[1] as i64 - 28_000).abs() < 200);
+        assert!((seg.means[1] - 0.65).abs() < 0.02);
+        assert!(seg.log_likelihood.is_finite());
+        assert!(segment_composition_hmm(b"NNNN", 2, 1e-5, 10)
+            .error
+            .is_some());
+    }
+}
@@ -0,0 +1,531 @@
+//! Serializable MinHash sketches and a multi-sketch database.
+//!
+//! Sketches can be precomputed for the phage library, shipped as bytes and
+//! queried in the browser without touching the original sequences.
+//!
+//! # Binary layout (little-endian)
+//!
+//! Sketch (`PXMH`, version 1):
+//! ```text
+//! magic[4] = "PXMH" | version u16 | flags u16 (bit 0 = canonical)
+//! k u32 | num_hashes u32 | hash_seed u32
+//! genome_length u64 | total_kmers u64
+//! name_len u32 | name (UTF-8) | signature u32 × num_hashes
+//! ```
+//!
+//! Database (`PXSD`, version 1):
+//! ```text
+//! magic[4] = "PXSD" | version u16 | reserved u16 | count u32
+//! (sketch_len u32 | sketch bytes) × count
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    json_string_array, minhash_jaccard_from_signatures, minhash_signature_seeded, MinHashSignature,
+};
+
+const SKETCH_MAGIC: &[u8; 4] = b"PXMH";
+const SKETCH_DB_MAGIC: &[u8; 4] = b"PXSD";
+const SKETCH_VERSION: u16 = 1;
+const SKETCH_DB_VERSION: u16 = 1;
+const SKETCH_FLAG_CANONICAL: u16 = 1;
+
+/// Upper bounds checked when decoding untrusted bytes.
+const SKETCH_MAX_HASHES: usize = 1 << 20;
+const SKETCH_MAX_NAME_LEN: usize = 1 << 16;
+
+/// Cursor over a byte slice; every read is bounds-checked.
+pub(crate) struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> ByteReader<'a> {
+        ByteReader { bytes, pos: 0 }
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(n)?;
+        let slice = self.bytes.get(self.pos..end)?;
+        self.pos = end;
+        Some(slice)
+    }
+
+    pub(crate) fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        self.take(8).map(|b| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(b);
+            u64::from_le_bytes(buf)
+        })
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+}
+
+/// A named MinHash sketch with the parameters needed to compare it.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct MinHashSketch {
+    name: String,
+    k: u32,
+    canonical: bool,
+    hash_seed: u32,
+    genome_length: u64,
+    total_kmers: u64,
+    signature: Vec<u32>,
+}
+
+impl MinHashSketch {
+    fn write_into(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(SKETCH_MAGIC);
+        out.extend_from_slice(&SKETCH_VERSION.to_le_bytes());
+        let flags = if self.canonical {
+            SKETCH_FLAG_CANONICAL
+        } else {
+            0
+        };
+        out.extend_from_slice(&flags.to_le_bytes());
+        out.extend_from_slice(&self.k.to_le_bytes());
+        out.extend_from_slice(&(self.signature.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.hash_seed.to_le_bytes());
+        out.extend_from_slice(&self.genome_length.to_le_bytes());
+        out.extend_from_slice(&self.total_kmers.to_le_bytes());
+        out.extend_from_slice(&(self.name.len() as u32).to_le_bytes());
+        out.extend_from_slice(self.name.as_bytes());
+        for &h in &self.signature {
+            out.extend_from_slice(&h.to_le_bytes());
+        }
+    }
+
+    fn read_from(reader: &mut ByteReader) -> Option<MinHashSketch> {
+        if reader.take(4)? != SKETCH_MAGIC || reader.u16()? != SKETCH_VERSION {
+            return None;
+        }
+        let flags = reader.u16()?;
+        let k = reader.u32()?;
+        let num_hashes = reader.u32()? as usize;
+        let hash_seed = reader.u32()?;
+        let genome_length = reader.u64()?;
+        let total_kmers = reader.u64()?;
+        let name_len = reader.u32()? as usize;
+        if num_hashes > SKETCH_MAX_HASHES || name_len > SKETCH_MAX_NAME_LEN {
+            return None;
+        }
+        let name = String::from_utf8(reader.take(name_len)?.to_vec()).ok()?;
+        let signature = reader
+            .take(num_hashes * 4)?
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        Some(MinHashSketch {
+            name,
+            k,
+            canonical: flags & SKETCH_FLAG_CANONICAL != 0,
+            hash_seed,
+            genome_length,
+            total_kmers,
+            signature,
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl MinHashSketch {
+    /// Wrap an existing `MinHashSignature`, keeping its k, seed and strand mode.
+    pub fn from_signature(
+        signature: &MinHashSignature,
+        name: &str,
+        genome_length: u64,
+    ) -> MinHashSketch {
+        MinHashSketch {
+            name: name.to_string(),
+            k: signature.k as u32,
+            canonical: signature.canonical,
+            hash_seed: signature.hash_seed,
+            genome_length,
+            total_kmers: signature.total_kmers,
+            signature: signature.signature.clone(),
+        }
+    }
+
+    /// Decode a sketch written by `to_bytes`.
+    /// Returns undefined for bad magic, unknown version or truncated data.
+    pub fn from_bytes(bytes: &[u8]) -> Option<MinHashSketch> {
+        let mut reader = ByteReader::new(bytes);
+        let sketch = MinHashSketch::read_from(&mut reader)?;
+        reader.is_done().then_some(sketch)
+    }
+
+    /// Encode the sketch in the versioned binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(40 + self.name.len() + 4 * self.signature.len());
+        self.write_into(&mut out);
+        out
+    }
+
+    /// True if both sketches use the same k, hash count, seed and strand mode.
+    pub fn is_compatible(&self, other: &MinHashSketch) -> bool {
+        self.k == other.k
+            && self.canonical == other.canonical
+            && self.hash_seed == other.hash_seed
+            && self.signature.len() == other.signature.len()
+    }
+
+    /// Estimated Jaccard similarity; 0.0 if the sketches are incompatible.
+    pub fn jaccard(&self, other: &MinHashSketch) -> f64 {
+        if !self.is_compatible(other) {
+            return 0.0;
+        }
+        minhash_jaccard_from_signatures(&self.signature, &other.signature)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn num_hashes(&self) -> usize {
+        self.signature.len()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn canonical(&self) -> bool {
+        self.canonical
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn hash_seed(&self) -> u32 {
+        self.hash_seed
+    }
+
+    /// Length of the sketched sequence in bases.
+    #[wasm_bindgen(getter)]
+    pub fn genome_length(&self) -> u64 {
+        self.genome_length
+    }
+
+    /// Number of valid k-mers hashed.
+    #[wasm_bindgen(getter)]
+    pub fn total_kmers(&self) -> u64 {
+        self.total_kmers
+    }
+
+    /// Signature values as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn signature(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.signature.len() as u32);
+        arr.copy_from(&self.signature);
+        arr
+    }
+}
+
+/// Compute a named MinHash sketch.
+///
+/// # Arguments
+/// * `seq` - Sequence bytes (ASCII). Case-insensitive, U treated as T.
+/// * `k` - K-mer size (capped at 32)
+/// * `num_hashes` - Signature length
+/// * `canonical` - Hash min(forward, reverse complement) k-mers
+/// * `hash_seed` - Hash family seed (0 matches `minhash_signature`)
+/// * `name` - Genome name stored with the sketch
+#[wasm_bindgen]
+pub fn minhash_sketch(
+    seq: &[u8],
+    k: usize,
+    num_hashes: usize,
+    canonical: bool,
+    hash_seed: u32,
+    name: &str,
+) -> MinHashSketch {
+    let sig = minhash_signature_seeded(seq, k, num_hashes, canonical, hash_seed);
+    MinHashSketch {
+        name: name.to_string(),
+        k: sig.k as u32,
+        canonical,
+        hash_seed,
+        genome_length: seq.len() as u64,
+        total_kmers: sig.total_kmers,
+        signature: sig.signature,
+    }
+}
+
+/// Ranked hits from `SketchDatabase::query`.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct SketchQueryResult {
+    indices: Vec<u32>,
+    jaccard: Vec<f64>,
+    mash_distances: Vec<f64>,
+    names: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl SketchQueryResult {
+    /// Database indices of the hits, best first, as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> js_sys::Uint32Array {
+        let arr = js_sys::Uint32Array::new_with_length(self.indices.len() as u32);
+        arr.copy_from(&self.indices);
+        arr
+    }
+
+    /// Estimated Jaccard similarity per hit as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn jaccard(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.jaccard.len() as u32);
+        arr.copy_from(&self.jaccard);
+        arr
+    }
+
+    /// Mash distance per hit (1.0 when Jaccard is 0) as Float64Array.
+    #[wasm_bindgen(getter)]
+    pub fn mash_distances(&self) -> js_sys::Float64Array {
+        let arr = js_sys::Float64Array::new_with_length(self.mash_distances.len() as u32);
+        arr.copy_from(&self.mash_distances);
+        arr
+    }
+
+    /// Hit names as a JSON array.
+    #[wasm_bindgen(getter)]
+    pub fn names_json(&self) -> String {
+        json_string_array(&self.names)
+    }
+
+    /// Number of hits.
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+/// Mash distance: D = -1/k · ln(2J / (1 + J)), clamped to [0, 1].
+pub(crate) fn mash_distance(jaccard: f64, k: u32) -> f64 {
+    if jaccard <= 0.0 || k == 0 {
+        return 1.0;
+    }
+    (-(2.0 * jaccard / (1.0 + jaccard)).ln() / k as f64).clamp(0.0, 1.0)
+}
+
+/// A collection of compatible sketches that can be shipped as one blob.
+///
+/// All sketches must share k, hash count, seed and strand mode with the
+/// first sketch added.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct SketchDatabase {
+    sketches: Vec<MinHashSketch>,
+}
+
+impl Default for SketchDatabase {
+    fn default() -> Self {
+        SketchDatabase::new()
+    }
+}
+
+#[wasm_bindgen]
+impl SketchDatabase {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SketchDatabase {
+        SketchDatabase {
+            sketches: Vec::new(),
+        }
+    }
+
+    /// Add a copy of `sketch`. Returns false (and adds nothing) if it is
+    /// incompatible with the sketches already stored.
+    pub fn add(&mut self, sketch: &MinHashSketch) -> bool {
+        if let Some(first) = self.sketches.first() {
+            if !first.is_compatible(sketch) {
+                return false;
+            }
+        }
+        self.sketches.push(sketch.clone());
+        true
+    }
+
+    /// Number of sketches.
+    #[wasm_bindgen(getter)]
+    pub fn len(&self) -> usize {
+        self.sketches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sketches.is_empty()
+    }
+
+    /// Copy of the sketch at `index`, or undefined if out of range.
+    pub fn get(&self, index: usize) -> Option<MinHashSketch> {
+        self.sketches.get(index).cloned()
+    }
+
+    /// Encode all sketches in the versioned container format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(SKETCH_DB_MAGIC);
+        out.extend_from_slice(&SKETCH_DB_VERSION.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&(self.sketches.len() as u32).to_le_bytes());
+        let mut buf = Vec::new();
+        for sketch in &self.sketches {
+            buf.clear();
+            sketch.write_into(&mut buf);
+            out.extend_from_slice(&(buf.len() as u32).to_le_bytes());
+            out.extend_from_slice(&buf);
+        }
+        out
+    }
+
+    /// Decode a container written by `to_bytes`.
+    /// Returns undefined for malformed data or incompatible sketches.
+    pub fn from_bytes(bytes: &[u8]) -> Option<SketchDatabase> {
+        let mut reader = ByteReader::new(bytes);
+        if reader.take(4)? != SKETCH_DB_MAGIC || reader.u16()? != SKETCH_DB_VERSION {
+            return None;
+        }
+        reader.u16()?;
+        let count = reader.u32()? as usize;
+        let mut db = SketchDatabase::new();
+        for _ in 0..count {
+            let len = reader.u32()? as usize;
+            let sketch = MinHashSketch::from_bytes(reader.take(len)?)?;
+            if !db.add(&sketch) {
+                return None;
+            }
+        }
+        reader.is_done().then_some(db)
+    }
+
+    /// Return the `top_n` stored sketches most similar to `query`.
+    ///
+    /// Ranked by Jaccard (descending), ties by database index. Sketches with
+    /// Jaccard 0 are omitted; an incompatible query returns no hits.
+    pub fn query(&self, query: &MinHashSketch, top_n: usize) -> SketchQueryResult {
+        let mut hits: Vec<(usize, f64)> = self
+            .sketches
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i, query.jaccard(s)))
+            .filter(|&(_, j)| j > 0.0)
+            .collect();
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        hits.truncate(top_n);
+
+        SketchQueryResult {
+            indices: hits.iter().map(|&(i, _)| i as u32).collect(),
+            jaccard: hits.iter().map(|&(_, j)| j).collect(),
+            mash_distances: hits
+                .iter()
+                .map(|&(_, j)| mash_distance(j, query.k))
+                .collect(),
+            names: hits
+                .iter()
+                .map(|&(i, _)| self.sketches[i].name.clone())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod sketch_tests {
+    use super::*;
+    use crate::{minhash_signature, minhash_signature_canonical};
+
+    const GENOME: &[u8] = b"ATGCGTACGTTAGCCGATAGGCTTACGATCGATCGGATCCATTGACCAGTACGATCGATGCA";
+
+    #[test]
+    fn test_sketch_roundtrip() {
+        let sketch = minhash_sketch(GENOME, 11, 64, true, 42, "phage λ");
+        let bytes = sketch.to_bytes();
+        assert_eq!(&bytes[..4], b"PXMH");
+        let back = MinHashSketch::from_bytes(&bytes).expect("decode");
+        assert_eq!(back.name, "phage λ");
+        assert_eq!(back.k, 11);
+        assert!(back.canonical);
+        assert_eq!(back.hash_seed, 42);
+        assert_eq!(back.genome_length, GENOME.len() as u64);
+        assert_eq!(back.signature, sketch.signature);
+
+        // Truncated or corrupted input is rejected.
+        assert!(MinHashSketch::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert!(MinHashSketch::from_bytes(&bad).is_none());
+    }
+
+    #[test]
+    fn test_seed_zero_matches_signature() {
+        let sketch = minhash_sketch(GENOME, 9, 32, true, 0, "a");
+        let sig = minhash_signature_canonical(GENOME, 9, 32);
+        assert_eq!(sketch.signature, sig.signature);
+        let wrapped = MinHashSketch::from_signature(&sig, "a", GENOME.len() as u64);
+        assert!(wrapped.canonical);
+        assert!((wrapped.jaccard(&sketch) - 1.0).abs() < 1e-12);
+        // Strand mode comes from the signature, not the caller.
+        let forward = minhash_signature(GENOME, 9, 32);
+        let wrapped_forward = MinHashSketch::from_signature(&forward, "f", GENOME.len() as u64);
+        assert!(!wrapped_forward.canonical);
+        assert_eq!(wrapped_forward.jaccard(&sketch), 0.0);
+        assert_eq!(forward.jaccard(&sig), 0.0);
+        assert!((sig.jaccard(&sig) - 1.0).abs() < 1e-12);
+        // Different seed -> incompatible.
+        let other = minhash_sketch(GENOME, 9, 32, true, 7, "b");
+        assert!(!sketch.is_compatible(&other));
+        assert_eq!(sketch.jaccard(&other), 0.0);
+    }
+
+    #[test]
+    fn test_database_roundtrip_and_query() {
+        let mut mutated = GENOME.to_vec();
+        mutated[30] = b'A';
+        mutated[31] = b'A';
+        let mut db = SketchDatabase::new();
+        assert!(db.add(&minhash_sketch(
+            b"TTTTTTTTTTTTTTTTCCCCCCCCCCCC",
+            11,
+            64,
+            true,
+            0,
+            "poly"
+        )));
+        assert!(db.add(&minhash_sketch(&mutated, 11, 64, true, 0, "mutant")));
+        assert!(db.add(&minhash_sketch(GENOME, 11, 64, true, 0, "self")));
+        assert!(!db.add(&minhash_sketch(GENOME, 15, 64, true, 0, "wrong-k")));
+
+        let restored = SketchDatabase::from_bytes(&db.to_bytes()).expect("decode db");
+        assert_eq!(restored.len(), 3);
+
+        let query = minhash_sketch(GENOME, 11, 64, true, 0, "query");
+        let hits = restored.query(&query, 2);
+        assert_eq!(hits.indices, vec![2, 1]);
+        assert!((hits.jaccard[0] - 1.0).abs() < 1e-12);
+        assert_eq!(hits.mash_distances[0], 0.0);
+        assert!(hits.jaccard[1] < 1.0);
+        assert_eq!(hits.names_json(), "[\"self\",\"mutant\"]");
+    }
+}
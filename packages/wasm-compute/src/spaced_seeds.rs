@@ -0,0 +1,460 @@
+//! Spaced-seed k-mers for divergent sequence comparison.
+//!
+//! A spaced seed such as `1101101` samples the bases at its `1` (care)
+//! positions and ignores the `0` positions, so a window still matches when
+//! the mismatch falls on a don't-care base. Matches of neighbouring windows
+//! are much less correlated than for contiguous k-mers of the same weight,
+//! so homologous regions below ~80% identity are far more likely to share
+//! words, and `110110...` patterns tolerate third-codon-position changes.
+//! Several seeds of equal weight can be combined into an ensemble; their
+//! words are tagged with the seed index so different seeds never collide.
+//!
+//! Words are packed like contiguous k-mers (2 bits per care base), so the
+//! sparse counter, MinHash signatures and the `analyze_kmers` metrics work
+//! unchanged on them.
+//!
+//! Canonical mode pairs the forward word with the reverse complement read
+//! through the mirrored seed, which is exactly the word the same window
+//! yields on the opposite strand; windows are skipped if either reading hits
+//! an ambiguous base. This makes canonical words strand-independent for
+//! asymmetric seeds too.
+
+use wasm_bindgen::prelude::*;
+
+use crate::kmer_counter::{union_counts, KmerTable, SparseKmerCounts};
+use crate::{encode_base, kmer_analysis_from_pairs, mix_hash, KmerAnalysisResult};
+use crate::{MinHashSignature, SequenceHandle};
+
+/// Maximum seed weight (care positions); leaves room for the seed tag.
+pub(crate) const SPACED_MAX_WEIGHT: usize = 28;
+
+/// Maximum number of seeds in an ensemble.
+pub(crate) const SPACED_MAX_SEEDS: usize = 16;
+
+/// Bit offset of the seed index in ensemble keys.
+const SEED_TAG_SHIFT: u32 = 56;
+
+/// A parsed spaced seed.
+#[derive(Clone, Debug)]
+pub(crate) struct SpacedSeed {
+    offsets: Vec<usize>,
+    span: usize,
+}
+
+impl SpacedSeed {
+    /// Parse a `1`/`0` pattern; it must start and end with `1`.
+    pub(crate) fn parse(pattern: &str) -> Result<SpacedSeed, String> {
+        let bytes = pattern.as_bytes();
+        if let Some(&b) = bytes.iter().find(|&&b| b != b'0' && b != b'1') {
+            return Err(format!(
+                "invalid character '{}' in seed pattern {}",
+                b as char, pattern
+            ));
+        }
+        if bytes.first() != Some(&b'1') || bytes.last() != Some(&b'1') {
+            return Err(format!(
+                "seed pattern must start and end with 1 (got \"{}\")",
+                pattern
+            ));
+        }
+        let offsets: Vec<usize> = (0..bytes.len()).filter(|&i| bytes[i] == b'1').collect();
+        if offsets.len() > SPACED_MAX_WEIGHT {
+            return Err(format!(
+                "seed weight must be at most {} (got {})",
+                SPACED_MAX_WEIGHT,
+                offsets.len()
+            ));
+        }
+        Ok(SpacedSeed {
+            offsets,
+            span: bytes.len(),
+        })
+    }
+
+    /// Number of care positions.
+    pub(crate) fn weight(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// True if the pattern has no don't-care positions.
+    fn is_contiguous(&self) -> bool {
+        self.offsets.len() == self.span
+    }
+
+    /// True if the pattern reads the same in both directions.
+    pub(crate) fn is_symmetric(&self) -> bool {
+        self.offsets
+            .iter()
+            .zip(self.offsets.iter().rev())
+            .all(|(&a, &b)| a == self.span - 1 - b)
+    }
+}
+
+/// Parse a comma- or whitespace-separated list of seed patterns of equal weight.
+pub(crate) fn parse_seeds(patterns: &str) -> Result<Vec<SpacedSeed>, String> {
+    let seeds = patterns
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .map(SpacedSeed::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    if seeds.is_empty() {
+        return Err("no seed patterns given".to_string());
+    }
+    if seeds.len() > SPACED_MAX_SEEDS {
+        return Err(format!(
+            "at most {} seeds per ensemble (got {})",
+            SPACED_MAX_SEEDS,
+            seeds.len()
+        ));
+    }
+    if seeds.iter().any(|s| s.weight() != seeds[0].weight()) {
+        return Err("all seeds in an ensemble must have the same weight".to_string());
+    }
+    Ok(seeds)
+}
+
+/// Call `f(start, word)` for every window of `encoded` under `seed`.
+///
+/// Windows with an ambiguous base at a care position are skipped (in
+/// canonical mode also at mirrored care positions).
+pub(crate) fn for_each_spaced_kmer<F: FnMut(usize, u64)>(
+    encoded: &[u8],
+    seed: &SpacedSeed,
+    canonical: bool,
+    mut f: F,
+) {
+    if encoded.len() < seed.span {
+        return;
+    }
+    let last = seed.span - 1;
+    'windows: for start in 0..=encoded.len() - seed.span {
+        let window = &encoded[start..start + seed.span];
+        let mut fwd = 0u64;
+        let mut rc = 0u64;
+        for &offset in &seed.offsets {
+            let base = window[offset];
+            if base > 3 {
+                continue 'windows;
+            }
+            fwd = (fwd << 2) | base as u64;
+            if canonical {
+                let mirrored = window[last - offset];
+                if mirrored > 3 {
+                    continue 'windows;
+                }
+                rc = (rc << 2) | (3 - mirrored) as u64;
+            }
+        }
+        f(start, if canonical { fwd.min(rc) } else { fwd });
+    }
+}
+
+/// Call `f(key)` for every word of every seed; keys carry the seed index
+/// above bit 56, so seed 0 keys equal plain packed words.
+pub(crate) fn for_each_seed_key<F: FnMut(u64)>(
+    encoded: &[u8],
+    seeds: &[SpacedSeed],
+    canonical: bool,
+    mut f: F,
+) {
+    for (i, seed) in seeds.iter().enumerate() {
+        let tag = (i as u64) << SEED_TAG_SHIFT;
+        for_each_spaced_kmer(encoded, seed, canonical, |_, word| f(word | tag));
+    }
+}
+
+/// Count spaced words of an encoded sequence into `table`; returns windows counted.
+pub(crate) fn count_spaced_into(
+    table: &mut KmerTable,
+    encoded: &[u8],
+    seeds: &[SpacedSeed],
+    canonical: bool,
+) -> u64 {
+    let mut total = 0u64;
+    for_each_seed_key(encoded, seeds, canonical, |key| {
+        table.add(key, 1);
+        total += 1;
+    });
+    total
+}
+
+/// Hash family seed identifying a seed list: 0 for a single contiguous
+/// seed (its words equal plain k-mers), otherwise a nonzero hash of the
+/// patterns in order.
+fn seed_family(seeds: &[SpacedSeed]) -> u32 {
+    if let [seed] = seeds {
+        if seed.is_contiguous() {
+            return 0;
+        }
+    }
+    let mut family = 0u32;
+    for (i, seed) in seeds.iter().enumerate() {
+        family = mix_hash(((i as u64) << 32) | seed.span as u64, family);
+        for &offset in &seed.offsets {
+            family = mix_hash(offset as u64, family);
+        }
+    }
+    family.max(1)
+}
+
+/// MinHash over spaced words, seeded like `minhash_signature_seeded` with
+/// the `seed_family` of the patterns so signatures of different patterns
+/// (or of contiguous k-mers of the same weight) are never compatible.
+fn spaced_signature(
+    encoded: &[u8],
+    seeds: &[SpacedSeed],
+    num_hashes: usize,
+    canonical: bool,
+) -> MinHashSignature {
+    let hash_seed = seed_family(seeds);
+    let hash_seeds: Vec<u32> = (0..num_hashes)
+        .map(|i| (i as u32).wrapping_mul(0x9e3779b9) ^ hash_seed)
+        .collect();
+    let mut signature = vec![u32::MAX; num_hashes];
+    let mut total_kmers = 0u64;
+    for_each_seed_key(encoded, seeds, canonical, |key| {
+        total_kmers += 1;
+        for (slot, &seed) in signature.iter_mut().zip(hash_seeds.iter()) {
+            *slot = (*slot).min(mix_hash(key, seed));
+        }
+    });
+    MinHashSignature {
+        signature,
+        total_kmers,
+        k: seeds[0].weight(),
+        canonical,
+        hash_seed,
+    }
+}
+
+/// Sparse counts of spaced-seed words.
+///
+/// # Arguments
+/// * `seq` - Sequence as bytes (ASCII, case-insensitive, U counts as T)
+/// * `patterns` - One or more seed patterns, e.g. `"1101101"` or
+///   `"110100111,111001011"` (comma or space separated, equal weight)
+/// * `canonical` - Merge each word with its opposite-strand reading
+///
+/// # Returns
+/// `SparseKmerCounts` with `k` equal to the seed weight; `error` is set for
+/// invalid patterns. Text lookups via `get` take the care-position bases.
+#[wasm_bindgen]
+pub fn count_spaced_kmers(seq: &[u8], patterns: &str, canonical: bool) -> SparseKmerCounts {
+    let mut counts = SparseKmerCounts::spaced(patterns, canonical);
+    counts.add_sequence(seq);
+    counts
+}
+
+/// `analyze_kmers` on spaced-seed words (forward strand).
+///
+/// Jaccard, containment, cosine and Bray-Curtis are computed exactly as for
+/// contiguous k-mers; `k` in the result is the seed weight. A single
+/// all-ones pattern reproduces `analyze_kmers`.
+///
+/// # Returns
+/// `None` if the patterns are invalid.
+#[wasm_bindgen]
+pub fn analyze_spaced_kmers(
+    sequence_a: &str,
+    sequence_b: &str,
+    patterns: &str,
+) -> Option<KmerAnalysisResult> {
+    let seeds = parse_seeds(patterns).ok()?;
+    let count = |seq: &str| {
+        let encoded: Vec<u8> = seq.bytes().map(encode_base).collect();
+        let mut table = KmerTable::with_capacity(encoded.len().min(1 << 20) * seeds.len());
+        count_spaced_into(&mut table, &encoded, &seeds, false);
+        table
+    };
+    let counts_a = count(sequence_a);
+    let counts_b = count(sequence_b);
+    let pairs = union_counts(&counts_a, &counts_b);
+    Some(kmer_analysis_from_pairs(
+        seeds[0].weight(),
+        counts_a.len(),
+        counts_b.len(),
+        &pairs,
+    ))
+}
+
+/// MinHash signature over spaced-seed words.
+///
+/// Signatures compare with `minhash_jaccard_from_signatures` like any other;
+/// both sides must use the same patterns. A single all-ones pattern
+/// reproduces `minhash_signature` (or `minhash_signature_canonical`).
+///
+/// # Arguments
+/// * `seq` - Sequence as bytes (ASCII)
+/// * `patterns` - Seed pattern(s), see `count_spaced_kmers`
+/// * `num_hashes` - Signature length
+/// * `canonical` - Strand-independent words
+///
+/// # Returns
+/// `None` if the patterns are invalid.
+#[wasm_bindgen]
+pub fn minhash_signature_spaced(
+    seq: &[u8],
+    patterns: &str,
+    num_hashes: usize,
+    canonical: bool,
+) -> Option<MinHashSignature> {
+    let seeds = parse_seeds(patterns).ok()?;
+    let encoded: Vec<u8> = seq.iter().map(|&b| encode_base(b)).collect();
+    Some(spaced_signature(&encoded, &seeds, num_hashes, canonical))
+}
+
+#[wasm_bindgen]
+impl SequenceHandle {
+    /// Sparse counts of spaced-seed words of the pre-encoded sequence.
+    pub fn count_spaced_kmers(&self, patterns: &str, canonical: bool) -> SparseKmerCounts {
+        let mut counts = SparseKmerCounts::spaced(patterns, canonical);
+        counts.add_encoded(&self.encoded);
+        counts
+    }
+
+    /// Canonical MinHash signature over spaced-seed words (`None` if the
+    /// patterns are invalid).
+    pub fn minhash_spaced(&self, patterns: &str, num_hashes: usize) -> Option<MinHashSignature> {
+        let seeds = parse_seeds(patterns).ok()?;
+        Some(spaced_signature(&self.encoded, &seeds, num_hashes, true))
+    }
+}
+
+#[cfg(test)]
+mod spaced_seeds_tests {
+    use super::*;
+    use crate::rng::random_dna;
+    use crate::{analyze_kmers, minhash_jaccard_from_signatures, minhash_signature_canonical};
+
+    fn reverse_complement(seq: &[u8]) -> Vec<u8> {
+        seq.iter()
+            .rev()
+            .map(|&b| match b {
+                b'A' => b'T',
+                b'C' => b'G',
+                b'G' => b'C',
+                _ => b'A',
+            })
+            .collect()
+    }
+
+    /// Substitute every `period`-th base.
+    fn mutate(seq: &[u8], period: usize) -> Vec<u8> {
+        seq.iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                if i % period == period / 2 {
+                    match b {
+                        b'A' => b'C',
+                        b'C' => b'G',
+                        b'G' => b'T',
+                        _ => b'A',
+                    }
+                } else {
+                    b
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_seeds() {
+        let seed = SpacedSeed::parse("1101101").unwrap();
+        assert_eq!(seed.weight(), 5);
+        assert_eq!(seed.span, 7);
+        assert!(!seed.is_symmetric());
+        assert!(SpacedSeed::parse("1101011").unwrap().is_symmetric());
+        assert!(SpacedSeed::parse("0110").is_err());
+        assert!(SpacedSeed::parse("1x1").is_err());
+        assert!(SpacedSeed::parse(&"1".repeat(29)).is_err());
+        assert_eq!(parse_seeds("1101, 1011").unwrap().len(), 2);
+        assert!(parse_seeds("11011,111").is_err());
+        assert!(parse_seeds(" , ").is_err());
+    }
+
+    #[test]
+    fn test_contiguous_pattern_matches_kmers() {
+        let a = String::from_utf8(random_dna(3_000, 1)).unwrap();
+        let b = String::from_utf8(mutate(a.as_bytes(), 25)).unwrap();
+        let spaced = analyze_spaced_kmers(&a, &b, "11111111111").unwrap();
+        let plain = analyze_kmers(&a, &b, 11);
+        assert_eq!(spaced.shared_kmers, plain.shared_kmers);
+        assert_eq!(spaced.unique_kmers_a, plain.unique_kmers_a);
+        assert!((spaced.cosine_similarity - plain.cosine_similarity).abs() < 1e-12);
+
+        let sig = minhash_signature_spaced(a.as_bytes(), "111111111111111", 64, true).unwrap();
+        assert_eq!(
+            sig.signature,
+            minhash_signature_canonical(a.as_bytes(), 15, 64).signature
+        );
+        assert!(analyze_spaced_kmers(&a, &b, "1201").is_none());
+    }
+
+    #[test]
+    fn test_spaced_signature_compatibility() {
+        let seq = random_dna(2_000, 4);
+        let contiguous = minhash_signature_canonical(&seq, 5, 64);
+        let spaced = minhash_signature_spaced(&seq, "1101101", 64, true).unwrap();
+        assert_eq!(spaced.k, contiguous.k);
+        assert_ne!(spaced.hash_seed, 0);
+        assert!(!spaced.is_compatible(&contiguous));
+        assert_eq!(spaced.jaccard(&contiguous), 0.0);
+
+        // Same weight, different patterns or seed order.
+        let other = minhash_signature_spaced(&seq, "1011011", 64, true).unwrap();
+        assert!(!spaced.is_compatible(&other));
+        let ab = minhash_signature_spaced(&seq, "1101101,1011011", 64, true).unwrap();
+        let ba = minhash_signature_spaced(&seq, "1011011,1101101", 64, true).unwrap();
+        assert!(!ab.is_compatible(&ba));
+
+        let again = minhash_signature_spaced(&seq, "1101101", 64, true).unwrap();
+        assert!(spaced.is_compatible(&again));
+        assert_eq!(spaced.jaccard(&again), 1.0);
+    }
+
+    #[test]
+    fn test_canonical_strand_independent() {
+        let seq = random_dna(2_000, 2);
+        let rc = reverse_complement(&seq);
+        for pattern in ["110100111011", "1101101,1011011"] {
+            let fwd = count_spaced_kmers(&seq, pattern, true);
+            let rev = count_spaced_kmers(&rc, pattern, true);
+            assert_eq!(fwd.total_valid(), rev.total_valid());
+            let mut a: Vec<(u64, u32)> = fwd.table.iter().collect();
+            let mut b: Vec<(u64, u32)> = rev.table.iter().collect();
+            a.sort_unstable();
+            b.sort_unstable();
+            assert_eq!(a, b, "pattern {}", pattern);
+        }
+    }
+
+    #[test]
+    fn test_spaced_more_sensitive_than_contiguous() {
+        // Codon-like divergence: every third base substituted (67% identity).
+        let a = random_dna(20_000, 3);
+        let b = mutate(&a, 3);
+        let pattern = "11011011011011011";
+        let sig_a = minhash_signature_spaced(&a, pattern, 256, true).unwrap();
+        let sig_b = minhash_signature_spaced(&b, pattern, 256, true).unwrap();
+        let spaced = minhash_jaccard_from_signatures(&sig_a.signature, &sig_b.signature);
+        let plain = minhash_jaccard_from_signatures(
+            &minhash_signature_canonical(&a, 12, 256).signature,
+            &minhash_signature_canonical(&b, 12, 256).signature,
+        );
+        assert!(plain < 0.02, "contiguous {}", plain);
+        assert!((spaced - 0.2).abs() < 0.08, "spaced {}", spaced);
+    }
+
+    #[test]
+    fn test_ensemble_counts_and_lookup() {
+        let seq = b"ACGTTGCAACGTAGGC";
+        let counts = count_spaced_kmers(seq, "1011,1101", false);
+        assert!(counts.error().is_none());
+        assert_eq!(counts.k(), 3);
+        // Two seeds of span 4 over 16 bases.
+        assert_eq!(counts.total_valid(), 26);
+        // Seed 0 (1011) at position 0 reads A_GT.
+        assert!(counts.get("AGT") >= 1);
+        assert!(count_spaced_kmers(seq, "", false).error().is_some());
+    }
+}
@@ -0,0 +1,448 @@
+//! Reference distributions for p-values.
+//!
+//! - Standard normal tail and density
+//! - Student's t tail via the regularized incomplete beta function
+//! - The Blum–Kiefer–Rosenblatt limit of Hoeffding's D under independence:
+//!   n·B → Σ_{j,k≥1} Z²_jk / (π⁴ j² k²), evaluated with the Lugannani–Rice
+//!   saddlepoint approximation (relative error of a few percent, including
+//!   far in the tail where numerical inversion loses all digits)
+//! - Robust location and scale: median and the normal-consistent MAD
+//! - Multiple-testing corrections: Bonferroni and Holm for the family-wise
+//!   error rate, Benjamini–Hochberg/–Yekutieli for the false discovery rate,
+//!   and Storey's q-values with an estimated null proportion
+
+use std::f64::consts::PI;
+
+use wasm_bindgen::prelude::*;
+
+/// Rows and columns of the BKR eigenvalue grid kept explicitly; the rest
+/// (about 2% of the mean) is nearly deterministic and enters as a shift.
+const BKR_TERMS: usize = 60;
+
+/// Complementary error function (Chebyshev fit, relative error < 1.2e-7
+/// everywhere, so tail probabilities keep their precision).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let r = t * poly.exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+/// Upper tail of the standard normal, P(Z > z).
+pub(crate) fn normal_sf(z: f64) -> f64 {
+    0.5 * erfc(z / std::f64::consts::SQRT_2)
+}
+
+/// Standard normal density.
+pub(crate) fn normal_pdf(z: f64) -> f64 {
+    (-0.5 * z * z).exp() / (2.0 * PI).sqrt()
+}
+
+/// ln Γ(x) for x > 0 (Lanczos, g = 7, about 15 significant digits).
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection: Γ(x) Γ(1 - x) = π / sin(πx).
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFS[0], |acc, (i, &c)| acc + c / (x + (i + 1) as f64));
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz).
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..=300 {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        for coeff in [even, odd] {
+            d = 1.0 + coeff * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + coeff / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    h
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+pub(crate) fn regularized_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - ln_front.exp() * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Two-sided p-value P(|T| > |t|) for Student's t with `df` degrees of
+/// freedom.
+pub(crate) fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    if t.is_nan() || df <= 0.0 {
+        return f64::NAN;
+    }
+    if t.is_infinite() {
+        return 0.0;
+    }
+    regularized_beta(0.5 * df, 0.5, df / (df + t * t))
+}
+
+/// Eigenvalues 1/(π⁴ j² k²), largest first, and the omitted mass.
+fn bkr_eigenvalues() -> (Vec<f64>, f64) {
+    let pi4 = PI.powi(4);
+    let mut lambdas = Vec::with_capacity(BKR_TERMS * BKR_TERMS);
+    for j in 1..=BKR_TERMS {
+        for k in 1..=BKR_TERMS {
+            lambdas.push(1.0 / (pi4 * (j * j * k * k) as f64));
+        }
+    }
+    let kept: f64 = lambdas.iter().sum();
+    (lambdas, 1.0 / 36.0 - kept)
+}
+
+/// P(n·B > x) in the BKR limit.
+pub(crate) fn bkr_sf(x: f64) -> f64 {
+    let (lambdas, tail) = bkr_eigenvalues();
+    let x = x - tail;
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let top = lambdas[0];
+    // Parametrize the saddlepoint s by q = 1 - 2sλ₁ ∈ (0, ∞), which keeps
+    // every factor 1 - 2sλ positive and well conditioned near the pole.
+    let factor = |q: f64, lambda: f64| {
+        if lambda == top {
+            q
+        } else {
+            1.0 - (1.0 - q) * lambda / top
+        }
+    };
+    let mean_at = |q: f64| -> f64 { lambdas.iter().map(|&l| l / factor(q, l)).sum() };
+    let (mut lo, mut hi) = (-700.0f64, 700.0f64);
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if mean_at(mid.exp()) > x {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let q = (0.5 * (lo + hi)).exp();
+    let s = (1.0 - q) / (2.0 * top);
+
+    let mut cgf = 0.0;
+    let mut k2 = 0.0;
+    for &l in &lambdas {
+        let f = factor(q, l);
+        cgf -= 0.5 * f.ln();
+        k2 += 2.0 * l * l / (f * f);
+    }
+    let w = s.signum() * (2.0 * (s * x - cgf)).max(0.0).sqrt();
+    let p = if w.abs() < 1e-4 {
+        // At the mean: the limit 1/2 - κ₃ / (6 √(2π) κ₂^{3/2}).
+        let k3: f64 = lambdas.iter().map(|l| 8.0 * l * l * l).sum();
+        0.5 - k3 / (6.0 * (2.0 * PI).sqrt() * k2.powf(1.5))
+    } else {
+        let v = s * k2.sqrt();
+        normal_sf(w) + normal_pdf(w) * (1.0 / v - 1.0 / w)
+    };
+    p.clamp(0.0, 1.0)
+}
+
+/// Asymptotic p-value of Hoeffding's D (scaled to [-0.5, 1]) from `n`
+/// observations: n·B ≈ (n − 1)·D/30 + 1/36 (Hollander & Wolfe).
+pub(crate) fn hoeffding_p_value(d: f64, n: usize) -> f64 {
+    if n < 5 || !d.is_finite() {
+        return f64::NAN;
+    }
+    bkr_sf((n - 1) as f64 * d / 30.0 + 1.0 / 36.0)
+}
+
+/// Median of `values` (NaN when empty). Reorders `values`.
+pub(crate) fn median(values: &mut [f64]) -> f64 {
+    let n = values.len();
+    if n == 0 {
+        return f64::NAN;
+    }
+    let mid = n / 2;
+    let (lower, upper, _) = values.select_nth_unstable_by(mid, f64::total_cmp);
+    let upper = *upper;
+    if n % 2 == 1 {
+        upper
+    } else {
+        let lower = lower.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        0.5 * (lower + upper)
+    }
+}
+
+/// Median absolute deviation scaled by 1/Φ⁻¹(3/4), so it estimates σ for
+/// normal data. Returns (median, scaled MAD).
+pub(crate) fn median_and_mad(values: &[f64]) -> (f64, f64) {
+    let mut work = values.to_vec();
+    let center = median(&mut work);
+    for (w, &v) in work.iter_mut().zip(values) {
+        *w = (v - center).abs();
+    }
+    (center, median(&mut work) / 0.674_489_750_196_081_7)
+}
+
+/// Multiple-testing adjustment for `adjust_p_values`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PValueAdjustment {
+    /// Family-wise error: p·m
+    Bonferroni = 0,
+    /// Family-wise error, step-down (uniformly more powerful than Bonferroni)
+    Holm = 1,
+    /// False discovery rate under independence or positive dependence
+    BenjaminiHochberg = 2,
+    /// False discovery rate under arbitrary dependence: BH × Σ 1/i
+    BenjaminiYekutieli = 3,
+}
+
+/// Indices of the usable p-values (within [0, 1]), ascending by p-value.
+fn ranked(p_values: &[f64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..p_values.len())
+        .filter(|&i| (0.0..=1.0).contains(&p_values[i]))
+        .collect();
+    order.sort_by(|&a, &b| p_values[a].total_cmp(&p_values[b]));
+    order
+}
+
+/// Adjust p-values for multiple testing.
+///
+/// NaN or out-of-range entries are treated as missing: they stay NaN and do
+/// not count towards the number of tests m. Otherwise the values match R's
+/// `p.adjust`.
+///
+/// # Arguments
+/// * `p_values` - Raw p-values
+/// * `method` - Adjustment method
+///
+/// # Returns
+/// Adjusted p-values (q-values for the FDR methods), same order as input.
+#[wasm_bindgen]
+pub fn adjust_p_values(p_values: &[f64], method: PValueAdjustment) -> Vec<f64> {
+    let order = ranked(p_values);
+    let m = order.len() as f64;
+    let mut adjusted = vec![f64::NAN; p_values.len()];
+    match method {
+        PValueAdjustment::Bonferroni => {
+            for &i in &order {
+                adjusted[i] = (p_values[i] * m).min(1.0);
+            }
+        }
+        PValueAdjustment::Holm => {
+            let mut running = 0.0f64;
+            for (rank, &i) in order.iter().enumerate() {
+                running = running.max((p_values[i] * (m - rank as f64)).min(1.0));
+                adjusted[i] = running;
+            }
+        }
+        PValueAdjustment::BenjaminiHochberg | PValueAdjustment::BenjaminiYekutieli => {
+            let factor = if method == PValueAdjustment::BenjaminiYekutieli {
+                (1..=order.len()).map(|i| 1.0 / i as f64).sum()
+            } else {
+                1.0
+            };
+            let mut running = 1.0f64;
+            for (rank, &i) in order.iter().enumerate().rev() {
+                running = running.min(p_values[i] * factor * m / (rank + 1) as f64);
+                adjusted[i] = running;
+            }
+        }
+    }
+    adjusted
+}
+
+/// Benjamini–Hochberg q-values: `adjust_p_values` with `BenjaminiHochberg`.
+pub(crate) fn benjamini_hochberg(p_values: &[f64]) -> Vec<f64> {
+    adjust_p_values(p_values, PValueAdjustment::BenjaminiHochberg)
+}
+
+/// Storey's estimate of the proportion of true null hypotheses,
+/// π₀ = #{p > λ} / (m·(1 − λ)), clamped to [1/m, 1] so q-values never
+/// collapse to 0 when every p-value is at most λ.
+///
+/// # Arguments
+/// * `p_values` - Raw p-values (NaN and out-of-range entries are ignored)
+/// * `lambda` - Tuning parameter in [0, 1); NaN for the usual 0.5
+#[wasm_bindgen]
+pub fn storey_pi0(p_values: &[f64], lambda: f64) -> f64 {
+    let lambda = if lambda.is_nan() { 0.5 } else { lambda };
+    if !(0.0..1.0).contains(&lambda) {
+        return f64::NAN;
+    }
+    let usable: Vec<f64> = p_values
+        .iter()
+        .copied()
+        .filter(|p| (0.0..=1.0).contains(p))
+        .collect();
+    if usable.is_empty() {
+        return f64::NAN;
+    }
+    let above = usable.iter().filter(|&&p| p > lambda).count() as f64;
+    let m = usable.len() as f64;
+    (above / (m * (1.0 - lambda))).clamp(1.0 / m, 1.0)
+}
+
+/// Storey q-values: Benjamini–Hochberg scaled by the estimated π₀, which
+/// gains power when many hypotheses are non-null.
+///
+/// # Arguments
+/// * `p_values` - Raw p-values (NaN and out-of-range entries stay NaN)
+/// * `lambda` - Tuning parameter for π₀ in [0, 1); NaN for 0.5
+#[wasm_bindgen]
+pub fn storey_q_values(p_values: &[f64], lambda: f64) -> Vec<f64> {
+    let pi0 = storey_pi0(p_values, lambda);
+    benjamini_hochberg(p_values)
+        .into_iter()
+        .map(|q| q * pi0)
+        .collect()
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_tail() {
+        assert!((normal_sf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_sf(1.959_964) - 0.025).abs() < 1e-7);
+        assert!((normal_sf(-1.959_964) - 0.975).abs() < 1e-7);
+        // Relative accuracy far in the tail: P(Z > 8) = 6.22e-16.
+        assert!((normal_sf(8.0) / 6.220_960_574_271_78e-16 - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_student_t_and_gamma() {
+        // Γ(5) = 24, Γ(1/2) = √π.
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+        assert!((ln_gamma(0.5) - PI.sqrt().ln()).abs() < 1e-12);
+        // t(10) critical value 2.228 at two-sided α = 0.05; t(1) is Cauchy.
+        assert!((student_t_two_sided(2.228_138_85, 10.0) - 0.05).abs() < 1e-6);
+        assert!((student_t_two_sided(1.0, 1.0) - 0.5).abs() < 1e-12);
+        assert_eq!(student_t_two_sided(0.0, 5.0), 1.0);
+    }
+
+    #[test]
+    fn test_bkr_tail_is_monotone_with_known_quantiles() {
+        assert_eq!(bkr_sf(0.0), 1.0);
+        let mut last = 1.0;
+        for i in 1..40 {
+            let p = bkr_sf(i as f64 * 0.005);
+            assert!(p <= last && p >= 0.0);
+            last = p;
+        }
+        // Tabulated critical values of (π⁴/2)·n·B (Blum, Kiefer & Rosenblatt
+        // 1961): 2.844 at α = 0.05 and 4.230 at α = 0.01.
+        let scale = PI.powi(4) / 2.0;
+        assert!((bkr_sf(2.844 / scale) - 0.05).abs() < 0.005);
+        assert!((bkr_sf(4.230 / scale) - 0.01).abs() < 0.002);
+    }
+
+    #[test]
+    fn test_benjamini_hochberg_and_mad() {
+        // p.adjust(c(0.01, 0.04, 0.03, 0.2), "BH") = 0.04 0.0533 0.0533 0.2
+        let q = benjamini_hochberg(&[0.01, 0.04, f64::NAN, 0.03, 0.2]);
+        let expected = [0.04, 0.16 / 3.0, f64::NAN, 0.16 / 3.0, 0.2];
+        for (a, b) in q.iter().zip(expected) {
+            assert!((a - b).abs() < 1e-12 || (a.is_nan() && b.is_nan()));
+        }
+        let (center, mad) = median_and_mad(&[1.0, 2.0, 3.0, 4.0, 100.0]);
+        assert_eq!(center, 3.0);
+        assert!((mad - 1.0 / 0.674_489_750_196_081_7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_family_wise_and_storey_adjustments() {
+        let p = [0.01, 0.02, 0.03, 0.04, 0.05];
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-12);
+        // Reference values from R's p.adjust.
+        assert!(close(
+            &adjust_p_values(&p, PValueAdjustment::Bonferroni),
+            &[0.05, 0.1, 0.15, 0.2, 0.25]
+        ));
+        assert!(close(
+            &adjust_p_values(&p, PValueAdjustment::Holm),
+            &[0.05, 0.08, 0.09, 0.09, 0.09]
+        ));
+        let by = 0.05 * (1.0 + 1.0 / 2.0 + 1.0 / 3.0 + 1.0 / 4.0 + 1.0 / 5.0);
+        assert!(close(
+            &adjust_p_values(&p, PValueAdjustment::BenjaminiYekutieli),
+            &[by; 5]
+        ));
+
+        // Half strong signals, half uniform nulls: pi0 = 25 / (100 * 0.5).
+        let mixed: Vec<f64> = (0..50)
+            .map(|_| 1e-4)
+            .chain((1..=50).map(|i| i as f64 / 50.0))
+            .collect();
+        assert!((storey_pi0(&mixed, f64::NAN) - 0.5).abs() < 1e-12);
+        let bh = adjust_p_values(&mixed, PValueAdjustment::BenjaminiHochberg);
+        let q = storey_q_values(&mixed, 0.5);
+        assert!(close(&q, &bh.iter().map(|v| v * 0.5).collect::<Vec<_>>()));
+        assert!(storey_pi0(&mixed, 1.0).is_nan());
+
+        // All signals: pi0 bottoms out at 1/m instead of zeroing every q.
+        let strong = [1e-4, 2e-3, 0.01, 0.2];
+        assert!((storey_pi0(&strong, 0.5) - 0.25).abs() < 1e-12);
+        let q = storey_q_values(&strong, 0.5);
+        let bh = adjust_p_values(&strong, PValueAdjustment::BenjaminiHochberg);
+        assert!(q.iter().all(|&v| v > 0.0));
+        assert!(close(&q, &bh.iter().map(|v| v * 0.25).collect::<Vec<_>>()));
+    }
+}
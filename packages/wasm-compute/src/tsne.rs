@@ -0,0 +1,596 @@
+//! Barnes–Hut t-SNE (van der Maaten 2014) in two dimensions.
+//!
+//! Input affinities use the `3 · perplexity` nearest neighbors of each
+//! point, calibrated to the target perplexity and symmetrized. Repulsive
+//! forces are approximated with a quadtree: a cell whose width is below
+//! `theta` times its distance to the point acts as a single body
+//! (`theta = 0` gives exact gradients).
+//!
+//! Optimization follows the reference implementation: early exaggeration
+//! (12×) with momentum 0.5 for the first 250 iterations, then momentum 0.8,
+//! adaptive gains, and a learning rate of max(n / 12 / 4, 50). The layout is
+//! advanced with `step`, so the UI can draw intermediate frames, or with
+//! `step_with_callback`, which hands every N-th frame to a JS callback.
+
+use wasm_bindgen::prelude::*;
+
+use crate::embedding::{coords_array, emit_frame, PointSet};
+use crate::rng::SplitMix64;
+
+const EARLY_EXAGGERATION: f64 = 12.0;
+const EXAGGERATION_ITERATIONS: usize = 250;
+const QUADTREE_MAX_DEPTH: usize = 48;
+const NO_POINT: usize = usize::MAX;
+
+#[derive(Clone, Copy)]
+struct Cell {
+    cx: f64,
+    cy: f64,
+    half: f64,
+    mass: f64,
+    sum_x: f64,
+    sum_y: f64,
+    /// Index of the first of four consecutive children (0 = leaf).
+    children: usize,
+    point: usize,
+}
+
+impl Cell {
+    fn new(cx: f64, cy: f64, half: f64) -> Cell {
+        Cell {
+            cx,
+            cy,
+            half,
+            mass: 0.0,
+            sum_x: 0.0,
+            sum_y: 0.0,
+            children: 0,
+            point: NO_POINT,
+        }
+    }
+}
+
+/// Point-region quadtree with centers of mass.
+struct QuadTree {
+    cells: Vec<Cell>,
+    /// Leaf holding each point.
+    leaf_of: Vec<usize>,
+}
+
+impl QuadTree {
+    fn build(coords: &[f64]) -> QuadTree {
+        let n = coords.len() / 2;
+        let (mut min_x, mut max_x) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut min_y, mut max_y) = (f64::INFINITY, f64::NEG_INFINITY);
+        for p in coords.chunks_exact(2) {
+            min_x = min_x.min(p[0]);
+            max_x = max_x.max(p[0]);
+            min_y = min_y.min(p[1]);
+            max_y = max_y.max(p[1]);
+        }
+        let half = 0.5 * (max_x - min_x).max(max_y - min_y) + 1e-5;
+        let mut tree = QuadTree {
+            cells: vec![Cell::new(
+                0.5 * (min_x + max_x),
+                0.5 * (min_y + max_y),
+                half,
+            )],
+            leaf_of: vec![0; n],
+        };
+        for i in 0..n {
+            tree.insert(i, coords);
+        }
+        tree
+    }
+
+    fn child_for(&self, c: usize, x: f64, y: f64) -> usize {
+        let cell = &self.cells[c];
+        cell.children + usize::from(x > cell.cx) + 2 * usize::from(y > cell.cy)
+    }
+
+    fn insert(&mut self, i: usize, coords: &[f64]) {
+        let (x, y) = (coords[2 * i], coords[2 * i + 1]);
+        let mut c = 0;
+        for depth in 0.. {
+            let first_free = self.cells.len();
+            let cell = &mut self.cells[c];
+            cell.mass += 1.0;
+            cell.sum_x += x;
+            cell.sum_y += y;
+            if cell.children == 0 {
+                if cell.mass == 1.0 {
+                    cell.point = i;
+                    self.leaf_of[i] = c;
+                    return;
+                }
+                if depth >= QUADTREE_MAX_DEPTH {
+                    // Coincident points: keep them aggregated in this leaf.
+                    self.leaf_of[i] = c;
+                    return;
+                }
+                let (cx, cy, half, existing) = (cell.cx, cell.cy, cell.half, cell.point);
+                cell.point = NO_POINT;
+                cell.children = first_free;
+                let q = 0.5 * half;
+                for (dx, dy) in [(-q, -q), (q, -q), (-q, q), (q, q)] {
+                    self.cells.push(Cell::new(cx + dx, cy + dy, q));
+                }
+                let (ex, ey) = (coords[2 * existing], coords[2 * existing + 1]);
+                let child = self.child_for(c, ex, ey);
+                let moved = &mut self.cells[child];
+                moved.mass = 1.0;
+                moved.sum_x = ex;
+                moved.sum_y = ey;
+                moved.point = existing;
+                self.leaf_of[existing] = child;
+            }
+            c = self.child_for(c, x, y);
+        }
+    }
+
+    /// Repulsive force numerator on point `i` and its share of Z = Σ q_ij.
+    fn repulsion(
+        &self,
+        i: usize,
+        x: f64,
+        y: f64,
+        theta: f64,
+        stack: &mut Vec<usize>,
+    ) -> (f64, f64, f64) {
+        let (mut fx, mut fy, mut sum_q) = (0.0, 0.0, 0.0);
+        stack.clear();
+        stack.push(0);
+        while let Some(c) = stack.pop() {
+            let cell = &self.cells[c];
+            let (mut mass, mut sx, mut sy) = (cell.mass, cell.sum_x, cell.sum_y);
+            if c == self.leaf_of[i] {
+                mass -= 1.0;
+                sx -= x;
+                sy -= y;
+            }
+            if mass <= 0.0 {
+                continue;
+            }
+            let dx = x - sx / mass;
+            let dy = y - sy / mass;
+            let d2 = dx * dx + dy * dy;
+            let width = 2.0 * cell.half;
+            if cell.children == 0 || width * width < theta * theta * d2 {
+                let q = 1.0 / (1.0 + d2);
+                sum_q += mass * q;
+                fx += mass * q * q * dx;
+                fy += mass * q * q * dy;
+            } else {
+                stack.extend(cell.children..cell.children + 4);
+            }
+        }
+        (fx, fy, sum_q)
+    }
+}
+
+/// Row-normalized Gaussian affinities at the target perplexity.
+fn conditional_affinities(neighbors: &[(usize, f64)], perplexity: f64) -> Vec<f64> {
+    let d2: Vec<f64> = neighbors.iter().map(|&(_, d)| d * d).collect();
+    let target = perplexity.ln();
+    let (mut beta, mut lo, mut hi) = (1.0, 0.0, f64::INFINITY);
+    let d_min = d2.iter().copied().fold(f64::INFINITY, f64::min);
+    let mut p = vec![0.0; d2.len()];
+    for _ in 0..200 {
+        // Shift by the smallest distance to avoid underflow.
+        let mut sum = 0.0;
+        let mut weighted = 0.0;
+        for (pj, &dj) in p.iter_mut().zip(d2.iter()) {
+            *pj = (-beta * (dj - d_min)).exp();
+            sum += *pj;
+            weighted += *pj * (dj - d_min);
+        }
+        let entropy = sum.ln() + beta * weighted / sum;
+        if (entropy - target).abs() < 1e-5 {
+            break;
+        }
+        if entropy > target {
+            lo = beta;
+            beta = if hi.is_finite() {
+                0.5 * (beta + hi)
+            } else {
+                beta * 2.0
+            };
+        } else {
+            hi = beta;
+            beta = 0.5 * (beta + lo);
+        }
+    }
+    let sum: f64 = p.iter().sum();
+    p.iter_mut().for_each(|v| *v /= sum);
+    p
+}
+
+/// t-SNE layout, advanced with `step`.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct TsneEmbedding {
+    n: usize,
+    coords: Vec<f64>,
+    update: Vec<f64>,
+    gains: Vec<f64>,
+    /// Symmetric joint affinities p_ij (rows sum to the point's share of 1).
+    affinities: Vec<Vec<(usize, f64)>>,
+    theta: f64,
+    learning_rate: f64,
+    iteration: usize,
+    n_iterations: usize,
+    error: Option<String>,
+}
+
+impl TsneEmbedding {
+    fn failed(error: String) -> TsneEmbedding {
+        TsneEmbedding {
+            n: 0,
+            coords: Vec::new(),
+            update: Vec::new(),
+            gains: Vec::new(),
+            affinities: Vec::new(),
+            theta: 0.0,
+            learning_rate: 0.0,
+            iteration: 0,
+            n_iterations: 0,
+            error: Some(error),
+        }
+    }
+
+    fn new(
+        points: Result<PointSet, String>,
+        perplexity: f64,
+        theta: f64,
+        n_iterations: usize,
+        seed: u64,
+    ) -> TsneEmbedding {
+        let points = match points {
+            Ok(points) => points,
+            Err(error) => return TsneEmbedding::failed(error),
+        };
+        if perplexity.is_nan() || perplexity <= 0.0 {
+            return TsneEmbedding::failed("perplexity must be positive".to_string());
+        }
+        let n = points.len();
+        let perplexity = perplexity.min((n - 1) as f64 / 3.0).max(1.0);
+        let k = ((3.0 * perplexity) as usize).clamp(1, n - 1);
+        let neighbors = points.knn(k);
+
+        // Symmetrize: p_ij = (p_j|i + p_i|j) / 2n.
+        let mut entries: Vec<(usize, usize, f64)> = Vec::with_capacity(2 * n * k);
+        for (i, row) in neighbors.iter().enumerate() {
+            for (&(j, _), p) in row.iter().zip(conditional_affinities(row, perplexity)) {
+                let v = p / (2.0 * n as f64);
+                entries.push((i, j, v));
+                entries.push((j, i, v));
+            }
+        }
+        entries.sort_unstable_by_key(|e| (e.0, e.1));
+        let mut affinities: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+        for (i, j, v) in entries {
+            match affinities[i].last_mut() {
+                Some(last) if last.0 == j => last.1 += v,
+                _ => affinities[i].push((j, v)),
+            }
+        }
+
+        let mut rng = SplitMix64::new(seed);
+        let coords = (0..2 * n).map(|_| 1e-4 * rng.gaussian()).collect();
+        TsneEmbedding {
+            n,
+            coords,
+            update: vec![0.0; 2 * n],
+            gains: vec![1.0; 2 * n],
+            affinities,
+            theta: theta.max(0.0),
+            learning_rate: (n as f64 / EARLY_EXAGGERATION / 4.0).max(50.0),
+            iteration: 0,
+            n_iterations,
+            error: None,
+        }
+    }
+
+    fn gradient_step(&mut self) {
+        let n = self.n;
+        let exaggeration = if self.iteration < EXAGGERATION_ITERATIONS {
+            EARLY_EXAGGERATION
+        } else {
+            1.0
+        };
+        let momentum = if self.iteration < EXAGGERATION_ITERATIONS {
+            0.5
+        } else {
+            0.8
+        };
+
+        let tree = QuadTree::build(&self.coords);
+        let mut repulsive = vec![0.0; 2 * n];
+        let mut z = 0.0;
+        let mut stack = Vec::new();
+        for i in 0..n {
+            let (x, y) = (self.coords[2 * i], self.coords[2 * i + 1]);
+            let (fx, fy, sum_q) = tree.repulsion(i, x, y, self.theta, &mut stack);
+            repulsive[2 * i] = fx;
+            repulsive[2 * i + 1] = fy;
+            z += sum_q;
+        }
+        let z = z.max(f64::MIN_POSITIVE);
+
+        for i in 0..n {
+            let (x, y) = (self.coords[2 * i], self.coords[2 * i + 1]);
+            let (mut ax, mut ay) = (0.0, 0.0);
+            for &(j, p) in &self.affinities[i] {
+                let dx = x - self.coords[2 * j];
+                let dy = y - self.coords[2 * j + 1];
+                let q = 1.0 / (1.0 + dx * dx + dy * dy);
+                ax += exaggeration * p * q * dx;
+                ay += exaggeration * p * q * dy;
+            }
+            for (d, attractive) in [(0, ax), (1, ay)] {
+                let idx = 2 * i + d;
+                let grad = 4.0 * (attractive - repulsive[idx] / z);
+                self.gains[idx] = if (grad > 0.0) != (self.update[idx] > 0.0) {
+                    self.gains[idx] + 0.2
+                } else {
+                    (self.gains[idx] * 0.8).max(0.01)
+                };
+                self.update[idx] =
+                    momentum * self.update[idx] - self.learning_rate * self.gains[idx] * grad;
+            }
+        }
+        for (c, u) in self.coords.iter_mut().zip(self.update.iter()) {
+            *c += u;
+        }
+        for d in 0..2 {
+            let mean = self.coords.iter().skip(d).step_by(2).sum::<f64>() / n as f64;
+            self.coords
+                .iter_mut()
+                .skip(d)
+                .step_by(2)
+                .for_each(|c| *c -= mean);
+        }
+        self.iteration += 1;
+    }
+
+    /// Run up to `iterations` more steps, passing the layout to `frame`
+    /// whenever the iteration count is a multiple of `every` (never if 0)
+    /// and after the last step.
+    fn step_frames<E>(
+        &mut self,
+        iterations: usize,
+        every: usize,
+        mut frame: impl FnMut(&[f64], usize) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if self.error.is_some() {
+            return Ok(());
+        }
+        let end = self
+            .iteration
+            .saturating_add(iterations)
+            .min(self.n_iterations);
+        while self.iteration < end {
+            self.gradient_step();
+            if self.iteration == end || (every > 0 && self.iteration.is_multiple_of(every)) {
+                frame(&self.coords, self.iteration)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl TsneEmbedding {
+    /// Run up to `iterations` more gradient steps.
+    ///
+    /// # Returns
+    /// Fraction of iterations completed, in [0, 1].
+    pub fn step(&mut self, iterations: usize) -> f64 {
+        if self.error.is_none() {
+            let end = self
+                .iteration
+                .saturating_add(iterations)
+                .min(self.n_iterations);
+            while self.iteration < end {
+                self.gradient_step();
+            }
+        }
+        self.progress()
+    }
+
+    /// Run up to `iterations` more gradient steps and call
+    /// `callback(coords, iteration)` every `every` iterations and after the
+    /// last one, so an animated layout needs a single call. `coords` is a
+    /// fresh Float32Array as returned by the `coords` getter.
+    ///
+    /// # Returns
+    /// Fraction of iterations completed, in [0, 1]. If the callback throws,
+    /// stepping stops and the exception is rethrown.
+    pub fn step_with_callback(
+        &mut self,
+        iterations: usize,
+        every: usize,
+        callback: &js_sys::Function,
+    ) -> Result<f64, JsValue> {
+        self.step_frames(iterations, every, |coords, iteration| {
+            emit_frame(callback, coords, iteration)
+        })?;
+        Ok(self.progress())
+    }
+
+    /// Current coordinates (n × 2, row-major) as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn coords(&self) -> js_sys::Float32Array {
+        coords_array(&self.coords)
+    }
+
+    /// Fraction of iterations completed, in [0, 1].
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> f64 {
+        if self.n_iterations == 0 || self.error.is_some() {
+            1.0
+        } else {
+            self.iteration as f64 / self.n_iterations as f64
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_complete(&self) -> bool {
+        self.error.is_some() || self.iteration >= self.n_iterations
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Error message for invalid input.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Start a t-SNE layout of a feature matrix (Euclidean distances).
+///
+/// # Arguments
+/// * `data` - Row-major matrix (n_samples × n_features)
+/// * `perplexity` - Effective neighbor count (5–50; capped at (n-1)/3)
+/// * `theta` - Barnes–Hut accuracy (0.5 typical, 0 = exact)
+/// * `n_iterations` - Total iterations (1000 typical)
+/// * `seed` - Seed for the initial layout
+///
+/// # Returns
+/// `TsneEmbedding`; call `step` until `is_complete`.
+#[wasm_bindgen]
+pub fn tsne_from_features(
+    data: &[f32],
+    n_samples: usize,
+    n_features: usize,
+    perplexity: f64,
+    theta: f64,
+    n_iterations: usize,
+    seed: u64,
+) -> TsneEmbedding {
+    TsneEmbedding::new(
+        PointSet::from_features(data, n_samples, n_features),
+        perplexity,
+        theta,
+        n_iterations,
+        seed,
+    )
+}
+
+/// Start a t-SNE layout from a condensed distance matrix.
+///
+/// Arguments as for `tsne_from_features`.
+#[wasm_bindgen]
+pub fn tsne_from_distances(
+    condensed: &[f32],
+    perplexity: f64,
+    theta: f64,
+    n_iterations: usize,
+    seed: u64,
+) -> TsneEmbedding {
+    TsneEmbedding::new(
+        PointSet::from_condensed(condensed),
+        perplexity,
+        theta,
+        n_iterations,
+        seed,
+    )
+}
+
+#[cfg(test)]
+mod tsne_tests {
+    use super::*;
+
+    /// Three Gaussian blobs in 8-D, `per` points each.
+    fn blobs(per: usize, seed: u64) -> Vec<f32> {
+        let mut rng = SplitMix64::new(seed);
+        let mut data = Vec::new();
+        for c in 0..3 {
+            for _ in 0..per {
+                for d in 0..8 {
+                    let center = if d == c { 20.0 } else { 0.0 };
+                    data.push((center + rng.gaussian()) as f32);
+                }
+            }
+        }
+        data
+    }
+
+    /// Fraction of points whose nearest 2-D neighbor is in the same blob.
+    fn neighbor_purity(coords: &[f64], per: usize) -> f64 {
+        let n = coords.len() / 2;
+        let same = (0..n)
+            .filter(|&i| {
+                let nearest = (0..n)
+                    .filter(|&j| j != i)
+                    .min_by(|&a, &b| {
+                        let da = (coords[2 * i] - coords[2 * a]).powi(2)
+                            + (coords[2 * i + 1] - coords[2 * a + 1]).powi(2);
+                        let db = (coords[2 * i] - coords[2 * b]).powi(2)
+                            + (coords[2 * i + 1] - coords[2 * b + 1]).powi(2);
+                        da.total_cmp(&db)
+                    })
+                    .unwrap();
+                nearest / per == i / per
+            })
+            .count();
+        same as f64 / n as f64
+    }
+
+    #[test]
+    fn test_separates_clusters_and_is_deterministic() {
+        let data = blobs(30, 1);
+        let mut a = tsne_from_features(&data, 90, 8, 15.0, 0.5, 400, 7);
+        assert!(a.error.is_none());
+        assert!((a.step(100) - 0.25).abs() < 1e-12);
+        a.step(usize::MAX);
+        assert!(a.is_complete());
+        assert!(neighbor_purity(&a.coords, 30) > 0.95);
+
+        let mut b = tsne_from_features(&data, 90, 8, 15.0, 0.5, 400, 7);
+        b.step(400);
+        assert_eq!(a.coords, b.coords);
+
+        // Frames every 150 iterations plus the last one; same layout as `step`.
+        let mut c = tsne_from_features(&data, 90, 8, 15.0, 0.5, 400, 7);
+        let mut frames = Vec::new();
+        c.step_frames(usize::MAX, 150, |coords, iteration| {
+            frames.push((iteration, coords.to_vec()));
+            Ok::<(), ()>(())
+        })
+        .unwrap();
+        let iterations: Vec<usize> = frames.iter().map(|f| f.0).collect();
+        assert_eq!(iterations, vec![150, 300, 400]);
+        assert_eq!(frames[2].1, a.coords);
+    }
+
+    #[test]
+    fn test_from_distances_and_exact_gradient() {
+        let data = blobs(20, 2);
+        let points = PointSet::from_features(&data, 60, 8).unwrap();
+        let mut condensed = Vec::new();
+        for i in 0..60 {
+            for j in (i + 1)..60 {
+                condensed.push(points.distance(i, j) as f32);
+            }
+        }
+        // theta = 0 walks every leaf, i.e. exact t-SNE gradients.
+        let mut embedding = tsne_from_distances(&condensed, 10.0, 0.0, 1000, 3);
+        embedding.step(1000);
+        assert!(neighbor_purity(&embedding.coords, 20) > 0.95);
+        assert!(tsne_from_distances(&condensed[1..], 10.0, 0.5, 10, 1)
+            .error
+            .is_some());
+    }
+}
@@ -0,0 +1,524 @@
+//! UMAP (McInnes, Healy & Melville 2018) in two dimensions.
+//!
+//! 1. kNN graph (exact) with per-point `rho` (nearest-neighbor distance) and
+//!    `sigma` calibrated so the membership weights sum to log2(k).
+//! 2. Fuzzy union of the directed graphs: w = a + b - a·b.
+//! 3. SGD layout with the low-dimensional kernel 1 / (1 + a·d^(2b)), edges
+//!    sampled in proportion to their weight and 5 negative samples per
+//!    positive one, learning rate decaying linearly to zero.
+//!
+//! The curve parameters `a`, `b` are fitted to `min_dist` (spread 1) as in
+//! the reference implementation. The layout starts from a seeded uniform
+//! random initialization and is advanced one epoch batch at a time with
+//! `step`, or with `step_with_callback`, which hands every N-th frame to a
+//! JS callback.
+
+use wasm_bindgen::prelude::*;
+
+use crate::embedding::{coords_array, emit_frame, PointSet};
+use crate::rng::SplitMix64;
+
+const NEGATIVE_SAMPLE_RATE: f64 = 5.0;
+const GRADIENT_CLIP: f64 = 4.0;
+
+/// Fit `1 / (1 + a·x^(2b))` to the UMAP target curve for `min_dist`
+/// (spread 1) by damped Gauss–Newton least squares.
+pub(crate) fn fit_ab(min_dist: f64) -> (f64, f64) {
+    let xs: Vec<f64> = (1..300).map(|i| 3.0 * i as f64 / 299.0).collect();
+    let ys: Vec<f64> = xs
+        .iter()
+        .map(|&x| {
+            if x < min_dist {
+                1.0
+            } else {
+                (-(x - min_dist)).exp()
+            }
+        })
+        .collect();
+    let sse = |a: f64, b: f64| -> f64 {
+        xs.iter()
+            .zip(ys.iter())
+            .map(|(&x, &y)| (1.0 / (1.0 + a * x.powf(2.0 * b)) - y).powi(2))
+            .sum()
+    };
+    let (mut a, mut b, mut lambda) = (1.0, 1.0, 1e-3);
+    let mut err = sse(a, b);
+    for _ in 0..200 {
+        // Normal equations JᵀJ·δ = -Jᵀr for the 2 parameters.
+        let (mut jaa, mut jab, mut jbb, mut ga, mut gb) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for (&x, &y) in xs.iter().zip(ys.iter()) {
+            let p = x.powf(2.0 * b);
+            let denom = 1.0 + a * p;
+            let r = 1.0 / denom - y;
+            let da = -p / (denom * denom);
+            let db = -a * p * 2.0 * x.ln() / (denom * denom);
+            jaa += da * da;
+            jab += da * db;
+            jbb += db * db;
+            ga += da * r;
+            gb += db * r;
+        }
+        let (m11, m22) = (jaa * (1.0 + lambda), jbb * (1.0 + lambda));
+        let det = m11 * m22 - jab * jab;
+        if det.abs() < f64::MIN_POSITIVE {
+            break;
+        }
+        let delta_a = -(m22 * ga - jab * gb) / det;
+        let delta_b = -(m11 * gb - jab * ga) / det;
+        let (na, nb) = (a + delta_a, b + delta_b);
+        let new_err = if na > 0.0 && nb > 0.0 {
+            sse(na, nb)
+        } else {
+            f64::INFINITY
+        };
+        if new_err < err {
+            let converged = err - new_err < 1e-14 * err.max(1e-300);
+            (a, b, err) = (na, nb, new_err);
+            lambda *= 0.3;
+            if converged {
+                break;
+            }
+        } else {
+            lambda *= 10.0;
+        }
+    }
+    (a, b)
+}
+
+/// Fuzzy simplicial set: symmetric weighted edges (both directions).
+fn fuzzy_graph(neighbors: &[Vec<(usize, f64)>]) -> Vec<(usize, usize, f64)> {
+    let mut directed: Vec<(usize, usize, f64)> = Vec::new();
+    for (i, row) in neighbors.iter().enumerate() {
+        if row.is_empty() {
+            continue;
+        }
+        let target = (row.len() as f64).log2();
+        let rho = row
+            .iter()
+            .map(|&(_, d)| d)
+            .find(|&d| d > 0.0)
+            .unwrap_or(0.0);
+        let mean_d = row.iter().map(|&(_, d)| d).sum::<f64>() / row.len() as f64;
+        let membership = |sigma: f64| -> f64 {
+            row.iter()
+                .map(|&(_, d)| (-(d - rho).max(0.0) / sigma).exp())
+                .sum()
+        };
+        let (mut lo, mut hi, mut sigma) = (0.0, f64::INFINITY, 1.0);
+        for _ in 0..64 {
+            let psum = membership(sigma);
+            if (psum - target).abs() < 1e-5 {
+                break;
+            }
+            if psum > target {
+                hi = sigma;
+                sigma = 0.5 * (lo + hi);
+            } else {
+                lo = sigma;
+                sigma = if hi.is_finite() {
+                    0.5 * (lo + hi)
+                } else {
+                    sigma * 2.0
+                };
+            }
+        }
+        let sigma = sigma.max(1e-3 * mean_d).max(f64::MIN_POSITIVE);
+        for &(j, d) in row {
+            directed.push((i, j, (-(d - rho).max(0.0) / sigma).exp()));
+        }
+    }
+
+    // w_ij ∪ w_ji = a + b - ab, over the union of both edge directions.
+    let mut keyed: Vec<((usize, usize), f64)> = directed
+        .iter()
+        .map(|&(i, j, w)| ((i.min(j), i.max(j)), w))
+        .collect();
+    keyed.sort_unstable_by_key(|e| e.0);
+    let mut edges = Vec::with_capacity(keyed.len());
+    let mut idx = 0;
+    while idx < keyed.len() {
+        let (key, mut w) = keyed[idx];
+        idx += 1;
+        while idx < keyed.len() && keyed[idx].0 == key {
+            let other = keyed[idx].1;
+            w = w + other - w * other;
+            idx += 1;
+        }
+        edges.push((key.0, key.1, w));
+        edges.push((key.1, key.0, w));
+    }
+    edges
+}
+
+/// UMAP layout, advanced with `step`.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct UmapEmbedding {
+    n: usize,
+    coords: Vec<f64>,
+    edges: Vec<(usize, usize)>,
+    epochs_per_sample: Vec<f64>,
+    next_sample: Vec<f64>,
+    next_negative: Vec<f64>,
+    a: f64,
+    b: f64,
+    epoch: usize,
+    n_epochs: usize,
+    rng: SplitMix64,
+    error: Option<String>,
+}
+
+impl UmapEmbedding {
+    fn failed(error: String) -> UmapEmbedding {
+        UmapEmbedding {
+            n: 0,
+            coords: Vec::new(),
+            edges: Vec::new(),
+            epochs_per_sample: Vec::new(),
+            next_sample: Vec::new(),
+            next_negative: Vec::new(),
+            a: 0.0,
+            b: 0.0,
+            epoch: 0,
+            n_epochs: 0,
+            rng: SplitMix64::new(0),
+            error: Some(error),
+        }
+    }
+
+    fn new(
+        points: Result<PointSet, String>,
+        n_neighbors: usize,
+        min_dist: f64,
+        n_epochs: usize,
+        seed: u64,
+    ) -> UmapEmbedding {
+        let points = match points {
+            Ok(points) => points,
+            Err(error) => return UmapEmbedding::failed(error),
+        };
+        if n_neighbors < 2 {
+            return UmapEmbedding::failed("n_neighbors must be at least 2".to_string());
+        }
+        if !(0.0..3.0).contains(&min_dist) {
+            return UmapEmbedding::failed("min_dist must be in [0, 3)".to_string());
+        }
+        let n = points.len();
+        let n_epochs = if n_epochs > 0 {
+            n_epochs
+        } else if n <= 10_000 {
+            500
+        } else {
+            200
+        };
+        let graph = fuzzy_graph(&points.knn(n_neighbors));
+        let max_w = graph.iter().map(|e| e.2).fold(0.0, f64::max);
+        // Edges too weak to be sampled even once are dropped.
+        let kept: Vec<(usize, usize, f64)> = graph
+            .into_iter()
+            .filter(|e| e.2 >= max_w / n_epochs as f64)
+            .collect();
+        let epochs_per_sample: Vec<f64> = kept.iter().map(|e| max_w / e.2).collect();
+
+        let mut rng = SplitMix64::new(seed);
+        let coords = (0..2 * n).map(|_| 20.0 * rng.next_f64() - 10.0).collect();
+        let (a, b) = fit_ab(min_dist);
+        UmapEmbedding {
+            n,
+            coords,
+            edges: kept.iter().map(|e| (e.0, e.1)).collect(),
+            next_sample: epochs_per_sample.clone(),
+            next_negative: epochs_per_sample
+                .iter()
+                .map(|e| e / NEGATIVE_SAMPLE_RATE)
+                .collect(),
+            epochs_per_sample,
+            a,
+            b,
+            epoch: 0,
+            n_epochs,
+            rng,
+            error: None,
+        }
+    }
+
+    fn run_epoch(&mut self) {
+        let (a, b) = (self.a, self.b);
+        let e = self.epoch as f64;
+        let alpha = 1.0 - e / self.n_epochs as f64;
+        let clip = |g: f64| g.clamp(-GRADIENT_CLIP, GRADIENT_CLIP);
+        for k in 0..self.edges.len() {
+            if self.next_sample[k] > e {
+                continue;
+            }
+            let (i, j) = self.edges[k];
+            let dx = self.coords[2 * i] - self.coords[2 * j];
+            let dy = self.coords[2 * i + 1] - self.coords[2 * j + 1];
+            let d2 = dx * dx + dy * dy;
+            if d2 > 0.0 {
+                let coeff = -2.0 * a * b * d2.powf(b - 1.0) / (1.0 + a * d2.powf(b));
+                let (gx, gy) = (clip(coeff * dx) * alpha, clip(coeff * dy) * alpha);
+                self.coords[2 * i] += gx;
+                self.coords[2 * i + 1] += gy;
+                self.coords[2 * j] -= gx;
+                self.coords[2 * j + 1] -= gy;
+            }
+            self.next_sample[k] += self.epochs_per_sample[k];
+
+            let per_negative = self.epochs_per_sample[k] / NEGATIVE_SAMPLE_RATE;
+            let n_negative = ((e - self.next_negative[k]) / per_negative).max(0.0) as usize;
+            for _ in 0..n_negative {
+                let r = self.rng.below(self.n);
+                if r == i {
+                    continue;
+                }
+                let dx = self.coords[2 * i] - self.coords[2 * r];
+                let dy = self.coords[2 * i + 1] - self.coords[2 * r + 1];
+                let d2 = dx * dx + dy * dy;
+                let (gx, gy) = if d2 > 0.0 {
+                    let coeff = 2.0 * b / ((0.001 + d2) * (1.0 + a * d2.powf(b)));
+                    (clip(coeff * dx), clip(coeff * dy))
+                } else {
+                    (GRADIENT_CLIP, GRADIENT_CLIP)
+                };
+                self.coords[2 * i] += gx * alpha;
+                self.coords[2 * i + 1] += gy * alpha;
+            }
+            self.next_negative[k] += n_negative as f64 * per_negative;
+        }
+        self.epoch += 1;
+    }
+
+    /// Run up to `epochs` more epochs, passing the layout to `frame`
+    /// whenever the epoch count is a multiple of `every` (never if 0) and
+    /// after the last epoch.
+    fn step_frames<E>(
+        &mut self,
+        epochs: usize,
+        every: usize,
+        mut frame: impl FnMut(&[f64], usize) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if self.error.is_some() {
+            return Ok(());
+        }
+        let end = self.epoch.saturating_add(epochs).min(self.n_epochs);
+        while self.epoch < end {
+            self.run_epoch();
+            if self.epoch == end || (every > 0 && self.epoch.is_multiple_of(every)) {
+                frame(&self.coords, self.epoch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl UmapEmbedding {
+    /// Run up to `epochs` more optimization epochs.
+    ///
+    /// # Returns
+    /// Fraction of epochs completed, in [0, 1].
+    pub fn step(&mut self, epochs: usize) -> f64 {
+        if self.error.is_none() {
+            let end = self.epoch.saturating_add(epochs).min(self.n_epochs);
+            while self.epoch < end {
+                self.run_epoch();
+            }
+        }
+        self.progress()
+    }
+
+    /// Run up to `epochs` more epochs and call `callback(coords, epoch)`
+    /// every `every` epochs and after the last one, so an animated layout
+    /// needs a single call. `coords` is a fresh Float32Array as returned by
+    /// the `coords` getter.
+    ///
+    /// # Returns
+    /// Fraction of epochs completed, in [0, 1]. If the callback throws,
+    /// stepping stops and the exception is rethrown.
+    pub fn step_with_callback(
+        &mut self,
+        epochs: usize,
+        every: usize,
+        callback: &js_sys::Function,
+    ) -> Result<f64, JsValue> {
+        self.step_frames(epochs, every, |coords, epoch| {
+            emit_frame(callback, coords, epoch)
+        })?;
+        Ok(self.progress())
+    }
+
+    /// Current coordinates (n × 2, row-major) as Float32Array.
+    #[wasm_bindgen(getter)]
+    pub fn coords(&self) -> js_sys::Float32Array {
+        coords_array(&self.coords)
+    }
+
+    /// Fraction of epochs completed, in [0, 1].
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> f64 {
+        if self.n_epochs == 0 || self.error.is_some() {
+            1.0
+        } else {
+            self.epoch as f64 / self.n_epochs as f64
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_complete(&self) -> bool {
+        self.error.is_some() || self.epoch >= self.n_epochs
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn epoch(&self) -> usize {
+        self.epoch
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n_epochs(&self) -> usize {
+        self.n_epochs
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Number of directed edges in the fuzzy graph.
+    #[wasm_bindgen(getter)]
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Error message for invalid input.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+/// Start a UMAP layout of a feature matrix (Euclidean distances).
+///
+/// # Arguments
+/// * `data` - Row-major matrix (n_samples × n_features)
+/// * `n_neighbors` - Local neighborhood size (15 typical)
+/// * `min_dist` - Minimum spacing of embedded points (0.1 typical)
+/// * `n_epochs` - Total epochs (0 = 500, or 200 above 10,000 points)
+/// * `seed` - Seed for initialization and negative sampling
+///
+/// # Returns
+/// `UmapEmbedding`; call `step` until `is_complete`.
+#[wasm_bindgen]
+pub fn umap_from_features(
+    data: &[f32],
+    n_samples: usize,
+    n_features: usize,
+    n_neighbors: usize,
+    min_dist: f64,
+    n_epochs: usize,
+    seed: u64,
+) -> UmapEmbedding {
+    UmapEmbedding::new(
+        PointSet::from_features(data, n_samples, n_features),
+        n_neighbors,
+        min_dist,
+        n_epochs,
+        seed,
+    )
+}
+
+/// Start a UMAP layout from a condensed distance matrix.
+///
+/// Arguments as for `umap_from_features`.
+#[wasm_bindgen]
+pub fn umap_from_distances(
+    condensed: &[f32],
+    n_neighbors: usize,
+    min_dist: f64,
+    n_epochs: usize,
+    seed: u64,
+) -> UmapEmbedding {
+    UmapEmbedding::new(
+        PointSet::from_condensed(condensed),
+        n_neighbors,
+        min_dist,
+        n_epochs,
+        seed,
+    )
+}
+
+#[cfg(test)]
+mod umap_tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_ab_matches_reference() {
+        // umap-learn: find_ab_params(1.0, 0.1) ≈ (1.577, 0.895).
+        let (a, b) = fit_ab(0.1);
+        assert!((a - 1.577).abs() < 0.02, "a {}", a);
+        assert!((b - 0.895).abs() < 0.01, "b {}", b);
+    }
+
+    #[test]
+    fn test_separates_clusters() {
+        let mut rng = SplitMix64::new(5);
+        let per = 40;
+        let mut data = Vec::new();
+        for c in 0..3 {
+            for _ in 0..per {
+                for d in 0..6 {
+                    let center = if d == c { 15.0 } else { 0.0 };
+                    data.push((center + rng.gaussian()) as f32);
+                }
+            }
+        }
+        let mut embedding = umap_from_features(&data, 3 * per, 6, 10, 0.1, 200, 11);
+        assert!(embedding.error.is_none());
+        assert!((embedding.step(50) - 0.25).abs() < 1e-12);
+        let mut frames = Vec::new();
+        embedding
+            .step_frames(usize::MAX, 60, |_, epoch| {
+                frames.push(epoch);
+                Ok::<(), ()>(())
+            })
+            .unwrap();
+        assert_eq!(frames, vec![60, 120, 180, 200]);
+        assert!(embedding.is_complete());
+
+        // Every point is closer to its own cluster's centroid.
+        let centroid = |c: usize, d: usize| {
+            (c * per..(c + 1) * per)
+                .map(|i| embedding.coords[2 * i + d])
+                .sum::<f64>()
+                / per as f64
+        };
+        let centroids: Vec<(f64, f64)> = (0..3).map(|c| (centroid(c, 0), centroid(c, 1))).collect();
+        for i in 0..3 * per {
+            let (x, y) = (embedding.coords[2 * i], embedding.coords[2 * i + 1]);
+            let nearest = (0..3)
+                .min_by(|&p, &q| {
+                    let dp = (x - centroids[p].0).powi(2) + (y - centroids[p].1).powi(2);
+                    let dq = (x - centroids[q].0).powi(2) + (y - centroids[q].1).powi(2);
+                    dp.total_cmp(&dq)
+                })
+                .unwrap();
+            assert_eq!(nearest, i / per, "point {}", i);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_graph_is_symmetric() {
+        let data: Vec<f32> = (0..20).map(|i| ((i * 7) % 11) as f32).collect();
+        let points = PointSet::from_features(&data, 10, 2).unwrap();
+        let edges = fuzzy_graph(&points.knn(3));
+        for &(i, j, w) in &edges {
+            assert!(w > 0.0 && w <= 1.0);
+            assert!(edges.iter().any(|&(p, q, v)| p == j && q == i && v == w));
+        }
+        assert!(umap_from_distances(&[1.0, 1.0, 1.0], 1, 0.1, 0, 0)
+            .error
+            .is_some());
+    }
+}
@@ -0,0 +1,915 @@
+//! Variant calling from pairwise alignments with VCF 4.3 export.
+//!
+//! Turns the edit masks of a `MyersDiffResult` (or a fresh Myers alignment)
+//! into normalized variant records: SNVs, MNVs, left-aligned indels and
+//! complex replacements. Records can be annotated against CDS features and
+//! serialized as VCF text.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    codon_to_aa, dotplot_complement_upper, myers_diff, normalize_base, MyersDiffResult,
+    DIFF_OP_DELETE, DIFF_OP_INSERT, DIFF_OP_MATCH,
+};
+
+/// Minimum run of matching bases that separates two variant regions.
+/// Closer edits are merged and re-aligned together.
+const VARIANT_ANCHOR_MIN: usize = 8;
+
+/// Largest region (ref_len * alt_len DP cells) that is re-aligned with
+/// substitutions; bigger regions keep the Myers edit layout.
+const VARIANT_REALIGN_MAX_CELLS: usize = 4_000_000;
+
+/// Variant classes produced by the caller.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariantKind {
+    /// Single-nucleotide substitution
+    Snv = 0,
+    /// Run of adjacent substitutions (same length REF/ALT)
+    Mnv = 1,
+    /// Bases present in the query but not the reference
+    Insertion = 2,
+    /// Bases present in the reference but not the query
+    Deletion = 3,
+    /// Replacement with different REF/ALT lengths that is not a pure indel
+    Complex = 4,
+}
+
+/// Predicted consequence of a variant on the CDS it falls in.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariantEffect {
+    /// Outside every supplied CDS
+    Intergenic = 0,
+    /// Codon changes but the amino acid does not
+    Synonymous = 1,
+    /// Amino acid substitution
+    Missense = 2,
+    /// Premature stop codon introduced
+    Nonsense = 3,
+    /// Stop codon replaced by an amino acid
+    StopLost = 4,
+    /// Length change that is not a multiple of 3
+    Frameshift = 5,
+    /// Length change that preserves the reading frame
+    InframeIndel = 6,
+}
+
+impl VariantKind {
+    fn label(self) -> &'static str {
+        match self {
+            VariantKind::Snv => "SNV",
+            VariantKind::Mnv => "MNV",
+            VariantKind::Insertion => "INS",
+            VariantKind::Deletion => "DEL",
+            VariantKind::Complex => "COMPLEX",
+        }
+    }
+}
+
+impl VariantEffect {
+    fn label(self) -> &'static str {
+        match self {
+            VariantEffect::Intergenic => "intergenic",
+            VariantEffect::Synonymous => "synonymous",
+            VariantEffect::Missense => "missense",
+            VariantEffect::Nonsense => "nonsense",
+            VariantEffect::StopLost => "stop_lost",
+            VariantEffect::Frameshift => "frameshift",
+            VariantEffect::InframeIndel => "inframe_indel",
+        }
+    }
+
+    /// Ranking used to pick the reported effect when several CDS overlap.
+    fn severity(self) -> u8 {
+        match self {
+            VariantEffect::Intergenic => 0,
+            VariantEffect::Synonymous => 1,
+            VariantEffect::Missense => 2,
+            VariantEffect::InframeIndel => 3,
+            VariantEffect::StopLost => 4,
+            VariantEffect::Nonsense => 5,
+            VariantEffect::Frameshift => 6,
+        }
+    }
+}
+
+/// A single normalized variant.
+///
+/// `pos`, `ref_allele` and `alt_allele` are already in VCF form: indels carry
+/// their padding base and `pos` is the 0-based reference index of the first
+/// REF base.
+#[derive(Clone, Debug)]
+struct Variant {
+    pos: usize,
+    kind: VariantKind,
+    ref_allele: Vec<u8>,
+    alt_allele: Vec<u8>,
+    effect: VariantEffect,
+    cds_index: i32,
+    aa_change: String,
+}
+
+/// Result of variant calling.
+///
+/// # Ownership
+/// The caller must call `.free()` to release WASM memory.
+#[wasm_bindgen]
+pub struct VariantCallResult {
+    variants: Vec<Variant>,
+    reference_length: usize,
+    error: Option<String>,
+}
+
+#[wasm_bindgen]
+impl VariantCallResult {
+    /// Number of variant records.
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> usize {
+        self.variants.len()
+    }
+
+    /// 1-based VCF POS of each record as Uint32Array.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> js_sys::Uint32Array {
+        let values: Vec<u32> = self.variants.iter().map(|v| (v.pos + 1) as u32).collect();
+        let arr = js_sys::Uint32Array::new_with_length(values.len() as u32);
+        arr.copy_from(&values);
+        arr
+    }
+
+    /// `VariantKind` code of each record as Uint8Array.
+    #[wasm_bindgen(getter)]
+    pub fn kinds(&self) -> js_sys::Uint8Array {
+        let values: Vec<u8> = self.variants.iter().map(|v| v.kind as u8).collect();
+        let arr = js_sys::Uint8Array::new_with_length(values.len() as u32);
+        arr.copy_from(&values);
+        arr
+    }
+
+    /// `VariantEffect` code of each record as Uint8Array.
+    #[wasm_bindgen(getter)]
+    pub fn effects(&self) -> js_sys::Uint8Array {
+        let values: Vec<u8> = self.variants.iter().map(|v| v.effect as u8).collect();
+        let arr = js_sys::Uint8Array::new_with_length(values.len() as u32);
+        arr.copy_from(&values);
+        arr
+    }
+
+    /// Index of the CDS used for each record's effect (-1 if intergenic).
+    #[wasm_bindgen(getter)]
+    pub fn cds_indices(&self) -> js_sys::Int32Array {
+        let values: Vec<i32> = self.variants.iter().map(|v| v.cds_index).collect();
+        let arr = js_sys::Int32Array::new_with_length(values.len() as u32);
+        arr.copy_from(&values);
+        arr
+    }
+
+    /// JSON-encoded array of {pos, kind, ref, alt, effect, cds, aa} records.
+    /// `pos` is 1-based like VCF.
+    #[wasm_bindgen(getter)]
+    pub fn json(&self) -> String {
+        let records: Vec<String> = self
+            .variants
+            .iter()
+            .map(|v| {
+                format!(
+                    "{{\"pos\":{},\"kind\":\"{}\",\"ref\":\"{}\",\"alt\":\"{}\",\"effect\":\"{}\",\"cds\":{},\"aa\":\"{}\"}}",
+                    v.pos + 1,
+                    v.kind.label(),
+                    allele_str(&v.ref_allele),
+                    allele_str(&v.alt_allele),
+                    v.effect.label(),
+                    v.cds_index,
+                    v.aa_change
+                )
+            })
+            .collect();
+        format!("[{}]", records.join(","))
+    }
+
+    /// Error message if the alignment could not be computed, or if exactly
+    /// one sequence is empty (a whole-sequence indel has no VCF anchor base).
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+
+    /// Serialize the records as VCF 4.3 text.
+    ///
+    /// # Arguments
+    /// * `chrom` - CHROM column value (reference accession or name)
+    /// * `sample` - Sample column name for the query. If empty, the FORMAT and
+    ///   sample columns are omitted (sites-only VCF).
+    pub fn to_vcf(&self, chrom: &str, sample: &str) -> String {
+        let chrom = if chrom.is_empty() { "reference" } else { chrom };
+        let mut out = String::new();
+        out.push_str("##fileformat=VCFv4.3\n");
+        out.push_str("##source=phage_explorer-wasm-compute\n");
+        out.push_str(&format!(
+            "##contig=<ID={},length={}>\n",
+            chrom, self.reference_length
+        ));
+        out.push_str("##INFO=<ID=TYPE,Number=1,Type=String,Description=\"Variant type (SNV, MNV, INS, DEL, COMPLEX)\">\n");
+        out.push_str("##INFO=<ID=EFFECT,Number=1,Type=String,Description=\"Predicted coding consequence\">\n");
+        out.push_str("##INFO=<ID=CDS,Number=1,Type=Integer,Description=\"Index of the CDS feature used for EFFECT\">\n");
+        out.push_str("##INFO=<ID=AACHANGE,Number=1,Type=String,Description=\"Amino acid change (ref, codon number, alt)\">\n");
+        if !sample.is_empty() {
+            out.push_str("##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">\n");
+            out.push_str("#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\t");
+            out.push_str(sample);
+            out.push('\n');
+        } else {
+            out.push_str("#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n");
+        }
+
+        for v in &self.variants {
+            let mut info = format!("TYPE={};EFFECT={}", v.kind.label(), v.effect.label());
+            if v.cds_index >= 0 {
+                info.push_str(&format!(";CDS={}", v.cds_index));
+            }
+            if !v.aa_change.is_empty() {
+                info.push_str(&format!(";AACHANGE={}", v.aa_change));
+            }
+            out.push_str(&format!(
+                "{}\t{}\t.\t{}\t{}\t.\tPASS\t{}",
+                chrom,
+                v.pos + 1,
+                allele_str(&v.ref_allele),
+                allele_str(&v.alt_allele),
+                info
+            ));
+            if !sample.is_empty() {
+                out.push_str("\tGT\t1");
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+/// Call variants between a reference and a query genome.
+///
+/// Aligns the two sequences with `myers_diff` (same guardrails) and then
+/// normalizes and annotates the differences, see `call_variants_from_diff`.
+///
+/// # Arguments
+/// * `reference` - Reference sequence bytes (ASCII)
+/// * `query` - Query sequence bytes (ASCII)
+/// * `cds_starts` - 0-based CDS start coordinates on the reference
+/// * `cds_ends` - 0-based exclusive CDS end coordinates
+/// * `cds_strands` - Strand per CDS: 1 (forward) or -1 (reverse)
+///
+/// # Ownership
+/// Caller must call `.free()` on the result.
+#[wasm_bindgen]
+pub fn call_variants(
+    reference: &[u8],
+    query: &[u8],
+    cds_starts: &[u32],
+    cds_ends: &[u32],
+    cds_strands: &[i8],
+) -> VariantCallResult {
+    let diff = myers_diff(reference, query);
+    call_variants_from_diff(reference, query, &diff, cds_starts, cds_ends, cds_strands)
+}
+
+/// Call variants from an existing alignment.
+///
+/// Walks the masks of `diff` (from `myers_diff`, `myers_diff_with_limit` or
+/// `equal_len_diff`) and groups the edits between matching anchors into
+/// variant records:
+/// - Equal-length replacements are split into SNVs and MNVs (runs of adjacent
+///   substitutions).
+/// - Pure insertions/deletions are left-aligned against the reference and
+///   given a VCF padding base.
+/// - Anything else is reported as a COMPLEX record after trimming the shared
+///   prefix and suffix.
+///
+/// Bases are compared case-insensitively with U treated as T. Ambiguity codes
+/// are written as N in the alleles.
+///
+/// CDS features are given as parallel arrays (see `call_variants`). Each
+/// record reports the most severe effect over all CDS it touches; codons that
+/// contain N translate to X.
+///
+/// # Ownership
+/// Caller must call `.free()` on the result.
+#[wasm_bindgen]
+pub fn call_variants_from_diff(
+    reference: &[u8],
+    query: &[u8],
+    diff: &MyersDiffResult,
+    cds_starts: &[u32],
+    cds_ends: &[u32],
+    cds_strands: &[i8],
+) -> VariantCallResult {
+    if let Some(err) = &diff.error {
+        return VariantCallResult {
+            variants: Vec::new(),
+            reference_length: reference.len(),
+            error: Some(err.clone()),
+        };
+    }
+    if diff.mask_a.len() != reference.len() || diff.mask_b.len() != query.len() {
+        return VariantCallResult {
+            variants: Vec::new(),
+            reference_length: reference.len(),
+            error: Some(format!(
+                "Diff masks do not match sequences: mask_a={}, reference={}, mask_b={}, query={}",
+                diff.mask_a.len(),
+                reference.len(),
+                diff.mask_b.len(),
+                query.len()
+            )),
+        };
+    }
+    if cds_starts.len() != cds_ends.len() || cds_starts.len() != cds_strands.len() {
+        return VariantCallResult {
+            variants: Vec::new(),
+            reference_length: reference.len(),
+            error: Some("CDS arrays must have equal length".to_string()),
+        };
+    }
+
+    if reference.is_empty() != query.is_empty() {
+        // The only difference would be the whole sequence, which VCF cannot
+        // anchor: there is no reference base before or after the event.
+        return VariantCallResult {
+            variants: Vec::new(),
+            reference_length: reference.len(),
+            error: Some(format!(
+                "Cannot represent variants against an empty sequence: reference={}, query={}",
+                reference.len(),
+                query.len()
+            )),
+        };
+    }
+
+    let reference: Vec<u8> = reference.iter().map(|&b| vcf_base(b)).collect();
+    let query: Vec<u8> = query.iter().map(|&b| vcf_base(b)).collect();
+
+    let (n, m) = (reference.len(), query.len());
+
+    // Collect maximal edit regions [ref_start, ref_end) x [alt_start, alt_end)
+    // between paired matches.
+    let mut regions: Vec<(usize, usize, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0usize, 0usize);
+    let (mut block_i, mut block_j) = (0usize, 0usize);
+    loop {
+        if i < n && diff.mask_a[i] == DIFF_OP_DELETE {
+            i += 1;
+        } else if j < m && diff.mask_b[j] == DIFF_OP_INSERT {
+            j += 1;
+        } else if i < n && j < m {
+            // Paired position: a match closes the block, a mismatch extends it.
+            if diff.mask_a[i] == DIFF_OP_MATCH && diff.mask_b[j] == DIFF_OP_MATCH {
+                if block_i < i || block_j < j {
+                    regions.push((block_i, i, block_j, j));
+                }
+                block_i = i + 1;
+                block_j = j + 1;
+            }
+            i += 1;
+            j += 1;
+        } else {
+            // Leftover bases on one side are unpaired edits.
+            i = n;
+            j = m;
+            if block_i < i || block_j < j {
+                regions.push((block_i, i, block_j, j));
+            }
+            break;
+        }
+    }
+
+    // Myers only emits insertions and deletions, so a substitution may surface
+    // as an INS and a DEL a few bases apart. Merge regions that are not
+    // separated by a solid anchor and re-align them with substitutions allowed.
+    let mut merged: Vec<(usize, usize, usize, usize)> = Vec::new();
+    for region in regions {
+        match merged.last_mut() {
+            Some(last) if region.0 - last.1 < VARIANT_ANCHOR_MIN => {
+                last.1 = region.1;
+                last.3 = region.3;
+            }
+            _ => merged.push(region),
+        }
+    }
+
+    let mut events: Vec<Event> = Vec::new();
+    for (rs, re, qs, qe) in merged {
+        realign_region(&reference, &query, rs, re, qs, qe, &mut events);
+    }
+
+    let cds: Vec<(usize, usize, i8)> = cds_starts
+        .iter()
+        .zip(cds_ends.iter())
+        .zip(cds_strands.iter())
+        .map(|((&s, &e), &strand)| (s as usize, (e as usize).min(n), strand))
+        .collect();
+
+    let mut variants: Vec<Variant> = events
+        .into_iter()
+        .filter_map(|event| finish_event(&reference, event, &cds))
+        .collect();
+    variants.sort_by(|a, b| {
+        a.pos
+            .cmp(&b.pos)
+            .then(a.ref_allele.len().cmp(&b.ref_allele.len()))
+    });
+
+    VariantCallResult {
+        variants,
+        reference_length: n,
+        error: None,
+    }
+}
+
+/// Raw difference between reference `[start, start + ref_seq.len())` and `alt_seq`.
+struct Event {
+    start: usize,
+    ref_seq: Vec<u8>,
+    alt_seq: Vec<u8>,
+}
+
+/// Uppercase, U->T, and collapse anything outside ACGT to N.
+#[inline(always)]
+fn vcf_base(b: u8) -> u8 {
+    match normalize_base(b) {
+        base @ (b'A' | b'C' | b'G' | b'T') => base,
+        _ => b'N',
+    }
+}
+
+fn allele_str(allele: &[u8]) -> &str {
+    // Alleles only ever contain ACGTN.
+    std::str::from_utf8(allele).unwrap_or("N")
+}
+
+/// Re-align one edit region with unit-cost edit distance (a substitution is
+/// cheaper than an insertion plus a deletion) and emit its events.
+fn realign_region(
+    reference: &[u8],
+    query: &[u8],
+    rs: usize,
+    re: usize,
+    qs: usize,
+    qe: usize,
+    events: &mut Vec<Event>,
+) {
+    let (rl, ql) = (re - rs, qe - qs);
+    if rl == 0 || ql == 0 || rl * ql > VARIANT_REALIGN_MAX_CELLS {
+        split_block(reference, query, rs, re, qs, qe, events);
+        return;
+    }
+
+    let r = &reference[rs..re];
+    let q = &query[qs..qe];
+    let width = ql + 1;
+    let mut dp = vec![0u32; (rl + 1) * width];
+    for (x, cell) in dp.iter_mut().take(width).enumerate() {
+        *cell = x as u32;
+    }
+    for a in 1..=rl {
+        dp[a * width] = a as u32;
+        for b in 1..=ql {
+            let sub = dp[(a - 1) * width + b - 1] + u32::from(r[a - 1] != q[b - 1]);
+            let del = dp[(a - 1) * width + b] + 1;
+            let ins = dp[a * width + b - 1] + 1;
+            dp[a * width + b] = sub.min(del).min(ins);
+        }
+    }
+
+    // Traceback into (ref_index, alt_index, is_match) columns, preferring
+    // diagonal moves so substitutions win ties.
+    let mut cols: Vec<(usize, usize, bool)> = Vec::with_capacity(rl + ql);
+    let (mut a, mut b) = (rl, ql);
+    while a > 0 || b > 0 {
+        let here = dp[a * width + b];
+        if a > 0 && b > 0 && here == dp[(a - 1) * width + b - 1] + u32::from(r[a - 1] != q[b - 1]) {
+            a -= 1;
+            b -= 1;
+            cols.push((a, b, r[a] == q[b]));
+        } else if a > 0 && here == dp[(a - 1) * width + b] + 1 {
+            a -= 1;
+            cols.push((a, b, false));
+        } else {
+            b -= 1;
+            cols.push((a, b, false));
+        }
+    }
+    cols.reverse();
+
+    // Group consecutive non-match columns into blocks.
+    let mut block: Option<(usize, usize)> = None;
+    for (ca, cb, is_match) in cols {
+        if is_match {
+            if let Some((ba, bb)) = block.take() {
+                split_block(reference, query, rs + ba, rs + ca, qs + bb, qs + cb, events);
+            }
+        } else if block.is_none() {
+            block = Some((ca, cb));
+        }
+    }
+    if let Some((ba, bb)) = block {
+        split_block(reference, query, rs + ba, re, qs + bb, qe, events);
+    }
+}
+
+/// Turn the edits between two anchors into trimmed events.
+fn split_block(
+    reference: &[u8],
+    query: &[u8],
+    ref_start: usize,
+    ref_end: usize,
+    alt_start: usize,
+    alt_end: usize,
+    events: &mut Vec<Event>,
+) {
+    let mut rs = ref_start;
+    let mut re = ref_end;
+    let mut qs = alt_start;
+    let mut qe = alt_end;
+
+    // Trim shared prefix/suffix so the event is minimal.
+    while rs < re && qs < qe && reference[rs] == query[qs] {
+        rs += 1;
+        qs += 1;
+    }
+    while rs < re && qs < qe && reference[re - 1] == query[qe - 1] {
+        re -= 1;
+        qe -= 1;
+    }
+    if rs == re && qs == qe {
+        return;
+    }
+
+    if re - rs == qe - qs {
+        // Same length: emit one event per run of differing positions.
+        let mut k = 0;
+        let len = re - rs;
+        while k < len {
+            if reference[rs + k] == query[qs + k] {
+                k += 1;
+                continue;
+            }
+            let run_start = k;
+            while k < len && reference[rs + k] != query[qs + k] {
+                k += 1;
+            }
+            events.push(Event {
+                start: rs + run_start,
+                ref_seq: reference[rs + run_start..rs + k].to_vec(),
+                alt_seq: query[qs + run_start..qs + k].to_vec(),
+            });
+        }
+        return;
+    }
+
+    events.push(Event {
+        start: rs,
+        ref_seq: reference[rs..re].to_vec(),
+        alt_seq: query[qs..qe].to_vec(),
+    });
+}
+
+/// Normalize an event into VCF form and annotate it.
+fn finish_event(reference: &[u8], event: Event, cds: &[(usize, usize, i8)]) -> Option<Variant> {
+    let Event {
+        mut start,
+        mut ref_seq,
+        mut alt_seq,
+    } = event;
+
+    let kind = if ref_seq.is_empty() {
+        VariantKind::Insertion
+    } else if alt_seq.is_empty() {
+        VariantKind::Deletion
+    } else if ref_seq.len() == alt_seq.len() {
+        if ref_seq.len() == 1 {
+            VariantKind::Snv
+        } else {
+            VariantKind::Mnv
+        }
+    } else {
+        VariantKind::Complex
+    };
+
+    // Left-align pure indels against the reference.
+    match kind {
+        VariantKind::Deletion => {
+            let len = ref_seq.len();
+            while start > 0 && reference[start - 1] == reference[start + len - 1] {
+                start -= 1;
+            }
+            ref_seq = reference[start..start + len].to_vec();
+        }
+        VariantKind::Insertion => {
+            while start > 0 && alt_seq.last() == Some(&reference[start - 1]) {
+                alt_seq.rotate_right(1);
+                start -= 1;
+            }
+        }
+        _ => {}
+    }
+
+    let (effect, cds_index, aa_change) = annotate(reference, start, &ref_seq, &alt_seq, kind, cds);
+
+    // VCF padding: indels need a shared base, taken from the left when possible.
+    let (pos, ref_allele, alt_allele) = match kind {
+        VariantKind::Insertion | VariantKind::Deletion => {
+            if start > 0 {
+                let pad = reference[start - 1];
+                let mut r = vec![pad];
+                r.extend_from_slice(&ref_seq);
+                let mut a = vec![pad];
+                a.extend_from_slice(&alt_seq);
+                (start - 1, r, a)
+            } else if start + ref_seq.len() < reference.len() {
+                let pad = reference[start + ref_seq.len()];
+                let mut r = ref_seq.clone();
+                r.push(pad);
+                let mut a = alt_seq.clone();
+                a.push(pad);
+                (start, r, a)
+            } else {
+                // Whole reference deleted, or insertion into an empty reference.
+                // `call_variants_from_diff` rejects both up front.
+                return None;
+            }
+        }
+        _ => (start, ref_seq, alt_seq),
+    };
+
+    Some(Variant {
+        pos,
+        kind,
+        ref_allele,
+        alt_allele,
+        effect,
+        cds_index,
+        aa_change,
+    })
+}
+
+/// Pick the most severe effect across all overlapping CDS.
+fn annotate(
+    reference: &[u8],
+    start: usize,
+    ref_seq: &[u8],
+    alt_seq: &[u8],
+    kind: VariantKind,
+    cds: &[(usize, usize, i8)],
+) -> (VariantEffect, i32, String) {
+    let mut best = (VariantEffect::Intergenic, -1i32, String::new());
+
+    for (idx, &(cds_start, cds_end, strand)) in cds.iter().enumerate() {
+        if cds_start >= cds_end {
+            continue;
+        }
+        let overlaps = match kind {
+            // Insertion sits between start-1 and start; only count it inside the CDS.
+            VariantKind::Insertion => start > cds_start && start < cds_end,
+            _ => start < cds_end && start + ref_seq.len() > cds_start,
+        };
+        if !overlaps {
+            continue;
+        }
+
+        let (effect, aa_change) = match kind {
+            VariantKind::Snv | VariantKind::Mnv => {
+                substitution_effect(reference, start, alt_seq, cds_start, cds_end, strand)
+            }
+            _ => {
+                let delta = ref_seq.len().abs_diff(alt_seq.len());
+                if delta.is_multiple_of(3) {
+                    (VariantEffect::InframeIndel, String::new())
+                } else {
+                    (VariantEffect::Frameshift, String::new())
+                }
+            }
+        };
+
+        if best.1 < 0 || effect.severity() > best.0.severity() {
+            best = (effect, idx as i32, aa_change);
+        }
+    }
+
+    best
+}
+
+/// Translate the codons touched by a same-length substitution.
+fn substitution_effect(
+    reference: &[u8],
+    start: usize,
+    alt_seq: &[u8],
+    cds_start: usize,
+    cds_end: usize,
+    strand: i8,
+) -> (VariantEffect, String) {
+    let end = start + alt_seq.len();
+    let lo = start.max(cds_start);
+    let hi = end.min(cds_end);
+    let full_codons = (cds_end - cds_start) / 3;
+
+    // Codon index (0-based along the CDS) for each reference position.
+    let codon_of = |p: usize| -> usize {
+        if strand < 0 {
+            (cds_end - 1 - p) / 3
+        } else {
+            (p - cds_start) / 3
+        }
+    };
+    let (first, last) = {
+        let a = codon_of(lo);
+        let b = codon_of(hi - 1);
+        (a.min(b), a.max(b))
+    };
+
+    let base_at = |p: usize, use_alt: bool| -> u8 {
+        if use_alt && p >= start && p < end {
+            alt_seq[p - start]
+        } else {
+            reference[p]
+        }
+    };
+
+    let mut ref_aas = Vec::new();
+    let mut alt_aas = Vec::new();
+    for codon in first..=last.min(full_codons.saturating_sub(1)) {
+        let codon_bases = |use_alt: bool| -> [u8; 3] {
+            if strand < 0 {
+                let top = cds_end - 3 * codon;
+                [
+                    dotplot_complement_upper(base_at(top - 1, use_alt)),
+                    dotplot_complement_upper(base_at(top - 2, use_alt)),
+                    dotplot_complement_upper(base_at(top - 3, use_alt)),
+                ]
+            } else {
+                let p = cds_start + 3 * codon;
+                [
+                    base_at(p, use_alt),
+                    base_at(p + 1, use_alt),
+                    base_at(p + 2, use_alt),
+                ]
+            }
+        };
+        let r = codon_bases(false);
+        let a = codon_bases(true);
+        ref_aas.push(codon_to_aa(r[0], r[1], r[2]));
+        alt_aas.push(codon_to_aa(a[0], a[1], a[2]));
+    }
+
+    if ref_aas.is_empty() {
+        // Only touches a trailing partial codon.
+        return (VariantEffect::Synonymous, String::new());
+    }
+
+    let effect = if ref_aas == alt_aas {
+        VariantEffect::Synonymous
+    } else if alt_aas
+        .iter()
+        .zip(ref_aas.iter())
+        .any(|(&a, &r)| a == b'*' && r != b'*')
+    {
+        VariantEffect::Nonsense
+    } else if ref_aas
+        .iter()
+        .zip(alt_aas.iter())
+        .any(|(&r, &a)| r == b'*' && a != b'*')
+    {
+        VariantEffect::StopLost
+    } else {
+        VariantEffect::Missense
+    };
+
+    let aa_change = format!(
+        "{}{}{}",
+        allele_str(&ref_aas),
+        first + 1,
+        allele_str(&alt_aas)
+    );
+    (effect, aa_change)
+}
+
+#[cfg(test)]
+mod variant_tests {
+    use super::*;
+
+    fn call(reference: &[u8], query: &[u8]) -> VariantCallResult {
+        call_variants(reference, query, &[], &[], &[])
+    }
+
+    #[test]
+    fn test_identical_sequences_have_no_variants() {
+        let result = call(b"ACGTACGT", b"acgtacgt");
+        assert!(result.error.is_none());
+        assert_eq!(result.variants.len(), 0);
+    }
+
+    #[test]
+    fn test_snv_and_mnv() {
+        let result = call(b"AAACCCGGGTTT", b"AAAGCCGGATTT");
+        assert_eq!(result.variants.len(), 2);
+        assert_eq!(result.variants[0].kind, VariantKind::Snv);
+        assert_eq!(result.variants[0].pos, 3);
+        assert_eq!(result.variants[0].ref_allele, b"C");
+        assert_eq!(result.variants[0].alt_allele, b"G");
+
+        let adjacent = call(b"AAACCCGGGTTT", b"AAACTTGGGTTT");
+        assert_eq!(adjacent.variants.len(), 1);
+        assert_eq!(adjacent.variants[0].kind, VariantKind::Mnv);
+        assert_eq!(adjacent.variants[0].ref_allele, b"CC");
+        assert_eq!(adjacent.variants[0].alt_allele, b"TT");
+    }
+
+    #[test]
+    fn test_deletion_is_left_aligned_with_padding() {
+        // Deleting one A from the homopolymer must be reported at its left end.
+        let result = call(b"GCAAAATG", b"GCAAATG");
+        assert_eq!(result.variants.len(), 1);
+        let v = &result.variants[0];
+        assert_eq!(v.kind, VariantKind::Deletion);
+        assert_eq!(v.pos, 1);
+        assert_eq!(v.ref_allele, b"CA");
+        assert_eq!(v.alt_allele, b"C");
+    }
+
+    #[test]
+    fn test_insertion_is_left_aligned_with_padding() {
+        let result = call(b"GCATATG", b"GCATATATG");
+        assert_eq!(result.variants.len(), 1);
+        let v = &result.variants[0];
+        assert_eq!(v.kind, VariantKind::Insertion);
+        assert_eq!(v.pos, 1);
+        assert_eq!(v.ref_allele, b"C");
+        assert_eq!(v.alt_allele, b"CAT");
+    }
+
+    #[test]
+    fn test_coding_effects_forward_strand() {
+        // CDS: ATG AAA TGG TAA
+        let reference = b"ATGAAATGGTAA";
+        let cds = ([0u32], [12u32], [1i8]);
+
+        let syn = call_variants(reference, b"ATGAAGTGGTAA", &cds.0, &cds.1, &cds.2);
+        assert_eq!(syn.variants[0].effect, VariantEffect::Synonymous);
+        assert_eq!(syn.variants[0].aa_change, "K2K");
+
+        let mis = call_variants(reference, b"ATGAACTGGTAA", &cds.0, &cds.1, &cds.2);
+        assert_eq!(mis.variants[0].effect, VariantEffect::Missense);
+        assert_eq!(mis.variants[0].aa_change, "K2N");
+        assert_eq!(mis.variants[0].cds_index, 0);
+
+        let stop = call_variants(reference, b"ATGAAATGATAA", &cds.0, &cds.1, &cds.2);
+        assert_eq!(stop.variants[0].effect, VariantEffect::Nonsense);
+
+        let fs = call_variants(reference, b"ATGAAAATGGTAA", &cds.0, &cds.1, &cds.2);
+        assert_eq!(fs.variants[0].effect, VariantEffect::Frameshift);
+    }
+
+    #[test]
+    fn test_coding_effect_reverse_strand() {
+        // Reverse strand CDS: revcomp(TTACCATTTCAT) = ATG AAA TGG TAA
+        let reference = b"TTACCATTTCAT";
+        // T->G at index 6 changes the AAA codon to AAC on the coding strand.
+        let result = call_variants(reference, b"TTACCAGTTCAT", &[0], &[12], &[-1]);
+        assert_eq!(result.variants.len(), 1);
+        assert_eq!(result.variants[0].effect, VariantEffect::Missense);
+        assert_eq!(result.variants[0].aa_change, "K2N");
+    }
+
+    #[test]
+    fn test_vcf_export() {
+        let result = call(b"GCAAAATG", b"GCAAATG");
+        let vcf = result.to_vcf("phageA", "phageB");
+        assert!(vcf.starts_with("##fileformat=VCFv4.3\n"));
+        assert!(vcf.contains("##contig=<ID=phageA,length=8>"));
+        assert!(vcf.contains("#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tphageB\n"));
+        assert!(vcf.contains("phageA\t2\t.\tCA\tC\t.\tPASS\tTYPE=DEL;EFFECT=intergenic\tGT\t1\n"));
+
+        let reference = b"ATGAAATGGTAA";
+        let mis = call_variants(reference, b"ATGAACTGGTAA", &[0], &[12], &[1]);
+        let vcf = mis.to_vcf("phageA", "");
+        assert!(vcf.contains("##INFO=<ID=AACHANGE,"));
+        assert!(!vcf.contains("ID=AA,"));
+        assert!(vcf.contains("TYPE=SNV;EFFECT=missense;CDS=0;AACHANGE=K2N\n"));
+    }
+
+    #[test]
+    fn test_empty_sequence_is_reported() {
+        let deleted = call(b"ACGT", b"");
+        assert!(deleted.error.is_some());
+        assert!(deleted.variants.is_empty());
+
+        let inserted = call(b"", b"ACGT");
+        assert!(inserted.error.is_some());
+
+        let both = call(b"", b"");
+        assert!(both.error.is_none());
+        assert!(both.variants.is_empty());
+    }
+}